@@ -1,34 +1,985 @@
 #include "patches/rocksdb.h"
 
+#include <atomic>
+#include <cassert>
+#include <cstdarg>
+#include <cstdio>
+#include <cstring>
+#include <map>
+#include <string>
+#include <unordered_map>
+#include <vector>
+
+#include "cache/cache_entry_roles.h"
+#include "options/cf_options.h"
+#include "options/db_options.h"
+#include "options/options_parser.h"
+#include "rocksdb/cache.h"
+#include "rocksdb/compaction_filter.h"
+#include "rocksdb/comparator.h"
+#include "rocksdb/convenience.h"
+#include "rocksdb/db.h"
+#include "rocksdb/env.h"
+#include "rocksdb/file_system.h"
+#include "rocksdb/listener.h"
+#include "rocksdb/merge_operator.h"
+#include "rocksdb/rate_limiter.h"
+#include "rocksdb/slice_transform.h"
+#include "rocksdb/sst_file_manager.h"
+#include "rocksdb/system_clock.h"
+#include "rocksdb/table.h"
+#include "rocksdb/utilities/options_type.h"
 #include "rocksdb/utilities/options_util.h"
+#include "rocksdb/utilities/transaction_db.h"
+#include "rocksdb/write_batch.h"
+#include "rocksdb/write_buffer_manager.h"
 
+using rocksdb::BlockBasedTableOptions;
 using rocksdb::Cache;
+using rocksdb::CacheEntryRole;
 using rocksdb::ColumnFamilyDescriptor;
+using rocksdb::ColumnFamilyHandle;
+using rocksdb::ColumnFamilyOptions;
+using rocksdb::Comparator;
+using rocksdb::ConfigOptions;
+using rocksdb::BackgroundErrorReason;
+using rocksdb::CompactionJobInfo;
+using rocksdb::DB;
+using rocksdb::DBOptions;
+using rocksdb::EventListener;
+using rocksdb::FSDirectory;
+using rocksdb::FSRandomAccessFile;
+using rocksdb::FSSequentialFile;
+using rocksdb::FSWritableFile;
+using rocksdb::FileLock;
+using rocksdb::FileOptions;
+using rocksdb::FileSystem;
+using rocksdb::FlushJobInfo;
+using rocksdb::MemTableInfo;
 using rocksdb::Env;
+using rocksdb::EnvWrapper;
+using rocksdb::GetMergeOperandsOptions;
+using rocksdb::IODebugContext;
+using rocksdb::IOOptions;
+using rocksdb::IOStatus;
+using rocksdb::InfoLogLevel;
+using rocksdb::Iterator;
+using rocksdb::LRUCacheOptions;
+using rocksdb::Logger;
 using rocksdb::Options;
+using rocksdb::PinnableSlice;
+using rocksdb::RateLimiter;
+using rocksdb::ReadOptions;
+using rocksdb::Slice;
+using rocksdb::SliceParts;
+using rocksdb::SliceTransform;
+using rocksdb::SstFileManager;
 using rocksdb::Status;
+using rocksdb::SystemClock;
+using rocksdb::SystemClockWrapper;
+using rocksdb::TableFactory;
+using rocksdb::TableFileCreationInfo;
+using rocksdb::TableFileDeletionInfo;
+using rocksdb::Transaction;
+using rocksdb::TransactionDB;
+using rocksdb::TransactionDBOptions;
+using rocksdb::TransactionOptions;
+using rocksdb::TxnDBWritePolicy;
+using rocksdb::WriteBatch;
+using rocksdb::WriteBufferManager;
+using rocksdb::WriteStallInfo;
+using rocksdb::WriteOptions;
+
+static bool SaveError(char** errptr, const Status& s) {
+    assert(errptr != nullptr);
+    if (s.ok()) {
+        return false;
+    } else if (*errptr == nullptr) {
+        *errptr = strdup(s.ToString().c_str());
+    } else {
+        free(*errptr);
+        *errptr = strdup(s.ToString().c_str());
+    }
+    return true;
+}
+
+static char* CopyString(const Slice& str) {
+    char* result = reinterpret_cast<char*>(malloc(sizeof(char) * str.size()));
+    memcpy(result, str.data(), sizeof(char) * str.size());
+    return result;
+}
+
+// Comparator calling back into the bindings, including the key shortening
+// hooks which the comparator of the C API leaves as no-ops.
+class HookedComparator : public Comparator {
+ public:
+    void* state_;
+    void (*destructor_)(void*);
+    int (*compare_)(void*, const char* a, size_t alen, const char* b, size_t blen);
+    const char* (*name_)(void*);
+    char* (*find_shortest_separator_)(
+        void*, const char* start, size_t start_len,
+        const char* limit, size_t limit_len, size_t* result_len);
+    char* (*find_short_successor_)(
+        void*, const char* key, size_t key_len, size_t* result_len);
+
+    ~HookedComparator() override { (*destructor_)(state_); }
+
+    int Compare(const Slice& a, const Slice& b) const override {
+        return (*compare_)(state_, a.data(), a.size(), b.data(), b.size());
+    }
+
+    const char* Name() const override { return (*name_)(state_); }
+
+    void FindShortestSeparator(std::string* start, const Slice& limit) const override {
+        if (find_shortest_separator_ == nullptr) {
+            return;
+        }
+        size_t len = 0;
+        char* result = (*find_shortest_separator_)(
+            state_, start->data(), start->size(), limit.data(), limit.size(), &len);
+        if (result != nullptr) {
+            start->assign(result, len);
+            free(result);
+        }
+    }
+
+    void FindShortSuccessor(std::string* key) const override {
+        if (find_short_successor_ == nullptr) {
+            return;
+        }
+        size_t len = 0;
+        char* result = (*find_short_successor_)(state_, key->data(), key->size(), &len);
+        if (result != nullptr) {
+            key->assign(result, len);
+            free(result);
+        }
+    }
+};
+
+// Orders keys by a fixed-width big-endian integer prefix, then by the
+// remaining bytes. Keys shorter than the prefix sort first.
+template <typename T>
+class BigEndianComparator : public Comparator {
+ public:
+    explicit BigEndianComparator(const char* name) : name_(name) {}
+
+    int Compare(const Slice& a, const Slice& b) const override {
+        bool a_short = a.size() < sizeof(T);
+        bool b_short = b.size() < sizeof(T);
+        if (a_short || b_short) {
+            if (a_short && b_short) {
+                return a.compare(b);
+            }
+            return a_short ? -1 : 1;
+        }
+        T x = Decode(a.data());
+        T y = Decode(b.data());
+        if (x != y) {
+            return x < y ? -1 : 1;
+        }
+        return Slice(a.data() + sizeof(T), a.size() - sizeof(T))
+            .compare(Slice(b.data() + sizeof(T), b.size() - sizeof(T)));
+    }
+
+    const char* Name() const override { return name_; }
+
+    void FindShortestSeparator(std::string*, const Slice&) const override {}
+    void FindShortSuccessor(std::string*) const override {}
+
+ private:
+    static T Decode(const char* data) {
+        uint64_t value = 0;
+        for (size_t i = 0; i < sizeof(T); i++) {
+            value = (value << 8) | static_cast<unsigned char>(data[i]);
+        }
+        return static_cast<T>(value);
+    }
+
+    const char* name_;
+};
+
+// Byte-wise ordering of user keys followed by an 8-byte little-endian
+// timestamp, newer timestamps first.
+class BytewiseWithU64TsComparator : public Comparator {
+ public:
+    BytewiseWithU64TsComparator() : Comparator(sizeof(uint64_t)) {}
+
+    const char* Name() const override { return "leveldb.BytewiseComparator.u64ts"; }
+
+    void FindShortestSeparator(std::string*, const Slice&) const override {}
+    void FindShortSuccessor(std::string*) const override {}
+
+    int Compare(const Slice& a, const Slice& b) const override {
+        int ret = CompareWithoutTimestamp(a, true, b, true);
+        if (ret != 0) {
+            return ret;
+        }
+        return -CompareTimestamp(
+            Slice(a.data() + a.size() - sizeof(uint64_t), sizeof(uint64_t)),
+            Slice(b.data() + b.size() - sizeof(uint64_t), sizeof(uint64_t)));
+    }
+
+    using Comparator::CompareWithoutTimestamp;
+    int CompareWithoutTimestamp(const Slice& a, bool a_has_ts, const Slice& b,
+                                bool b_has_ts) const override {
+        Slice lhs = a_has_ts ? Slice(a.data(), a.size() - sizeof(uint64_t)) : a;
+        Slice rhs = b_has_ts ? Slice(b.data(), b.size() - sizeof(uint64_t)) : b;
+        return lhs.compare(rhs);
+    }
+
+    int CompareTimestamp(const Slice& ts1, const Slice& ts2) const override {
+        uint64_t lhs = Decode(ts1.data());
+        uint64_t rhs = Decode(ts2.data());
+        if (lhs == rhs) {
+            return 0;
+        }
+        return lhs < rhs ? -1 : 1;
+    }
+
+ private:
+    static uint64_t Decode(const char* data) {
+        uint64_t value = 0;
+        for (size_t i = sizeof(uint64_t); i > 0; i--) {
+            value = (value << 8) | static_cast<unsigned char>(data[i - 1]);
+        }
+        return value;
+    }
+};
+
+// Rejects timestamps whose size differs from the one of the column family
+// comparator, which RocksDB only checks in debug builds.
+static Status CheckTimestampSize(ColumnFamilyHandle* cf, size_t ts_size) {
+    size_t expected = cf->GetComparator()->timestamp_size();
+    if (expected != ts_size) {
+        return Status::InvalidArgument(
+            "Timestamp size " + std::to_string(ts_size) +
+            " does not match the comparator timestamp size " + std::to_string(expected));
+    }
+    return Status::OK();
+}
+
+static const Comparator* BuiltinComparator(int comparator) {
+    static BigEndianComparator<uint32_t> big_endian_u32("ckb_rocksdb.BigEndianU32Comparator");
+    static BigEndianComparator<uint64_t> big_endian_u64("ckb_rocksdb.BigEndianU64Comparator");
+    static BigEndianComparator<int32_t> big_endian_i32("ckb_rocksdb.BigEndianI32Comparator");
+    static BigEndianComparator<int64_t> big_endian_i64("ckb_rocksdb.BigEndianI64Comparator");
+    static BytewiseWithU64TsComparator bytewise_with_u64_ts;
+    switch (comparator) {
+        case 1:
+            return rocksdb::ReverseBytewiseComparator();
+        case 2:
+            return &big_endian_u32;
+        case 3:
+            return &big_endian_u64;
+        case 4:
+            return &big_endian_i32;
+        case 5:
+            return &big_endian_i64;
+        case 6:
+            return &bytewise_with_u64_ts;
+        default:
+            return rocksdb::BytewiseComparator();
+    }
+}
+
+// Event listener forwarding to C callbacks, with the info converted to the
+// plain structs of rocksdb.h.
+struct rocksdb_eventlistener_t : public EventListener {
+    void* state_;
+    void (*destructor_)(void*);
+    void (*on_flush_begin_)(void*, const rocksdb_flush_job_info_t*);
+    void (*on_flush_completed_)(void*, const rocksdb_flush_job_info_t*);
+    void (*on_compaction_begin_)(void*, const rocksdb_compaction_job_info_t*);
+    void (*on_compaction_completed_)(void*, const rocksdb_compaction_job_info_t*);
+    void (*on_table_file_created_)(void*, const rocksdb_table_file_creation_info_t*);
+    void (*on_table_file_deleted_)(void*, const rocksdb_table_file_deletion_info_t*);
+    void (*on_background_error_)(void*, int, const char*);
+    void (*on_stall_conditions_changed_)(void*, const rocksdb_write_stall_info_t*);
+    void (*on_memtable_sealed_)(void*, const rocksdb_memtable_info_t*);
+
+    ~rocksdb_eventlistener_t() override { (*destructor_)(state_); }
+
+    static rocksdb_flush_job_info_t ToC(const FlushJobInfo& info) {
+        rocksdb_flush_job_info_t c;
+        c.cf_name = info.cf_name.c_str();
+        c.file_path = info.file_path.c_str();
+        c.file_number = info.file_number;
+        c.thread_id = info.thread_id;
+        c.job_id = info.job_id;
+        c.triggered_writes_slowdown = info.triggered_writes_slowdown;
+        c.triggered_writes_stop = info.triggered_writes_stop;
+        c.smallest_seqno = info.smallest_seqno;
+        c.largest_seqno = info.largest_seqno;
+        c.flush_reason = static_cast<int>(info.flush_reason);
+        return c;
+    }
+
+    static void CompactionCallback(
+        void (*callback)(void*, const rocksdb_compaction_job_info_t*),
+        void* state,
+        const CompactionJobInfo& info) {
+
+        std::string status = info.status.ToString();
+        std::vector<const char*> input_files;
+        for (const std::string& file : info.input_files) {
+            input_files.push_back(file.c_str());
+        }
+        std::vector<const char*> output_files;
+        for (const std::string& file : info.output_files) {
+            output_files.push_back(file.c_str());
+        }
+        rocksdb_compaction_job_info_t c;
+        c.cf_name = info.cf_name.c_str();
+        c.status = info.status.ok() ? nullptr : status.c_str();
+        c.thread_id = info.thread_id;
+        c.job_id = info.job_id;
+        c.base_input_level = info.base_input_level;
+        c.output_level = info.output_level;
+        c.input_files = input_files.data();
+        c.num_input_files = input_files.size();
+        c.output_files = output_files.data();
+        c.num_output_files = output_files.size();
+        c.compaction_reason = static_cast<int>(info.compaction_reason);
+        c.elapsed_micros = info.stats.elapsed_micros;
+        c.num_input_records = info.stats.num_input_records;
+        c.num_output_records = info.stats.num_output_records;
+        c.total_input_bytes = info.stats.total_input_bytes;
+        c.total_output_bytes = info.stats.total_output_bytes;
+        (*callback)(state, &c);
+    }
+
+    void OnFlushBegin(DB*, const FlushJobInfo& info) override {
+        if (on_flush_begin_ != nullptr) {
+            rocksdb_flush_job_info_t c = ToC(info);
+            (*on_flush_begin_)(state_, &c);
+        }
+    }
+
+    void OnFlushCompleted(DB*, const FlushJobInfo& info) override {
+        if (on_flush_completed_ != nullptr) {
+            rocksdb_flush_job_info_t c = ToC(info);
+            (*on_flush_completed_)(state_, &c);
+        }
+    }
+
+    void OnCompactionBegin(DB*, const CompactionJobInfo& info) override {
+        if (on_compaction_begin_ != nullptr) {
+            CompactionCallback(on_compaction_begin_, state_, info);
+        }
+    }
+
+    void OnCompactionCompleted(DB*, const CompactionJobInfo& info) override {
+        if (on_compaction_completed_ != nullptr) {
+            CompactionCallback(on_compaction_completed_, state_, info);
+        }
+    }
+
+    void OnTableFileCreated(const TableFileCreationInfo& info) override {
+        if (on_table_file_created_ != nullptr) {
+            std::string status = info.status.ToString();
+            rocksdb_table_file_creation_info_t c;
+            c.db_name = info.db_name.c_str();
+            c.cf_name = info.cf_name.c_str();
+            c.file_path = info.file_path.c_str();
+            c.job_id = info.job_id;
+            c.reason = static_cast<int>(info.reason);
+            c.file_size = info.file_size;
+            c.status = info.status.ok() ? nullptr : status.c_str();
+            (*on_table_file_created_)(state_, &c);
+        }
+    }
+
+    void OnTableFileDeleted(const TableFileDeletionInfo& info) override {
+        if (on_table_file_deleted_ != nullptr) {
+            std::string status = info.status.ToString();
+            rocksdb_table_file_deletion_info_t c;
+            c.db_name = info.db_name.c_str();
+            c.file_path = info.file_path.c_str();
+            c.job_id = info.job_id;
+            c.status = info.status.ok() ? nullptr : status.c_str();
+            (*on_table_file_deleted_)(state_, &c);
+        }
+    }
+
+    void OnBackgroundError(BackgroundErrorReason reason, Status* bg_error) override {
+        if (on_background_error_ != nullptr) {
+            std::string status = bg_error->ToString();
+            (*on_background_error_)(state_, static_cast<int>(reason), status.c_str());
+        }
+    }
+
+    void OnStallConditionsChanged(const WriteStallInfo& info) override {
+        if (on_stall_conditions_changed_ != nullptr) {
+            rocksdb_write_stall_info_t c;
+            c.cf_name = info.cf_name.c_str();
+            c.cur = static_cast<int>(info.condition.cur);
+            c.prev = static_cast<int>(info.condition.prev);
+            (*on_stall_conditions_changed_)(state_, &c);
+        }
+    }
+
+    void OnMemTableSealed(const MemTableInfo& info) override {
+        if (on_memtable_sealed_ != nullptr) {
+            rocksdb_memtable_info_t c;
+            c.cf_name = info.cf_name.c_str();
+            c.first_seqno = info.first_seqno;
+            c.earliest_seqno = info.earliest_seqno;
+            c.num_entries = info.num_entries;
+            c.num_deletes = info.num_deletes;
+            (*on_memtable_sealed_)(state_, &c);
+        }
+    }
+};
+
+// Info logger handing every formatted line to a C callback instead of
+// writing a LOG file.
+class CallbackLogger : public Logger {
+ public:
+    void* state_;
+    void (*destructor_)(void*);
+    void (*log_)(void*, int level, const char* msg, size_t len);
+
+    explicit CallbackLogger(InfoLogLevel log_level) : Logger(log_level) {}
+
+    ~CallbackLogger() override { (*destructor_)(state_); }
+
+    using Logger::Logv;
+
+    void Logv(const char* format, va_list ap) override {
+        Logv(InfoLogLevel::INFO_LEVEL, format, ap);
+    }
+
+    void LogHeader(const char* format, va_list ap) override {
+        Logv(InfoLogLevel::HEADER_LEVEL, format, ap);
+    }
+
+    void Logv(const InfoLogLevel log_level, const char* format, va_list ap) override {
+        if (log_level < GetInfoLogLevel()) {
+            return;
+        }
+        char buf[512];
+        va_list copy;
+        va_copy(copy, ap);
+        int len = vsnprintf(buf, sizeof(buf), format, copy);
+        va_end(copy);
+        if (len < 0) {
+            return;
+        }
+        if (static_cast<size_t>(len) < sizeof(buf)) {
+            (*log_)(state_, static_cast<int>(log_level), buf, len);
+        } else {
+            std::string msg(len + 1, '\0');
+            vsnprintf(&msg[0], msg.size(), format, ap);
+            (*log_)(state_, static_cast<int>(log_level), msg.data(), len);
+        }
+    }
+};
+
+// Converts the result of a file system callback, taking ownership of the
+// error message.
+static IOStatus CallbackIOStatus(int code, char* errmsg) {
+    std::string msg = errmsg != nullptr ? errmsg : "";
+    free(errmsg);
+    switch (code) {
+        case rocksdb_fs_ok:
+            return IOStatus::OK();
+        case rocksdb_fs_not_found:
+            return IOStatus::PathNotFound(msg);
+        case rocksdb_fs_no_space:
+            return IOStatus::NoSpace(msg);
+        case rocksdb_fs_not_supported:
+            return IOStatus::NotSupported(msg);
+        default:
+            return IOStatus::IOError(msg);
+    }
+}
+
+class CallbackSequentialFile : public FSSequentialFile {
+ public:
+    CallbackSequentialFile(void* file, const rocksdb_filesystem_callbacks_t* cb)
+        : file_(file), cb_(cb) {}
+
+    ~CallbackSequentialFile() override { (*cb_->sequential_destroy)(file_); }
+
+    IOStatus Read(size_t n, const IOOptions&, Slice* result, char* scratch,
+                  IODebugContext*) override {
+        char* errmsg = nullptr;
+        size_t read = 0;
+        int code = (*cb_->sequential_read)(file_, scratch, n, &read, &errmsg);
+        *result = Slice(scratch, code == rocksdb_fs_ok ? read : 0);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus Skip(uint64_t n) override {
+        char* errmsg = nullptr;
+        int code = (*cb_->sequential_skip)(file_, n, &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+ private:
+    void* file_;
+    const rocksdb_filesystem_callbacks_t* cb_;
+};
+
+class CallbackRandomAccessFile : public FSRandomAccessFile {
+ public:
+    CallbackRandomAccessFile(void* file, const rocksdb_filesystem_callbacks_t* cb)
+        : file_(file), cb_(cb) {}
+
+    ~CallbackRandomAccessFile() override { (*cb_->random_access_destroy)(file_); }
+
+    IOStatus Read(uint64_t offset, size_t n, const IOOptions&, Slice* result,
+                  char* scratch, IODebugContext*) const override {
+        char* errmsg = nullptr;
+        size_t read = 0;
+        int code = (*cb_->random_access_read)(file_, offset, scratch, n, &read, &errmsg);
+        *result = Slice(scratch, code == rocksdb_fs_ok ? read : 0);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+ private:
+    void* file_;
+    const rocksdb_filesystem_callbacks_t* cb_;
+};
+
+class CallbackWritableFile : public FSWritableFile {
+ public:
+    CallbackWritableFile(void* file, const rocksdb_filesystem_callbacks_t* cb)
+        : file_(file), cb_(cb) {}
+
+    ~CallbackWritableFile() override { (*cb_->writable_destroy)(file_); }
+
+    using FSWritableFile::Append;
+
+    IOStatus Append(const Slice& data, const IOOptions&, IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_->writable_append)(file_, data.data(), data.size(), &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus Close(const IOOptions&, IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_->writable_close)(file_, &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus Flush(const IOOptions&, IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_->writable_flush)(file_, &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus Sync(const IOOptions&, IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_->writable_sync)(file_, &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+ private:
+    void* file_;
+    const rocksdb_filesystem_callbacks_t* cb_;
+};
+
+class CallbackDirectory : public FSDirectory {
+ public:
+    CallbackDirectory(void* state, const rocksdb_filesystem_callbacks_t* cb,
+                      const std::string& name)
+        : state_(state), cb_(cb), name_(name) {}
+
+    IOStatus Fsync(const IOOptions&, IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_->sync_dir)(state_, name_.c_str(), &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+ private:
+    void* state_;
+    const rocksdb_filesystem_callbacks_t* cb_;
+    std::string name_;
+};
+
+class CallbackFileLock : public FileLock {
+ public:
+    explicit CallbackFileLock(const std::string& fname) : fname_(fname) {}
+
+    std::string fname_;
+};
+
+// File system whose file and directory operations are callbacks. The info
+// log is written through its writable files, while the test directory and
+// absolute paths come from the default file system.
+class CallbackFileSystem : public FileSystem {
+ public:
+    void* state_;
+    void (*destructor_)(void*);
+    std::string name_;
+    rocksdb_filesystem_callbacks_t cb_;
+    Env* env_ = nullptr;
+
+    ~CallbackFileSystem() override { (*destructor_)(state_); }
+
+    const char* Name() const override { return name_.c_str(); }
+
+    IOStatus NewSequentialFile(const std::string& fname, const FileOptions&,
+                               std::unique_ptr<FSSequentialFile>* result,
+                               IODebugContext*) override {
+        char* errmsg = nullptr;
+        void* file = nullptr;
+        int code = (*cb_.new_sequential_file)(state_, fname.c_str(), &file, &errmsg);
+        if (code == rocksdb_fs_ok) {
+            result->reset(new CallbackSequentialFile(file, &cb_));
+        }
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus NewRandomAccessFile(const std::string& fname, const FileOptions&,
+                                 std::unique_ptr<FSRandomAccessFile>* result,
+                                 IODebugContext*) override {
+        char* errmsg = nullptr;
+        void* file = nullptr;
+        int code = (*cb_.new_random_access_file)(state_, fname.c_str(), &file, &errmsg);
+        if (code == rocksdb_fs_ok) {
+            result->reset(new CallbackRandomAccessFile(file, &cb_));
+        }
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus NewWritableFile(const std::string& fname, const FileOptions&,
+                             std::unique_ptr<FSWritableFile>* result,
+                             IODebugContext*) override {
+        char* errmsg = nullptr;
+        void* file = nullptr;
+        int code = (*cb_.new_writable_file)(state_, fname.c_str(), &file, &errmsg);
+        if (code == rocksdb_fs_ok) {
+            result->reset(new CallbackWritableFile(file, &cb_));
+        }
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus ReopenWritableFile(const std::string& fname, const FileOptions&,
+                                std::unique_ptr<FSWritableFile>* result,
+                                IODebugContext*) override {
+        char* errmsg = nullptr;
+        void* file = nullptr;
+        int code = (*cb_.reopen_writable_file)(state_, fname.c_str(), &file, &errmsg);
+        if (code == rocksdb_fs_ok) {
+            result->reset(new CallbackWritableFile(file, &cb_));
+        }
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus NewDirectory(const std::string& name, const IOOptions&,
+                          std::unique_ptr<FSDirectory>* result,
+                          IODebugContext*) override {
+        result->reset(new CallbackDirectory(state_, &cb_, name));
+        return IOStatus::OK();
+    }
+
+    IOStatus FileExists(const std::string& fname, const IOOptions&,
+                        IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.file_exists)(state_, fname.c_str(), &errmsg);
+        if (code == rocksdb_fs_not_found) {
+            free(errmsg);
+            return IOStatus::NotFound();
+        }
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus GetChildren(const std::string& dir, const IOOptions&,
+                         std::vector<std::string>* result,
+                         IODebugContext*) override {
+        char* errmsg = nullptr;
+        char** names = nullptr;
+        size_t num_names = 0;
+        int code = (*cb_.get_children)(state_, dir.c_str(), &names, &num_names, &errmsg);
+        result->clear();
+        for (size_t i = 0; i < num_names; i++) {
+            result->emplace_back(names[i]);
+            free(names[i]);
+        }
+        free(names);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus DeleteFile(const std::string& fname, const IOOptions&,
+                        IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.delete_file)(state_, fname.c_str(), &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus CreateDir(const std::string& dirname, const IOOptions&,
+                       IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.create_dir)(state_, dirname.c_str(), &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus CreateDirIfMissing(const std::string& dirname, const IOOptions&,
+                                IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.create_dir_if_missing)(state_, dirname.c_str(), &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus DeleteDir(const std::string& dirname, const IOOptions&,
+                       IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.delete_dir)(state_, dirname.c_str(), &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus GetFileSize(const std::string& fname, const IOOptions&,
+                         uint64_t* file_size, IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.get_file_size)(state_, fname.c_str(), file_size, &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus GetFileModificationTime(const std::string& fname, const IOOptions&,
+                                     uint64_t* file_mtime, IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.get_file_modification_time)(state_, fname.c_str(), file_mtime, &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus RenameFile(const std::string& src, const std::string& target,
+                        const IOOptions&, IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.rename_file)(state_, src.c_str(), target.c_str(), &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus LinkFile(const std::string& src, const std::string& target,
+                      const IOOptions&, IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.link_file)(state_, src.c_str(), target.c_str(), &errmsg);
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus LockFile(const std::string& fname, const IOOptions&, FileLock** lock,
+                      IODebugContext*) override {
+        char* errmsg = nullptr;
+        int code = (*cb_.lock_file)(state_, fname.c_str(), &errmsg);
+        *lock = code == rocksdb_fs_ok ? new CallbackFileLock(fname) : nullptr;
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus UnlockFile(FileLock* lock, const IOOptions&, IODebugContext*) override {
+        CallbackFileLock* callback_lock = static_cast<CallbackFileLock*>(lock);
+        char* errmsg = nullptr;
+        int code = (*cb_.unlock_file)(state_, callback_lock->fname_.c_str(), &errmsg);
+        delete callback_lock;
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus IsDirectory(const std::string& path, const IOOptions&, bool* is_dir,
+                         IODebugContext*) override {
+        char* errmsg = nullptr;
+        unsigned char result = 0;
+        int code = (*cb_.is_directory)(state_, path.c_str(), &result, &errmsg);
+        *is_dir = result != 0;
+        return CallbackIOStatus(code, errmsg);
+    }
+
+    IOStatus NewLogger(const std::string& fname, const IOOptions&,
+                       std::shared_ptr<Logger>* result, IODebugContext*) override {
+        return rocksdb::status_to_io_status(rocksdb::NewEnvLogger(fname, env_, result));
+    }
+
+    IOStatus GetTestDirectory(const IOOptions& options, std::string* path,
+                              IODebugContext* dbg) override {
+        return FileSystem::Default()->GetTestDirectory(options, path, dbg);
+    }
+
+    IOStatus GetAbsolutePath(const std::string& db_path, const IOOptions& options,
+                             std::string* output_path, IODebugContext* dbg) override {
+        return FileSystem::Default()->GetAbsolutePath(db_path, options, output_path, dbg);
+    }
+};
+
+// Clock standing still at the time it was created, so that tests decide when
+// time passes by advancing it.
+class ManualSystemClock : public SystemClockWrapper {
+ public:
+    explicit ManualSystemClock(const std::shared_ptr<SystemClock>& target)
+        : SystemClockWrapper(target), now_micros_(target->NowMicros()) {}
+
+    static const char* kClassName() { return "ckb_rocksdb.ManualSystemClock"; }
+
+    const char* Name() const override { return kClassName(); }
+
+    uint64_t NowMicros() override { return now_micros_; }
+
+    uint64_t NowNanos() override { return now_micros_ * 1000; }
+
+    Status GetCurrentTime(int64_t* unix_time) override {
+        *unix_time = static_cast<int64_t>(now_micros_ / 1000000);
+        return Status::OK();
+    }
+
+    void Advance(uint64_t micros) { now_micros_ += micros; }
+
+ private:
+    std::atomic<uint64_t> now_micros_;
+};
+
+// Env taking the time from a ManualSystemClock and forwarding everything
+// else to its target.
+class MockClockEnv : public EnvWrapper {
+ public:
+    MockClockEnv(Env* target, const std::shared_ptr<ManualSystemClock>& clock)
+        : EnvWrapper(target) {
+        file_system_ = target->GetFileSystem();
+        system_clock_ = clock;
+    }
+
+    uint64_t NowMicros() override { return system_clock_->NowMicros(); }
+
+    uint64_t NowNanos() override { return system_clock_->NowNanos(); }
+
+    Status GetCurrentTime(int64_t* unix_time) override {
+        return system_clock_->GetCurrentTime(unix_time);
+    }
+};
 
 extern "C" {
     // Copy structs from librocksdb-sys/rocksdb/db/c.cc
     struct rocksdb_cache_t {
         std::shared_ptr<Cache>  rep;
     };
+    struct rocksdb_lru_cache_options_t {
+        LRUCacheOptions rep;
+    };
     struct rocksdb_env_t {
         Env* rep;
         bool is_default;
     };
+    struct rocksdb_block_based_table_options_t {
+        BlockBasedTableOptions rep;
+    };
     struct rocksdb_options_t {
         Options rep;
     };
+    struct rocksdb_t {
+        DB* rep;
+    };
+    struct rocksdb_column_family_handle_t {
+        ColumnFamilyHandle* rep;
+    };
+    struct rocksdb_transactiondb_options_t {
+        TransactionDBOptions rep;
+    };
+    struct rocksdb_transactiondb_t {
+        TransactionDB* rep;
+    };
+    struct rocksdb_transaction_t {
+        Transaction* rep;
+    };
+    struct rocksdb_transaction_options_t {
+        TransactionOptions rep;
+    };
+    struct rocksdb_readoptions_t {
+        ReadOptions rep;
+        Slice upper_bound;
+        Slice lower_bound;
+    };
+    struct rocksdb_writeoptions_t {
+        WriteOptions rep;
+    };
+    struct rocksdb_writebatch_t {
+        WriteBatch rep;
+    };
+    struct rocksdb_ratelimiter_t {
+        std::shared_ptr<RateLimiter> rep;
+    };
+    struct rocksdb_iterator_t {
+        Iterator* rep;
+    };
+    struct rocksdb_sstfilemanager_t {
+        std::shared_ptr<SstFileManager> rep;
+    };
+    struct rocksdb_write_buffer_manager_t {
+        std::shared_ptr<WriteBufferManager> rep;
+    };
+    struct rocksdb_universal_compaction_options_t {
+        rocksdb::CompactionOptionsUniversal* rep;
+    };
+    struct rocksdb_fifo_compaction_options_t {
+        rocksdb::CompactionOptionsFIFO rep;
+    };
+    struct rocksdb_slicetransform_t : public SliceTransform {
+        void* state_;
+        void (*destructor_)(void*);
+        const char* (*name_)(void*);
+        char* (*transform_)(
+            void*,
+            const char* key, size_t length,
+            size_t* dst_length);
+        unsigned char (*in_domain_)(
+            void*,
+            const char* key, size_t length);
+        unsigned char (*in_range_)(
+            void*,
+            const char* key, size_t length);
+
+        ~rocksdb_slicetransform_t() override { (*destructor_)(state_); }
+
+        const char* Name() const override { return (*name_)(state_); }
+
+        Slice Transform(const Slice& src) const override {
+            size_t len;
+            char* dst = (*transform_)(state_, src.data(), src.size(), &len);
+            return Slice(dst, len);
+        }
+
+        bool InDomain(const Slice& src) const override {
+            return (*in_domain_)(state_, src.data(), src.size());
+        }
+
+        bool InRange(const Slice& src) const override {
+            return (*in_range_)(state_, src.data(), src.size());
+        }
+    };
+
+    // Same as the Wrapper of c.cc, which is not exported.
+    struct SliceTransformWrapper : public rocksdb_slicetransform_t {
+        const SliceTransform* rep_;
+        ~SliceTransformWrapper() override { delete rep_; }
+        const char* Name() const override { return rep_->Name(); }
+        Slice Transform(const Slice& src) const override {
+            return rep_->Transform(src);
+        }
+        bool InDomain(const Slice& src) const override {
+            return rep_->InDomain(src);
+        }
+        bool InRange(const Slice& src) const override { return rep_->InRange(src); }
+        static void DoNothing(void*) { }
+    };
 
     // New structs
     struct rocksdb_column_family_descriptor_t {
         char *name;
         Options options;
+        // Options recorded by name only, which could not be restored, such
+        // as a comparator implemented in Rust.
+        std::unordered_map<std::string, std::string> unresolved;
     };
     struct rocksdb_column_family_descriptors_t {
         std::vector<rocksdb_column_family_descriptor_t> rep;
     };
+    struct rocksdb_timestamp_t {
+        std::string data;
+        Slice rep;
+    };
+    struct rocksdb_mock_clock_t {
+        std::shared_ptr<ManualSystemClock> rep;
+    };
+    struct rocksdb_options_map_t {
+        std::vector<std::pair<std::string, std::string>> rep;
+    };
 
     rocksdb_cache_t* rocksdb_null_cache() {
         rocksdb_cache_t* c = new rocksdb_cache_t;
@@ -68,46 +1019,1213 @@ extern "C" {
         return options;
     }
 
-    rocksdb_fulloptions_t rocksdb_options_load_from_file(
-        const char* config_file,
-        rocksdb_env_t* env,
+    static rocksdb_fulloptions_t LoadFullOptions(
+        const std::string& file_name,
+        Env* env,
         bool ignore_unknown_options,
-        rocksdb_cache_t* cache,
+        std::shared_ptr<Cache>* cache,
         char** errptr) {
 
         rocksdb_fulloptions_t full_opts;
         full_opts.db_opts = nullptr;
         full_opts.cf_descs = nullptr;
 
+        // Same as rocksdb::LoadOptionsFromFile, keeping the raw option values.
+        ConfigOptions config_options;
+        config_options.ignore_unknown_options = ignore_unknown_options;
+        config_options.input_strings_escaped = true;
+        config_options.env = env;
+        rocksdb::RocksDBOptionsParser parser;
+        Status status = parser.Parse(config_options, file_name, env->GetFileSystem().get());
+        if (SaveError(errptr, status)) {
+            return full_opts;
+        }
+
         rocksdb_options_t* db_opts = new rocksdb_options_t;
-        std::vector<ColumnFamilyDescriptor> cf_descs_tmp;
+        static_cast<DBOptions&>(db_opts->rep) = *parser.db_opt();
+        rocksdb_column_family_descriptors_t* cf_descs = new rocksdb_column_family_descriptors_t;
+        for (size_t i = 0; i < parser.cf_opts()->size(); i++) {
+            rocksdb_column_family_descriptor_t cf_desc;
+            cf_desc.name = strdup((*parser.cf_names())[i].c_str());
+            cf_desc.options = Options(db_opts->rep, (*parser.cf_opts())[i]);
+            if (cache != nullptr) {
+                TableFactory* tf = cf_desc.options.table_factory.get();
+                if (tf != nullptr) {
+                    BlockBasedTableOptions* opts = tf->GetOptions<BlockBasedTableOptions>();
+                    if (opts != nullptr) {
+                        opts->block_cache = *cache;
+                    }
+                }
+            }
 
-        Status status = rocksdb::LoadOptionsFromFile(
-            std::string(config_file),
-            env->rep,
-            &db_opts->rep,
-            &cf_descs_tmp,
-            ignore_unknown_options,
-            &cache->rep);
-        if (status.ok()) {
-            rocksdb_column_family_descriptors_t* cf_descs = new rocksdb_column_family_descriptors_t;
-            full_opts.db_opts = db_opts;
-            int cf_descs_tmp_size = static_cast<int>(cf_descs_tmp.size());
-            for (int i = 0; i < cf_descs_tmp_size; i++) {
-                rocksdb_column_family_descriptor_t cf_desc;
-                cf_desc.name = strdup(cf_descs_tmp[i].name.c_str());
-                cf_desc.options = Options(db_opts->rep, cf_descs_tmp[i].options);
-                cf_descs->rep.push_back(cf_desc);
+            const Options& options = cf_desc.options;
+            const std::unordered_map<std::string, std::string>& opt_map =
+                (*parser.cf_opt_maps())[i];
+            const std::pair<const char*, const char*> loaded[] = {
+                {"comparator", options.comparator->Name()},
+                {"merge_operator",
+                 options.merge_operator ? options.merge_operator->Name() : "nullptr"},
+                {"compaction_filter",
+                 options.compaction_filter ? options.compaction_filter->Name() : "nullptr"},
+            };
+            for (const auto& option : loaded) {
+                auto it = opt_map.find(option.first);
+                if (it != opt_map.end() && it->second != option.second) {
+                    cf_desc.unresolved[option.first] = it->second;
+                }
             }
-            full_opts.cf_descs = cf_descs;
-            return full_opts;
-        } else {
-            delete db_opts;
-        }
-        if (*errptr != nullptr) {
-            free(*errptr);
+            cf_descs->rep.push_back(cf_desc);
         }
-        *errptr = strdup(status.ToString().c_str());
+        full_opts.db_opts = db_opts;
+        full_opts.cf_descs = cf_descs;
         return full_opts;
     }
+
+    rocksdb_fulloptions_t rocksdb_options_load_from_file(
+        const char* config_file,
+        rocksdb_env_t* env,
+        bool ignore_unknown_options,
+        rocksdb_cache_t* cache,
+        char** errptr) {
+
+        return LoadFullOptions(
+            std::string(config_file), env->rep, ignore_unknown_options, &cache->rep, errptr);
+    }
+
+    rocksdb_fulloptions_t rocksdb_options_load_latest(
+        const char* db_path,
+        rocksdb_env_t* env,
+        bool ignore_unknown_options,
+        rocksdb_cache_t* cache,
+        char** errptr) {
+
+        rocksdb_fulloptions_t full_opts;
+        full_opts.db_opts = nullptr;
+        full_opts.cf_descs = nullptr;
+
+        std::string options_file_name;
+        Status status = rocksdb::GetLatestOptionsFileName(
+            std::string(db_path), env->rep, &options_file_name);
+        if (SaveError(errptr, status)) {
+            return full_opts;
+        }
+        full_opts = LoadFullOptions(
+            std::string(db_path) + "/" + options_file_name,
+            env->rep,
+            ignore_unknown_options,
+            cache != nullptr ? &cache->rep : nullptr,
+            errptr);
+        if (full_opts.db_opts != nullptr) {
+            full_opts.db_opts->rep.env = env->rep;
+        }
+        return full_opts;
+    }
+
+    const char* rocksdb_column_family_descriptors_unresolved_option(
+        const rocksdb_column_family_descriptors_t* cf_descs,
+        int index,
+        const char* option_name) {
+
+        const std::unordered_map<std::string, std::string>& unresolved =
+            cf_descs->rep[index].unresolved;
+        auto it = unresolved.find(option_name);
+        return it != unresolved.end() ? it->second.c_str() : nullptr;
+    }
+
+    void rocksdb_options_check_compatibility(
+        const char* db_path,
+        const rocksdb_options_t* db_opts,
+        int num_column_families,
+        const char* const* column_family_names,
+        const rocksdb_options_t* const* column_family_options,
+        bool ignore_unknown_options,
+        char** errptr) {
+
+        // Same as rocksdb::CheckOptionsCompatibility, but the column families
+        // are matched by name and mismatches tell which one failed.
+        Env* env = db_opts->rep.env;
+        std::string options_file_name;
+        Status status = rocksdb::GetLatestOptionsFileName(
+            std::string(db_path), env, &options_file_name);
+        if (SaveError(errptr, status)) {
+            return;
+        }
+        ConfigOptions config_options(db_opts->rep);
+        config_options.sanity_level = ConfigOptions::kSanityLevelLooselyCompatible;
+        config_options.ignore_unknown_options = ignore_unknown_options;
+        config_options.ignore_unsupported_options = true;
+        config_options.input_strings_escaped = true;
+        config_options.invoke_prepare_options = false;
+        config_options.env = env;
+        rocksdb::RocksDBOptionsParser parser;
+        status = parser.Parse(
+            config_options,
+            std::string(db_path) + "/" + options_file_name,
+            env->GetFileSystem().get());
+        if (SaveError(errptr, status)) {
+            return;
+        }
+        status = rocksdb::RocksDBOptionsParser::VerifyDBOptions(
+            config_options, db_opts->rep, *parser.db_opt(), parser.db_opt_map());
+        if (SaveError(errptr, status)) {
+            return;
+        }
+
+        for (size_t i = 0; i < parser.cf_names()->size(); i++) {
+            const std::string& name = (*parser.cf_names())[i];
+            const rocksdb_options_t* cf_opts = nullptr;
+            for (int j = 0; j < num_column_families; j++) {
+                if (name == column_family_names[j]) {
+                    cf_opts = column_family_options[j];
+                    break;
+                }
+            }
+            if (cf_opts == nullptr) {
+                SaveError(errptr, Status::InvalidArgument(
+                    "Column family not specified", name));
+                return;
+            }
+
+            const ColumnFamilyOptions& base_opt = cf_opts->rep;
+            const ColumnFamilyOptions& file_opt = (*parser.cf_opts())[i];
+            status = rocksdb::RocksDBOptionsParser::VerifyCFOptions(
+                config_options, base_opt, file_opt, &(*parser.cf_opt_maps())[i]);
+            if (status.ok()) {
+                status = rocksdb::RocksDBOptionsParser::VerifyTableFactory(
+                    config_options, base_opt.table_factory.get(), file_opt.table_factory.get());
+            }
+            if (status.ok()) {
+                // Objects the file names but which are not registered are
+                // loaded as defaults and pass the check above, and the prefix
+                // extractor is only compared on exact matches, so compare the
+                // persisted names.
+                std::string base_str;
+                std::unordered_map<std::string, std::string> base_map;
+                status = rocksdb::GetStringFromColumnFamilyOptions(
+                    config_options, base_opt, &base_str);
+                if (status.ok()) {
+                    status = rocksdb::StringToMap(base_str, &base_map);
+                }
+                const std::unordered_map<std::string, std::string>& file_map =
+                    (*parser.cf_opt_maps())[i];
+                for (const char* option : {"comparator", "merge_operator", "prefix_extractor"}) {
+                    if (!status.ok()) {
+                        break;
+                    }
+                    auto base_it = base_map.find(option);
+                    auto file_it = file_map.find(option);
+                    std::string base_value = base_it != base_map.end() ? base_it->second : "nullptr";
+                    std::string file_value = file_it != file_map.end() ? file_it->second : "nullptr";
+                    if (base_value != file_value) {
+                        status = Status::InvalidArgument(
+                            "[RocksDBOptionsParser]: failed the verification on "
+                            "ColumnFamilyOptions::" + std::string(option),
+                            "The specified one is " + base_value +
+                                " while the persisted one is " + file_value);
+                    }
+                }
+            }
+            if (!status.ok()) {
+                SaveError(errptr, Status::InvalidArgument(
+                    "Column family " + name, status.ToString()));
+                return;
+            }
+        }
+    }
+
+    char* rocksdb_options_get_options_string(
+        const rocksdb_options_t* options,
+        char** errptr) {
+
+        ConfigOptions config_options(options->rep);
+        std::string db_str;
+        std::string cf_str;
+        Status s = rocksdb::GetStringFromDBOptions(config_options, options->rep, &db_str);
+        if (s.ok()) {
+            s = rocksdb::GetStringFromColumnFamilyOptions(config_options, options->rep, &cf_str);
+        }
+        if (SaveError(errptr, s)) {
+            return nullptr;
+        }
+        return strdup((db_str + cf_str).c_str());
+    }
+
+    static ConfigOptions DetailedConfigOptions(
+        const Options& options,
+        unsigned char mutable_only) {
+
+        ConfigOptions config_options(options);
+        config_options.depth = ConfigOptions::kDepthDetailed;
+        config_options.mutable_options_only = mutable_only;
+        return config_options;
+    }
+
+    char* rocksdb_options_get_db_options_string(
+        const rocksdb_options_t* options,
+        unsigned char mutable_only,
+        char** errptr) {
+
+        std::string result;
+        Status s = rocksdb::GetStringFromDBOptions(
+            DetailedConfigOptions(options->rep, mutable_only), options->rep, &result);
+        if (SaveError(errptr, s)) {
+            return nullptr;
+        }
+        return strdup(result.c_str());
+    }
+
+    char* rocksdb_options_get_cf_options_string(
+        const rocksdb_options_t* options,
+        unsigned char mutable_only,
+        char** errptr) {
+
+        std::string result;
+        Status s = rocksdb::GetStringFromColumnFamilyOptions(
+            DetailedConfigOptions(options->rep, mutable_only), options->rep, &result);
+        if (SaveError(errptr, s)) {
+            return nullptr;
+        }
+        return strdup(result.c_str());
+    }
+
+    // Splits `opts_str` like rocksdb::StringToMap, except that the options of
+    // a value in braces are added under the name of their parent and a dot,
+    // such as "table_factory.block_size", with "{}" as the value of an empty
+    // one.
+    static Status FlattenOptionsString(
+        const std::string& opts_str,
+        const std::string& prefix,
+        std::map<std::string, std::string>* result) {
+
+        auto trim = [](const std::string& str) {
+            size_t start = str.find_first_not_of(" \t");
+            if (start == std::string::npos) {
+                return std::string();
+            }
+            return str.substr(start, str.find_last_not_of(" \t") - start + 1);
+        };
+        std::string opts = trim(opts_str);
+        size_t pos = 0;
+        while (pos < opts.size()) {
+            size_t eq_pos = opts.find_first_of("={};", pos);
+            if (eq_pos == std::string::npos || opts[eq_pos] != '=') {
+                return Status::InvalidArgument("Mismatched key value pair, '=' expected");
+            }
+            std::string name = prefix + trim(opts.substr(pos, eq_pos - pos));
+            if (name.size() == prefix.size()) {
+                return Status::InvalidArgument("Empty key found");
+            }
+            size_t value_pos = opts.find_first_not_of(" \t", eq_pos + 1);
+            bool nested = value_pos != std::string::npos && opts[value_pos] == '{';
+            std::string value;
+            Status s = rocksdb::OptionTypeInfo::NextToken(opts, ';', eq_pos + 1, &pos, &value);
+            if (!s.ok()) {
+                return s;
+            }
+            if (nested && !value.empty()) {
+                s = FlattenOptionsString(value, name + ".", result);
+                if (!s.ok()) {
+                    return s;
+                }
+            } else {
+                (*result)[name] = nested ? "{}" : value;
+            }
+            if (pos == std::string::npos) {
+                break;
+            }
+            pos++;
+        }
+        return Status::OK();
+    }
+
+    rocksdb_options_map_t* rocksdb_options_string_to_map(
+        const char* opts_str,
+        char** errptr) {
+
+        std::map<std::string, std::string> opts_map;
+        if (SaveError(errptr, FlattenOptionsString(std::string(opts_str), "", &opts_map))) {
+            return nullptr;
+        }
+        rocksdb_options_map_t* result = new rocksdb_options_map_t;
+        result->rep.assign(opts_map.begin(), opts_map.end());
+        return result;
+    }
+
+    size_t rocksdb_options_map_count(const rocksdb_options_map_t* map) {
+        return map->rep.size();
+    }
+
+    const char* rocksdb_options_map_name(const rocksdb_options_map_t* map, size_t index) {
+        return map->rep[index].first.c_str();
+    }
+
+    const char* rocksdb_options_map_value(const rocksdb_options_map_t* map, size_t index) {
+        return map->rep[index].second.c_str();
+    }
+
+    void rocksdb_options_map_destroy(rocksdb_options_map_t* map) {
+        delete map;
+    }
+
+    char* rocksdb_block_based_options_get_options_string(
+        const rocksdb_block_based_table_options_t* options,
+        char** errptr) {
+
+        ConfigOptions config_options;
+        std::unique_ptr<TableFactory> factory(rocksdb::NewBlockBasedTableFactory(options->rep));
+        std::string result;
+        if (SaveError(errptr, factory->GetOptionString(config_options, &result))) {
+            return nullptr;
+        }
+        return strdup(result.c_str());
+    }
+
+    void rocksdb_get_block_based_options_from_string(
+        const rocksdb_block_based_table_options_t* base_options,
+        const char* opts_str,
+        rocksdb_block_based_table_options_t* new_options,
+        char** errptr) {
+
+        ConfigOptions config_options;
+        config_options.input_strings_escaped = false;
+        SaveError(errptr, rocksdb::GetBlockBasedTableOptionsFromString(
+            config_options, base_options->rep, std::string(opts_str), &new_options->rep));
+    }
+
+    char* rocksdb_get_db_options_string(
+        rocksdb_t* db,
+        char** errptr) {
+
+        DBOptions options = db->rep->GetDBOptions();
+        ConfigOptions config_options(options);
+        std::string result;
+        Status s = rocksdb::GetStringFromMutableDBOptions(
+            config_options, rocksdb::MutableDBOptions(options), &result);
+        if (SaveError(errptr, s)) {
+            return nullptr;
+        }
+        return strdup(result.c_str());
+    }
+
+    static char* GetMutableCFOptionsString(
+        DB* db,
+        ColumnFamilyHandle* column_family,
+        char** errptr) {
+
+        Options options = db->GetOptions(column_family);
+        ConfigOptions config_options(options);
+        std::string result;
+        Status s = rocksdb::GetStringFromMutableCFOptions(
+            config_options, rocksdb::MutableCFOptions(options), &result);
+        if (SaveError(errptr, s)) {
+            return nullptr;
+        }
+        return strdup(result.c_str());
+    }
+
+    char* rocksdb_get_options_string(
+        rocksdb_t* db,
+        char** errptr) {
+
+        return GetMutableCFOptionsString(db->rep, db->rep->DefaultColumnFamily(), errptr);
+    }
+
+    char* rocksdb_get_options_string_cf(
+        rocksdb_t* db,
+        rocksdb_column_family_handle_t* column_family,
+        char** errptr) {
+
+        return GetMutableCFOptionsString(db->rep, column_family->rep, errptr);
+    }
+
+    unsigned char rocksdb_options_get_dump_malloc_stats(
+        rocksdb_options_t* opt) {
+
+        return opt->rep.dump_malloc_stats;
+    }
+
+    unsigned char rocksdb_options_get_memtable_whole_key_filtering(
+        rocksdb_options_t* opt) {
+
+        return opt->rep.memtable_whole_key_filtering;
+    }
+
+    void rocksdb_options_set_allow_2pc(
+        rocksdb_options_t* opt,
+        unsigned char v) {
+
+        opt->rep.allow_2pc = v;
+    }
+
+    unsigned char rocksdb_options_get_allow_2pc(
+        rocksdb_options_t* opt) {
+
+        return opt->rep.allow_2pc;
+    }
+
+    void rocksdb_options_set_two_write_queues(
+        rocksdb_options_t* opt,
+        unsigned char v) {
+
+        opt->rep.two_write_queues = v;
+    }
+
+    unsigned char rocksdb_options_get_two_write_queues(
+        rocksdb_options_t* opt) {
+
+        return opt->rep.two_write_queues;
+    }
+
+    size_t rocksdb_options_get_compression_per_level(
+        rocksdb_options_t* opt,
+        int* level_values,
+        size_t num_levels) {
+
+        const std::vector<rocksdb::CompressionType>& levels = opt->rep.compression_per_level;
+        for (size_t i = 0; i < levels.size() && i < num_levels; i++) {
+            level_values[i] = static_cast<int>(levels[i]);
+        }
+        return levels.size();
+    }
+
+    void rocksdb_options_get_compression_options(
+        rocksdb_options_t* opt,
+        int* w_bits,
+        int* level,
+        int* strategy,
+        int* max_dict_bytes) {
+
+        const rocksdb::CompressionOptions& opts = opt->rep.compression_opts;
+        *w_bits = opts.window_bits;
+        *level = opts.level;
+        *strategy = opts.strategy;
+        *max_dict_bytes = static_cast<int>(opts.max_dict_bytes);
+    }
+
+    unsigned char rocksdb_options_get_bottommost_compression_options(
+        rocksdb_options_t* opt,
+        int* w_bits,
+        int* level,
+        int* strategy,
+        int* max_dict_bytes,
+        int* zstd_max_train_bytes) {
+
+        const rocksdb::CompressionOptions& opts = opt->rep.bottommost_compression_opts;
+        *w_bits = opts.window_bits;
+        *level = opts.level;
+        *strategy = opts.strategy;
+        *max_dict_bytes = static_cast<int>(opts.max_dict_bytes);
+        *zstd_max_train_bytes = static_cast<int>(opts.zstd_max_train_bytes);
+        return opts.enabled;
+    }
+
+    size_t rocksdb_options_get_max_bytes_for_level_multiplier_additional(
+        rocksdb_options_t* opt,
+        int* level_values,
+        size_t num_levels) {
+
+        const std::vector<int>& levels = opt->rep.max_bytes_for_level_multiplier_additional;
+        for (size_t i = 0; i < levels.size() && i < num_levels; i++) {
+            level_values[i] = levels[i];
+        }
+        return levels.size();
+    }
+
+    const char* rocksdb_options_get_db_log_dir(
+        rocksdb_options_t* opt) {
+
+        return opt->rep.db_log_dir.c_str();
+    }
+
+    const char* rocksdb_options_get_wal_dir(
+        rocksdb_options_t* opt) {
+
+        return opt->rep.wal_dir.c_str();
+    }
+
+    size_t rocksdb_options_get_db_paths_count(
+        rocksdb_options_t* opt) {
+
+        return opt->rep.db_paths.size();
+    }
+
+    const char* rocksdb_options_get_db_path(
+        rocksdb_options_t* opt,
+        size_t index,
+        uint64_t* target_size) {
+
+        const rocksdb::DbPath& path = opt->rep.db_paths[index];
+        *target_size = path.target_size;
+        return path.path.c_str();
+    }
+
+    uint64_t rocksdb_options_get_periodic_compaction_seconds(
+        rocksdb_options_t* opt) {
+
+        return opt->rep.periodic_compaction_seconds;
+    }
+
+    char* rocksdb_options_get_prefix_extractor_name(
+        rocksdb_options_t* opt) {
+
+        if (opt->rep.prefix_extractor == nullptr) {
+            return nullptr;
+        }
+        return strdup(opt->rep.prefix_extractor->Name());
+    }
+
+    rocksdb_universal_compaction_options_t* rocksdb_options_get_universal_compaction_options(
+        rocksdb_options_t* opt) {
+
+        rocksdb_universal_compaction_options_t* result =
+            new rocksdb_universal_compaction_options_t;
+        result->rep = new rocksdb::CompactionOptionsUniversal(
+            opt->rep.compaction_options_universal);
+        return result;
+    }
+
+    rocksdb_fifo_compaction_options_t* rocksdb_options_get_fifo_compaction_options(
+        rocksdb_options_t* opt) {
+
+        rocksdb_fifo_compaction_options_t* result = new rocksdb_fifo_compaction_options_t;
+        result->rep = opt->rep.compaction_options_fifo;
+        return result;
+    }
+
+    rocksdb_t* rocksdb_transactiondb_as_db(
+        rocksdb_transactiondb_t* txn_db) {
+
+        rocksdb_t* result = new rocksdb_t;
+        result->rep = txn_db->rep;
+        return result;
+    }
+
+    void rocksdb_transactiondb_as_db_destroy(
+        rocksdb_t* db) {
+
+        delete db;
+    }
+
+    void rocksdb_transactiondb_options_set_write_policy(
+        rocksdb_transactiondb_options_t* opt,
+        int policy) {
+
+        opt->rep.write_policy = static_cast<TxnDBWritePolicy>(policy);
+    }
+
+    int rocksdb_transactiondb_options_get_write_policy(
+        rocksdb_transactiondb_options_t* opt) {
+
+        return static_cast<int>(opt->rep.write_policy);
+    }
+
+    void rocksdb_transactiondb_options_set_default_write_batch_flush_threshold(
+        rocksdb_transactiondb_options_t* opt,
+        int64_t threshold) {
+
+        opt->rep.default_write_batch_flush_threshold = threshold;
+    }
+
+    int64_t rocksdb_transactiondb_options_get_default_write_batch_flush_threshold(
+        rocksdb_transactiondb_options_t* opt) {
+
+        return opt->rep.default_write_batch_flush_threshold;
+    }
+
+    void rocksdb_transaction_options_set_write_batch_flush_threshold(
+        rocksdb_transaction_options_t* opt,
+        int64_t threshold) {
+
+        opt->rep.write_batch_flush_threshold = threshold;
+    }
+
+    int64_t rocksdb_transaction_options_get_write_batch_flush_threshold(
+        rocksdb_transaction_options_t* opt) {
+
+        return opt->rep.write_batch_flush_threshold;
+    }
+
+    void rocksdb_transaction_set_name(
+        rocksdb_transaction_t* txn,
+        const char* name,
+        size_t name_len,
+        char** errptr) {
+
+        SaveError(errptr, txn->rep->SetName(std::string(name, name_len)));
+    }
+
+    char* rocksdb_transaction_get_name(
+        rocksdb_transaction_t* txn,
+        size_t* name_len) {
+
+        std::string name = txn->rep->GetName();
+        *name_len = name.size();
+        return CopyString(name);
+    }
+
+    void rocksdb_transaction_prepare(
+        rocksdb_transaction_t* txn,
+        char** errptr) {
+
+        SaveError(errptr, txn->rep->Prepare());
+    }
+
+    rocksdb_transaction_t** rocksdb_transactiondb_get_prepared_transactions(
+        rocksdb_transactiondb_t* txn_db,
+        size_t* count) {
+
+        std::vector<Transaction*> txns;
+        txn_db->rep->GetAllPreparedTransactions(&txns);
+        *count = txns.size();
+        if (txns.empty()) {
+            return nullptr;
+        }
+        rocksdb_transaction_t** result = static_cast<rocksdb_transaction_t**>(
+            malloc(sizeof(rocksdb_transaction_t*) * txns.size()));
+        for (size_t i = 0; i < txns.size(); i++) {
+            result[i] = new rocksdb_transaction_t;
+            result[i]->rep = txns[i];
+        }
+        return result;
+    }
+
+    void rocksdb_get_merge_operands_cf(
+        rocksdb_t* db,
+        const rocksdb_readoptions_t* options,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key,
+        size_t keylen,
+        int max_operands,
+        char** values_list,
+        size_t* values_list_sizes,
+        int* num_operands,
+        char** errptr) {
+
+        *num_operands = 0;
+        ColumnFamilyHandle* cf = column_family != nullptr
+            ? column_family->rep
+            : db->rep->DefaultColumnFamily();
+        std::vector<PinnableSlice> operands(max_operands > 0 ? max_operands : 0);
+        GetMergeOperandsOptions merge_operands_options;
+        merge_operands_options.expected_max_number_of_operands = max_operands;
+        int number_of_operands = 0;
+        Status status = db->rep->GetMergeOperands(
+            options->rep,
+            cf,
+            Slice(key, keylen),
+            operands.data(),
+            &merge_operands_options,
+            &number_of_operands);
+        if (status.ok()) {
+            for (int i = 0; i < number_of_operands; i++) {
+                values_list[i] = CopyString(operands[i]);
+                values_list_sizes[i] = operands[i].size();
+            }
+            *num_operands = number_of_operands;
+        } else if (!status.IsNotFound()) {
+            SaveError(errptr, status);
+        }
+    }
+
+    void rocksdb_options_set_comparator_with_hooks(
+        rocksdb_options_t* options,
+        void* state,
+        void (*destructor)(void*),
+        int (*compare)(void*, const char* a, size_t alen, const char* b, size_t blen),
+        const char* (*name)(void*),
+        char* (*find_shortest_separator)(
+            void*, const char* start, size_t start_len,
+            const char* limit, size_t limit_len, size_t* result_len),
+        char* (*find_short_successor)(
+            void*, const char* key, size_t key_len, size_t* result_len)) {
+
+        HookedComparator* comparator = new HookedComparator;
+        comparator->state_ = state;
+        comparator->destructor_ = destructor;
+        comparator->compare_ = compare;
+        comparator->name_ = name;
+        comparator->find_shortest_separator_ = find_shortest_separator;
+        comparator->find_short_successor_ = find_short_successor;
+        options->rep.comparator = comparator;
+    }
+
+    void rocksdb_options_set_builtin_comparator(rocksdb_options_t* options, int comparator) {
+        options->rep.comparator = BuiltinComparator(comparator);
+    }
+
+    void rocksdb_put_with_ts_cf(
+        rocksdb_t* db,
+        const rocksdb_writeoptions_t* options,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        const char* val, size_t vallen,
+        char** errptr) {
+
+        ColumnFamilyHandle* cf = column_family != nullptr
+            ? column_family->rep
+            : db->rep->DefaultColumnFamily();
+        Status status = CheckTimestampSize(cf, tslen);
+        if (status.ok()) {
+            Slice timestamp(ts, tslen);
+            WriteOptions write_options = options->rep;
+            write_options.timestamp = &timestamp;
+            status = db->rep->Put(write_options, cf, Slice(key, keylen), Slice(val, vallen));
+        }
+        SaveError(errptr, status);
+    }
+
+    void rocksdb_delete_with_ts_cf(
+        rocksdb_t* db,
+        const rocksdb_writeoptions_t* options,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        char** errptr) {
+
+        ColumnFamilyHandle* cf = column_family != nullptr
+            ? column_family->rep
+            : db->rep->DefaultColumnFamily();
+        Status status = CheckTimestampSize(cf, tslen);
+        if (status.ok()) {
+            Slice timestamp(ts, tslen);
+            WriteOptions write_options = options->rep;
+            write_options.timestamp = &timestamp;
+            status = db->rep->Delete(write_options, cf, Slice(key, keylen));
+        }
+        SaveError(errptr, status);
+    }
+
+    void rocksdb_writebatch_put_with_ts_cf(
+        rocksdb_writebatch_t* b,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        const char* val, size_t vallen,
+        char** errptr) {
+
+        Status status = CheckTimestampSize(column_family->rep, tslen);
+        if (status.ok()) {
+            Slice key_parts[2] = {Slice(key, keylen), Slice(ts, tslen)};
+            Slice value(val, vallen);
+            status = b->rep.Put(column_family->rep, SliceParts(key_parts, 2), SliceParts(&value, 1));
+        }
+        SaveError(errptr, status);
+    }
+
+    void rocksdb_writebatch_delete_with_ts_cf(
+        rocksdb_writebatch_t* b,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        char** errptr) {
+
+        Status status = CheckTimestampSize(column_family->rep, tslen);
+        if (status.ok()) {
+            Slice key_parts[2] = {Slice(key, keylen), Slice(ts, tslen)};
+            status = b->rep.Delete(column_family->rep, SliceParts(key_parts, 2));
+        }
+        SaveError(errptr, status);
+    }
+
+    size_t rocksdb_default_column_family_timestamp_size(
+        rocksdb_t* db) {
+
+        return db->rep->DefaultColumnFamily()->GetComparator()->timestamp_size();
+    }
+
+    char* rocksdb_get_with_ts_cf(
+        rocksdb_t* db,
+        const rocksdb_readoptions_t* options,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        size_t* vallen,
+        char** found_ts, size_t* found_tslen,
+        char** errptr) {
+
+        *vallen = 0;
+        *found_ts = nullptr;
+        *found_tslen = 0;
+        ColumnFamilyHandle* cf = column_family != nullptr
+            ? column_family->rep
+            : db->rep->DefaultColumnFamily();
+        Status status = CheckTimestampSize(cf, tslen);
+        if (!status.ok()) {
+            SaveError(errptr, status);
+            return nullptr;
+        }
+        Slice timestamp(ts, tslen);
+        ReadOptions read_options = options->rep;
+        read_options.timestamp = &timestamp;
+        std::string value;
+        std::string value_ts;
+        status = db->rep->Get(read_options, cf, Slice(key, keylen), &value, &value_ts);
+        if (status.ok()) {
+            *vallen = value.size();
+            *found_ts = CopyString(value_ts);
+            *found_tslen = value_ts.size();
+            return CopyString(value);
+        }
+        if (!status.IsNotFound()) {
+            SaveError(errptr, status);
+        }
+        return nullptr;
+    }
+
+    rocksdb_timestamp_t* rocksdb_timestamp_create(const char* ts, size_t tslen) {
+        rocksdb_timestamp_t* result = new rocksdb_timestamp_t;
+        result->data.assign(ts, tslen);
+        result->rep = Slice(result->data);
+        return result;
+    }
+
+    void rocksdb_timestamp_destroy(rocksdb_timestamp_t* ts) {
+        delete ts;
+    }
+
+    void rocksdb_readoptions_set_timestamp(
+        rocksdb_readoptions_t* options, const rocksdb_timestamp_t* ts) {
+        options->rep.timestamp = ts != nullptr ? &ts->rep : nullptr;
+    }
+
+    void rocksdb_readoptions_set_iter_start_ts(
+        rocksdb_readoptions_t* options, const rocksdb_timestamp_t* ts) {
+        options->rep.iter_start_ts = ts != nullptr ? &ts->rep : nullptr;
+    }
+
+    const char* rocksdb_iter_timestamp(const rocksdb_iterator_t* iter, size_t* tslen) {
+        Slice ts = iter->rep->timestamp();
+        *tslen = ts.size();
+        return ts.data();
+    }
+
+    rocksdb_slicetransform_t* rocksdb_slicetransform_create_capped_prefix(size_t cap_len) {
+        SliceTransformWrapper* wrapper = new SliceTransformWrapper;
+        wrapper->rep_ = rocksdb::NewCappedPrefixTransform(cap_len);
+        wrapper->state_ = nullptr;
+        wrapper->destructor_ = &SliceTransformWrapper::DoNothing;
+        return wrapper;
+    }
+
+    rocksdb_eventlistener_t* rocksdb_eventlistener_create(
+        void* state,
+        void (*destructor)(void*),
+        void (*on_flush_begin)(void*, const rocksdb_flush_job_info_t*),
+        void (*on_flush_completed)(void*, const rocksdb_flush_job_info_t*),
+        void (*on_compaction_begin)(void*, const rocksdb_compaction_job_info_t*),
+        void (*on_compaction_completed)(void*, const rocksdb_compaction_job_info_t*),
+        void (*on_table_file_created)(void*, const rocksdb_table_file_creation_info_t*),
+        void (*on_table_file_deleted)(void*, const rocksdb_table_file_deletion_info_t*),
+        void (*on_background_error)(void*, int reason, const char* status),
+        void (*on_stall_conditions_changed)(void*, const rocksdb_write_stall_info_t*),
+        void (*on_memtable_sealed)(void*, const rocksdb_memtable_info_t*)) {
+
+        rocksdb_eventlistener_t* listener = new rocksdb_eventlistener_t;
+        listener->state_ = state;
+        listener->destructor_ = destructor;
+        listener->on_flush_begin_ = on_flush_begin;
+        listener->on_flush_completed_ = on_flush_completed;
+        listener->on_compaction_begin_ = on_compaction_begin;
+        listener->on_compaction_completed_ = on_compaction_completed;
+        listener->on_table_file_created_ = on_table_file_created;
+        listener->on_table_file_deleted_ = on_table_file_deleted;
+        listener->on_background_error_ = on_background_error;
+        listener->on_stall_conditions_changed_ = on_stall_conditions_changed;
+        listener->on_memtable_sealed_ = on_memtable_sealed;
+        return listener;
+    }
+
+    void rocksdb_options_add_eventlistener(
+        rocksdb_options_t* options, rocksdb_eventlistener_t* listener) {
+        options->rep.listeners.emplace_back(listener);
+    }
+
+    void rocksdb_options_set_callback_logger(
+        rocksdb_options_t* options,
+        void* state,
+        void (*destructor)(void*),
+        void (*log)(void*, int level, const char* msg, size_t len),
+        int log_level) {
+
+        InfoLogLevel level = static_cast<InfoLogLevel>(log_level);
+        CallbackLogger* logger = new CallbackLogger(level);
+        logger->state_ = state;
+        logger->destructor_ = destructor;
+        logger->log_ = log;
+        options->rep.info_log_level = level;
+        options->rep.info_log.reset(logger);
+    }
+
+    rocksdb_env_t* rocksdb_create_filesystem_env(
+        void* state,
+        void (*destructor)(void*),
+        const char* name,
+        const rocksdb_filesystem_callbacks_t* callbacks) {
+
+        CallbackFileSystem* fs = new CallbackFileSystem;
+        fs->state_ = state;
+        fs->destructor_ = destructor;
+        fs->name_ = name;
+        fs->cb_ = *callbacks;
+        std::shared_ptr<FileSystem> shared_fs(fs);
+        rocksdb_env_t* result = new rocksdb_env_t;
+        result->rep = rocksdb::NewCompositeEnv(shared_fs).release();
+        result->is_default = false;
+        fs->env_ = result->rep;
+        return result;
+    }
+
+    rocksdb_mock_clock_t* rocksdb_mock_clock_create() {
+        rocksdb_mock_clock_t* clock = new rocksdb_mock_clock_t;
+        clock->rep = std::make_shared<ManualSystemClock>(SystemClock::Default());
+        return clock;
+    }
+
+    void rocksdb_mock_clock_destroy(rocksdb_mock_clock_t* clock) {
+        delete clock;
+    }
+
+    void rocksdb_mock_clock_advance(rocksdb_mock_clock_t* clock, uint64_t micros) {
+        clock->rep->Advance(micros);
+    }
+
+    rocksdb_env_t* rocksdb_create_mock_clock_env(rocksdb_mock_clock_t* clock) {
+        rocksdb_env_t* result = new rocksdb_env_t;
+        result->rep = new MockClockEnv(Env::Default(), clock->rep);
+        result->is_default = false;
+        return result;
+    }
+
+    void rocksdb_options_set_periodic_compaction_seconds(
+        rocksdb_options_t* opt,
+        uint64_t seconds) {
+
+        opt->rep.periodic_compaction_seconds = seconds;
+    }
+
+    rocksdb_ratelimiter_t* rocksdb_ratelimiter_create_with_mode(
+        int64_t rate_bytes_per_sec,
+        int64_t refill_period_us,
+        int32_t fairness,
+        int mode,
+        unsigned char auto_tuned) {
+
+        rocksdb_ratelimiter_t* rate_limiter = new rocksdb_ratelimiter_t;
+        rate_limiter->rep.reset(rocksdb::NewGenericRateLimiter(
+            rate_bytes_per_sec, refill_period_us, fairness,
+            static_cast<RateLimiter::Mode>(mode), auto_tuned));
+        return rate_limiter;
+    }
+
+    void rocksdb_ratelimiter_set_bytes_per_second(
+        rocksdb_ratelimiter_t* limiter, int64_t bytes_per_second) {
+        limiter->rep->SetBytesPerSecond(bytes_per_second);
+    }
+
+    int64_t rocksdb_ratelimiter_get_bytes_per_second(rocksdb_ratelimiter_t* limiter) {
+        return limiter->rep->GetBytesPerSecond();
+    }
+
+    int64_t rocksdb_ratelimiter_get_single_burst_bytes(rocksdb_ratelimiter_t* limiter) {
+        return limiter->rep->GetSingleBurstBytes();
+    }
+
+    int64_t rocksdb_ratelimiter_get_total_bytes_through(rocksdb_ratelimiter_t* limiter) {
+        return limiter->rep->GetTotalBytesThrough();
+    }
+
+    int64_t rocksdb_ratelimiter_get_total_requests(rocksdb_ratelimiter_t* limiter) {
+        return limiter->rep->GetTotalRequests();
+    }
+
+    rocksdb_sstfilemanager_t* rocksdb_sstfilemanager_create(
+        rocksdb_env_t* env, char** errptr) {
+
+        Status s;
+        SstFileManager* rep = rocksdb::NewSstFileManager(
+            env->rep, nullptr, "", 0, true, &s);
+        if (SaveError(errptr, s)) {
+            delete rep;
+            return nullptr;
+        }
+        rocksdb_sstfilemanager_t* manager = new rocksdb_sstfilemanager_t;
+        manager->rep.reset(rep);
+        return manager;
+    }
+
+    void rocksdb_sstfilemanager_destroy(rocksdb_sstfilemanager_t* manager) {
+        delete manager;
+    }
+
+    void rocksdb_sstfilemanager_set_max_allowed_space_usage(
+        rocksdb_sstfilemanager_t* manager, uint64_t max_allowed_space) {
+        manager->rep->SetMaxAllowedSpaceUsage(max_allowed_space);
+    }
+
+    void rocksdb_sstfilemanager_set_compaction_buffer_size(
+        rocksdb_sstfilemanager_t* manager, uint64_t compaction_buffer_size) {
+        manager->rep->SetCompactionBufferSize(compaction_buffer_size);
+    }
+
+    unsigned char rocksdb_sstfilemanager_is_max_allowed_space_reached(
+        rocksdb_sstfilemanager_t* manager) {
+        return manager->rep->IsMaxAllowedSpaceReached();
+    }
+
+    unsigned char rocksdb_sstfilemanager_is_max_allowed_space_reached_including_compactions(
+        rocksdb_sstfilemanager_t* manager) {
+        return manager->rep->IsMaxAllowedSpaceReachedIncludingCompactions();
+    }
+
+    uint64_t rocksdb_sstfilemanager_get_total_size(rocksdb_sstfilemanager_t* manager) {
+        return manager->rep->GetTotalSize();
+    }
+
+    int64_t rocksdb_sstfilemanager_get_delete_rate_bytes_per_second(
+        rocksdb_sstfilemanager_t* manager) {
+        return manager->rep->GetDeleteRateBytesPerSecond();
+    }
+
+    void rocksdb_sstfilemanager_set_delete_rate_bytes_per_second(
+        rocksdb_sstfilemanager_t* manager, int64_t delete_rate) {
+        manager->rep->SetDeleteRateBytesPerSecond(delete_rate);
+    }
+
+    double rocksdb_sstfilemanager_get_max_trash_db_ratio(rocksdb_sstfilemanager_t* manager) {
+        return manager->rep->GetMaxTrashDBRatio();
+    }
+
+    void rocksdb_sstfilemanager_set_max_trash_db_ratio(
+        rocksdb_sstfilemanager_t* manager, double ratio) {
+        manager->rep->SetMaxTrashDBRatio(ratio);
+    }
+
+    uint64_t rocksdb_sstfilemanager_get_total_trash_size(rocksdb_sstfilemanager_t* manager) {
+        return manager->rep->GetTotalTrashSize();
+    }
+
+    void rocksdb_options_set_sst_file_manager(
+        rocksdb_options_t* opt, rocksdb_sstfilemanager_t* manager) {
+        opt->rep.sst_file_manager = manager->rep;
+    }
+
+    rocksdb_write_buffer_manager_t* rocksdb_write_buffer_manager_create(
+        size_t buffer_size, unsigned char allow_stall) {
+
+        rocksdb_write_buffer_manager_t* wbm = new rocksdb_write_buffer_manager_t;
+        wbm->rep.reset(new WriteBufferManager(buffer_size, {}, allow_stall));
+        return wbm;
+    }
+
+    rocksdb_write_buffer_manager_t* rocksdb_write_buffer_manager_create_with_cache(
+        size_t buffer_size, const rocksdb_cache_t* cache, unsigned char allow_stall) {
+
+        rocksdb_write_buffer_manager_t* wbm = new rocksdb_write_buffer_manager_t;
+        wbm->rep.reset(new WriteBufferManager(buffer_size, cache->rep, allow_stall));
+        return wbm;
+    }
+
+    void rocksdb_write_buffer_manager_destroy(rocksdb_write_buffer_manager_t* wbm) {
+        delete wbm;
+    }
+
+    unsigned char rocksdb_write_buffer_manager_enabled(rocksdb_write_buffer_manager_t* wbm) {
+        return wbm->rep->enabled();
+    }
+
+    unsigned char rocksdb_write_buffer_manager_cost_to_cache(
+        rocksdb_write_buffer_manager_t* wbm) {
+        return wbm->rep->cost_to_cache();
+    }
+
+    size_t rocksdb_write_buffer_manager_memory_usage(rocksdb_write_buffer_manager_t* wbm) {
+        return wbm->rep->memory_usage();
+    }
+
+    size_t rocksdb_write_buffer_manager_mutable_memtable_memory_usage(
+        rocksdb_write_buffer_manager_t* wbm) {
+        return wbm->rep->mutable_memtable_memory_usage();
+    }
+
+    size_t rocksdb_write_buffer_manager_dummy_entries_in_cache_usage(
+        rocksdb_write_buffer_manager_t* wbm) {
+        return wbm->rep->dummy_entries_in_cache_usage();
+    }
+
+    size_t rocksdb_write_buffer_manager_buffer_size(rocksdb_write_buffer_manager_t* wbm) {
+        return wbm->rep->buffer_size();
+    }
+
+    void rocksdb_write_buffer_manager_set_buffer_size(
+        rocksdb_write_buffer_manager_t* wbm, size_t new_size) {
+        wbm->rep->SetBufferSize(new_size);
+    }
+
+    void rocksdb_options_set_write_buffer_manager(
+        rocksdb_options_t* opt, rocksdb_write_buffer_manager_t* wbm) {
+        opt->rep.write_buffer_manager = wbm->rep;
+    }
+
+    void rocksdb_lru_cache_options_set_num_shard_bits(
+        rocksdb_lru_cache_options_t* opt, int num_shard_bits) {
+        opt->rep.num_shard_bits = num_shard_bits;
+    }
+
+    void rocksdb_lru_cache_options_set_strict_capacity_limit(
+        rocksdb_lru_cache_options_t* opt, unsigned char strict_capacity_limit) {
+        opt->rep.strict_capacity_limit = strict_capacity_limit;
+    }
+
+    void rocksdb_lru_cache_options_set_high_pri_pool_ratio(
+        rocksdb_lru_cache_options_t* opt, double high_pri_pool_ratio) {
+        opt->rep.high_pri_pool_ratio = high_pri_pool_ratio;
+    }
+
+    rocksdb_cache_t* rocksdb_cache_create_clock(
+        size_t capacity, int num_shard_bits, unsigned char strict_capacity_limit) {
+
+        std::shared_ptr<Cache> rep = rocksdb::NewClockCache(
+            capacity, num_shard_bits, strict_capacity_limit);
+        if (!rep) {
+            return nullptr;
+        }
+        rocksdb_cache_t* cache = new rocksdb_cache_t;
+        cache->rep = rep;
+        return cache;
+    }
+
+    void rocksdb_cache_set_strict_capacity_limit(
+        rocksdb_cache_t* cache, unsigned char strict_capacity_limit) {
+        cache->rep->SetStrictCapacityLimit(strict_capacity_limit);
+    }
+
+    unsigned char rocksdb_cache_has_strict_capacity_limit(rocksdb_cache_t* cache) {
+        return cache->rep->HasStrictCapacityLimit();
+    }
+
+    size_t rocksdb_cache_get_entry_stats(
+        rocksdb_cache_t* cache, uint64_t* entries, uint64_t* charges, size_t num_roles) {
+
+        // Same attribution as the "rocksdb.block-cache-entry-stats" property:
+        // entries are recognized by their deleter, unknown ones count as misc.
+        std::unordered_map<Cache::DeleterFn, CacheEntryRole> role_map =
+            rocksdb::CopyCacheDeleterRoleMap();
+        uint64_t role_entries[rocksdb::kNumCacheEntryRoles] = {};
+        uint64_t role_charges[rocksdb::kNumCacheEntryRoles] = {};
+        cache->rep->ApplyToAllEntries(
+            [&](const Slice&, void*, size_t charge, Cache::DeleterFn deleter) {
+                auto it = role_map.find(deleter);
+                size_t role = static_cast<size_t>(
+                    it == role_map.end() ? CacheEntryRole::kMisc : it->second);
+                role_entries[role] += 1;
+                role_charges[role] += charge;
+            },
+            Cache::ApplyToAllEntriesOptions());
+        for (size_t i = 0; i < num_roles && i < rocksdb::kNumCacheEntryRoles; ++i) {
+            entries[i] = role_entries[i];
+            charges[i] = role_charges[i];
+        }
+        return rocksdb::kNumCacheEntryRoles;
+    }
 }
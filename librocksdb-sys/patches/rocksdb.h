@@ -8,11 +8,128 @@ extern "C" {
 
 typedef struct rocksdb_column_family_descriptor_t   rocksdb_column_family_descriptor_t;
 typedef struct rocksdb_column_family_descriptors_t  rocksdb_column_family_descriptors_t;
+typedef struct rocksdb_timestamp_t                 rocksdb_timestamp_t;
+typedef struct rocksdb_mock_clock_t                rocksdb_mock_clock_t;
 typedef struct {
     rocksdb_options_t* db_opts;
     rocksdb_column_family_descriptors_t* cf_descs;
 } rocksdb_fulloptions_t;
 
+/* Event listener. The strings of the info structs are only valid during
+   the callback, a NULL status means success. */
+
+typedef struct rocksdb_eventlistener_t rocksdb_eventlistener_t;
+
+typedef struct {
+    const char* cf_name;
+    const char* file_path;
+    uint64_t file_number;
+    uint64_t thread_id;
+    int job_id;
+    unsigned char triggered_writes_slowdown;
+    unsigned char triggered_writes_stop;
+    uint64_t smallest_seqno;
+    uint64_t largest_seqno;
+    int flush_reason;
+} rocksdb_flush_job_info_t;
+
+typedef struct {
+    const char* cf_name;
+    const char* status;
+    uint64_t thread_id;
+    int job_id;
+    int base_input_level;
+    int output_level;
+    const char* const* input_files;
+    size_t num_input_files;
+    const char* const* output_files;
+    size_t num_output_files;
+    int compaction_reason;
+    uint64_t elapsed_micros;
+    uint64_t num_input_records;
+    uint64_t num_output_records;
+    uint64_t total_input_bytes;
+    uint64_t total_output_bytes;
+} rocksdb_compaction_job_info_t;
+
+typedef struct {
+    const char* db_name;
+    const char* cf_name;
+    const char* file_path;
+    int job_id;
+    int reason;
+    uint64_t file_size;
+    const char* status;
+} rocksdb_table_file_creation_info_t;
+
+typedef struct {
+    const char* db_name;
+    const char* file_path;
+    int job_id;
+    const char* status;
+} rocksdb_table_file_deletion_info_t;
+
+typedef struct {
+    const char* cf_name;
+    int cur;
+    int prev;
+} rocksdb_write_stall_info_t;
+
+typedef struct {
+    const char* cf_name;
+    uint64_t first_seqno;
+    uint64_t earliest_seqno;
+    uint64_t num_entries;
+    uint64_t num_deletes;
+} rocksdb_memtable_info_t;
+
+/* File system implemented through callbacks. Every callback returning an
+   int returns one of the rocksdb_fs_* codes and may set *errmsg to a
+   malloc'd message. Paths are NUL-terminated, and the file callbacks receive
+   the handle returned by the corresponding open callback. */
+
+enum {
+    rocksdb_fs_ok = 0,
+    rocksdb_fs_not_found = 1,
+    rocksdb_fs_no_space = 2,
+    rocksdb_fs_not_supported = 3,
+    rocksdb_fs_io_error = 4
+};
+
+typedef struct {
+    int (*new_sequential_file)(void*, const char* fname, void** file, char** errmsg);
+    int (*new_random_access_file)(void*, const char* fname, void** file, char** errmsg);
+    int (*new_writable_file)(void*, const char* fname, void** file, char** errmsg);
+    int (*reopen_writable_file)(void*, const char* fname, void** file, char** errmsg);
+    int (*file_exists)(void*, const char* fname, char** errmsg);
+    int (*get_children)(void*, const char* dir, char*** names, size_t* num_names, char** errmsg);
+    int (*delete_file)(void*, const char* fname, char** errmsg);
+    int (*create_dir)(void*, const char* dirname, char** errmsg);
+    int (*create_dir_if_missing)(void*, const char* dirname, char** errmsg);
+    int (*delete_dir)(void*, const char* dirname, char** errmsg);
+    int (*get_file_size)(void*, const char* fname, uint64_t* size, char** errmsg);
+    int (*get_file_modification_time)(void*, const char* fname, uint64_t* mtime, char** errmsg);
+    int (*rename_file)(void*, const char* src, const char* target, char** errmsg);
+    int (*link_file)(void*, const char* src, const char* target, char** errmsg);
+    int (*lock_file)(void*, const char* fname, char** errmsg);
+    int (*unlock_file)(void*, const char* fname, char** errmsg);
+    int (*sync_dir)(void*, const char* dirname, char** errmsg);
+    int (*is_directory)(void*, const char* path, unsigned char* is_dir, char** errmsg);
+
+    int (*sequential_read)(void* file, char* buf, size_t n, size_t* read, char** errmsg);
+    int (*sequential_skip)(void* file, uint64_t n, char** errmsg);
+    void (*sequential_destroy)(void* file);
+
+    int (*random_access_read)(void* file, uint64_t offset, char* buf, size_t n, size_t* read, char** errmsg);
+    void (*random_access_destroy)(void* file);
+
+    int (*writable_append)(void* file, const char* data, size_t n, char** errmsg);
+    int (*writable_flush)(void* file, char** errmsg);
+    int (*writable_sync)(void* file, char** errmsg);
+    int (*writable_close)(void* file, char** errmsg);
+    void (*writable_destroy)(void* file);
+} rocksdb_filesystem_callbacks_t;
+
 extern ROCKSDB_LIBRARY_API
     rocksdb_cache_t* rocksdb_null_cache();
 
@@ -42,6 +159,600 @@ extern ROCKSDB_LIBRARY_API
         rocksdb_cache_t* cache,
         char** errptr);
 
+/* Like rocksdb_options_load_from_file, loading the latest OPTIONS file of the
+   database at `db_path`. `cache` may be NULL to keep the block caches of the
+   file, and the env is set on the database options. */
+extern ROCKSDB_LIBRARY_API
+    rocksdb_fulloptions_t rocksdb_options_load_latest(
+        const char* db_path,
+        rocksdb_env_t* env,
+        bool ignore_unknown_options,
+        rocksdb_cache_t* cache,
+        char** errptr);
+
+/* Returns the value of `option_name`, one of "comparator", "merge_operator"
+   and "compaction_filter", if the options file named an object which could
+   not be restored, and NULL otherwise. Valid until the descriptors are
+   destroyed. */
+extern ROCKSDB_LIBRARY_API
+    const char* rocksdb_column_family_descriptors_unresolved_option(
+        const rocksdb_column_family_descriptors_t* cf_descs,
+        int index,
+        const char* option_name);
+
+/* Checks the database options and the options of every column family named in
+   the latest OPTIONS file of the database at `db_path` against the given
+   ones, which are matched by name, in any order. Column families not present
+   in the file are skipped. The files are read through the env of `db_opts`. */
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_check_compatibility(
+        const char* db_path,
+        const rocksdb_options_t* db_opts,
+        int num_column_families,
+        const char* const* column_family_names,
+        const rocksdb_options_t* const* column_family_options,
+        bool ignore_unknown_options,
+        char** errptr);
+
+/* Serializes the database and column family options as a string of
+   `name=value;` pairs, which rocksdb_get_options_from_string reads back. */
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_options_get_options_string(
+        const rocksdb_options_t* options,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_options_get_db_options_string(
+        const rocksdb_options_t* options,
+        unsigned char mutable_only,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_options_get_cf_options_string(
+        const rocksdb_options_t* options,
+        unsigned char mutable_only,
+        char** errptr);
+
+/* The options set by a string of `name=value;` pairs, by name, where the
+   options of a value in braces are named after their parent and a dot, such
+   as "table_factory.block_size". */
+typedef struct rocksdb_options_map_t rocksdb_options_map_t;
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_options_map_t* rocksdb_options_string_to_map(
+        const char* opts_str,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_options_map_count(const rocksdb_options_map_t* map);
+
+/* Valid until the map is destroyed. */
+extern ROCKSDB_LIBRARY_API
+    const char* rocksdb_options_map_name(const rocksdb_options_map_t* map, size_t index);
+
+/* Valid until the map is destroyed. */
+extern ROCKSDB_LIBRARY_API
+    const char* rocksdb_options_map_value(const rocksdb_options_map_t* map, size_t index);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_map_destroy(rocksdb_options_map_t* map);
+
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_block_based_options_get_options_string(
+        const rocksdb_block_based_table_options_t* options,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_get_block_based_options_from_string(
+        const rocksdb_block_based_table_options_t* base_options,
+        const char* opts_str,
+        rocksdb_block_based_table_options_t* new_options,
+        char** errptr);
+
+/* The current mutable options of the database and of a column family, as
+   `name=value;` pairs. */
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_get_db_options_string(
+        rocksdb_t* db,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_get_options_string(
+        rocksdb_t* db,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_get_options_string_cf(
+        rocksdb_t* db,
+        rocksdb_column_family_handle_t* column_family,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_set_allow_2pc(
+        rocksdb_options_t* opt,
+        unsigned char v);
+
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_options_get_allow_2pc(
+        rocksdb_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_set_two_write_queues(
+        rocksdb_options_t* opt,
+        unsigned char v);
+
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_options_get_two_write_queues(
+        rocksdb_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_options_get_dump_malloc_stats(
+        rocksdb_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_options_get_memtable_whole_key_filtering(
+        rocksdb_options_t* opt);
+
+/* Copies up to `num_levels` compression types into `level_values` and returns
+   the number of levels which have one. */
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_options_get_compression_per_level(
+        rocksdb_options_t* opt,
+        int* level_values,
+        size_t num_levels);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_get_compression_options(
+        rocksdb_options_t* opt,
+        int* w_bits,
+        int* level,
+        int* strategy,
+        int* max_dict_bytes);
+
+/* Like rocksdb_options_get_compression_options, also returning the zstd
+   training size, and whether the bottommost options are enabled. */
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_options_get_bottommost_compression_options(
+        rocksdb_options_t* opt,
+        int* w_bits,
+        int* level,
+        int* strategy,
+        int* max_dict_bytes,
+        int* zstd_max_train_bytes);
+
+/* Same as rocksdb_options_get_compression_per_level. */
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_options_get_max_bytes_for_level_multiplier_additional(
+        rocksdb_options_t* opt,
+        int* level_values,
+        size_t num_levels);
+
+/* The returned strings are valid until the options are changed or
+   destroyed. */
+extern ROCKSDB_LIBRARY_API
+    const char* rocksdb_options_get_db_log_dir(
+        rocksdb_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    const char* rocksdb_options_get_wal_dir(
+        rocksdb_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_options_get_db_paths_count(
+        rocksdb_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    const char* rocksdb_options_get_db_path(
+        rocksdb_options_t* opt,
+        size_t index,
+        uint64_t* target_size);
+
+extern ROCKSDB_LIBRARY_API
+    uint64_t rocksdb_options_get_periodic_compaction_seconds(
+        rocksdb_options_t* opt);
+
+/* Returns NULL without a prefix extractor. */
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_options_get_prefix_extractor_name(
+        rocksdb_options_t* opt);
+
+/* Return copies, which are destroyed like the ones created with
+   rocksdb_universal_compaction_options_create and
+   rocksdb_fifo_compaction_options_create. */
+extern ROCKSDB_LIBRARY_API
+    rocksdb_universal_compaction_options_t* rocksdb_options_get_universal_compaction_options(
+        rocksdb_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_fifo_compaction_options_t* rocksdb_options_get_fifo_compaction_options(
+        rocksdb_options_t* opt);
+
+/* Returns the transaction database itself as a database, not its base
+   database, so that writes through it still take the locks of the
+   transactions. Destroy it with rocksdb_transactiondb_as_db_destroy before
+   closing the transaction database. */
+extern ROCKSDB_LIBRARY_API
+    rocksdb_t* rocksdb_transactiondb_as_db(
+        rocksdb_transactiondb_t* txn_db);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_transactiondb_as_db_destroy(
+        rocksdb_t* db);
+
+/* One of rocksdb_txndb_write_committed, rocksdb_txndb_write_prepared or
+   rocksdb_txndb_write_unprepared. */
+enum {
+    rocksdb_txndb_write_committed = 0,
+    rocksdb_txndb_write_prepared = 1,
+    rocksdb_txndb_write_unprepared = 2
+};
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_transactiondb_options_set_write_policy(
+        rocksdb_transactiondb_options_t* opt,
+        int policy);
+
+extern ROCKSDB_LIBRARY_API
+    int rocksdb_transactiondb_options_get_write_policy(
+        rocksdb_transactiondb_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_transactiondb_options_set_default_write_batch_flush_threshold(
+        rocksdb_transactiondb_options_t* opt,
+        int64_t threshold);
+
+extern ROCKSDB_LIBRARY_API
+    int64_t rocksdb_transactiondb_options_get_default_write_batch_flush_threshold(
+        rocksdb_transactiondb_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_transaction_options_set_write_batch_flush_threshold(
+        rocksdb_transaction_options_t* opt,
+        int64_t threshold);
+
+extern ROCKSDB_LIBRARY_API
+    int64_t rocksdb_transaction_options_get_write_batch_flush_threshold(
+        rocksdb_transaction_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_transaction_set_name(
+        rocksdb_transaction_t* txn,
+        const char* name,
+        size_t name_len,
+        char** errptr);
+
+/* Returns the name of the transaction, empty if not named, to be freed
+   with rocksdb_free. */
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_transaction_get_name(
+        rocksdb_transaction_t* txn,
+        size_t* name_len);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_transaction_prepare(
+        rocksdb_transaction_t* txn,
+        char** errptr);
+
+/* Returns the transactions prepared but neither committed nor rolled back,
+   such as those recovered when opening, each to be destroyed with
+   rocksdb_transaction_destroy once done and the array to be freed with
+   rocksdb_free. */
+extern ROCKSDB_LIBRARY_API
+    rocksdb_transaction_t** rocksdb_transactiondb_get_prepared_transactions(
+        rocksdb_transactiondb_t* txn_db,
+        size_t* count);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_get_merge_operands_cf(
+        rocksdb_t* db,
+        const rocksdb_readoptions_t* options,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key,
+        size_t keylen,
+        int max_operands,
+        char** values_list,
+        size_t* values_list_sizes,
+        int* num_operands,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_set_comparator_with_hooks(
+        rocksdb_options_t* options,
+        void* state,
+        void (*destructor)(void*),
+        int (*compare)(void*, const char* a, size_t alen, const char* b, size_t blen),
+        const char* (*name)(void*),
+        char* (*find_shortest_separator)(
+            void*, const char* start, size_t start_len,
+            const char* limit, size_t limit_len, size_t* result_len),
+        char* (*find_short_successor)(
+            void*, const char* key, size_t key_len, size_t* result_len));
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_set_builtin_comparator(rocksdb_options_t* options, int comparator);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_put_with_ts_cf(
+        rocksdb_t* db,
+        const rocksdb_writeoptions_t* options,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        const char* val, size_t vallen,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_delete_with_ts_cf(
+        rocksdb_t* db,
+        const rocksdb_writeoptions_t* options,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        char** errptr);
+
+/* Write batch entries with the timestamp appended to the key, after checking
+   its size against the comparator of the column family. */
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_writebatch_put_with_ts_cf(
+        rocksdb_writebatch_t* b,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        const char* val, size_t vallen,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_writebatch_delete_with_ts_cf(
+        rocksdb_writebatch_t* b,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_default_column_family_timestamp_size(
+        rocksdb_t* db);
+
+extern ROCKSDB_LIBRARY_API
+    char* rocksdb_get_with_ts_cf(
+        rocksdb_t* db,
+        const rocksdb_readoptions_t* options,
+        rocksdb_column_family_handle_t* column_family,
+        const char* key, size_t keylen,
+        const char* ts, size_t tslen,
+        size_t* vallen,
+        char** found_ts, size_t* found_tslen,
+        char** errptr);
+
+/* A copy of a timestamp to read at. The read options only point to it, so it
+   must outlive them and the iterators created with them. */
+extern ROCKSDB_LIBRARY_API
+    rocksdb_timestamp_t* rocksdb_timestamp_create(const char* ts, size_t tslen);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_timestamp_destroy(rocksdb_timestamp_t* ts);
+
+/* A NULL timestamp unsets it. */
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_readoptions_set_timestamp(
+        rocksdb_readoptions_t* options, const rocksdb_timestamp_t* ts);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_readoptions_set_iter_start_ts(
+        rocksdb_readoptions_t* options, const rocksdb_timestamp_t* ts);
+
+extern ROCKSDB_LIBRARY_API
+    const char* rocksdb_iter_timestamp(const rocksdb_iterator_t* iter, size_t* tslen);
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_slicetransform_t* rocksdb_slicetransform_create_capped_prefix(size_t cap_len);
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_eventlistener_t* rocksdb_eventlistener_create(
+        void* state,
+        void (*destructor)(void*),
+        void (*on_flush_begin)(void*, const rocksdb_flush_job_info_t*),
+        void (*on_flush_completed)(void*, const rocksdb_flush_job_info_t*),
+        void (*on_compaction_begin)(void*, const rocksdb_compaction_job_info_t*),
+        void (*on_compaction_completed)(void*, const rocksdb_compaction_job_info_t*),
+        void (*on_table_file_created)(void*, const rocksdb_table_file_creation_info_t*),
+        void (*on_table_file_deleted)(void*, const rocksdb_table_file_deletion_info_t*),
+        void (*on_background_error)(void*, int reason, const char* status),
+        void (*on_stall_conditions_changed)(void*, const rocksdb_write_stall_info_t*),
+        void (*on_memtable_sealed)(void*, const rocksdb_memtable_info_t*));
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_add_eventlistener(
+        rocksdb_options_t* options, rocksdb_eventlistener_t* listener);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_set_callback_logger(
+        rocksdb_options_t* options,
+        void* state,
+        void (*destructor)(void*),
+        void (*log)(void*, int level, const char* msg, size_t len),
+        int log_level);
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_env_t* rocksdb_create_filesystem_env(
+        void* state,
+        void (*destructor)(void*),
+        const char* name,
+        const rocksdb_filesystem_callbacks_t* callbacks);
+
+/* Clock standing still at the time it was created until advanced. */
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_mock_clock_t* rocksdb_mock_clock_create(void);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_mock_clock_destroy(rocksdb_mock_clock_t* clock);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_mock_clock_advance(rocksdb_mock_clock_t* clock, uint64_t micros);
+
+/* The default env, except that it takes the time from `clock`. */
+extern ROCKSDB_LIBRARY_API
+    rocksdb_env_t* rocksdb_create_mock_clock_env(rocksdb_mock_clock_t* clock);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_set_periodic_compaction_seconds(
+        rocksdb_options_t* opt,
+        uint64_t seconds);
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_ratelimiter_t* rocksdb_ratelimiter_create_with_mode(
+        int64_t rate_bytes_per_sec,
+        int64_t refill_period_us,
+        int32_t fairness,
+        int mode,
+        unsigned char auto_tuned);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_ratelimiter_set_bytes_per_second(
+        rocksdb_ratelimiter_t* limiter, int64_t bytes_per_second);
+
+extern ROCKSDB_LIBRARY_API
+    int64_t rocksdb_ratelimiter_get_bytes_per_second(rocksdb_ratelimiter_t* limiter);
+
+extern ROCKSDB_LIBRARY_API
+    int64_t rocksdb_ratelimiter_get_single_burst_bytes(rocksdb_ratelimiter_t* limiter);
+
+extern ROCKSDB_LIBRARY_API
+    int64_t rocksdb_ratelimiter_get_total_bytes_through(rocksdb_ratelimiter_t* limiter);
+
+extern ROCKSDB_LIBRARY_API
+    int64_t rocksdb_ratelimiter_get_total_requests(rocksdb_ratelimiter_t* limiter);
+
+/* SST file manager, shared between databases through their options. */
+
+typedef struct rocksdb_sstfilemanager_t rocksdb_sstfilemanager_t;
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_sstfilemanager_t* rocksdb_sstfilemanager_create(
+        rocksdb_env_t* env, char** errptr);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_sstfilemanager_destroy(rocksdb_sstfilemanager_t* manager);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_sstfilemanager_set_max_allowed_space_usage(
+        rocksdb_sstfilemanager_t* manager, uint64_t max_allowed_space);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_sstfilemanager_set_compaction_buffer_size(
+        rocksdb_sstfilemanager_t* manager, uint64_t compaction_buffer_size);
+
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_sstfilemanager_is_max_allowed_space_reached(
+        rocksdb_sstfilemanager_t* manager);
+
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_sstfilemanager_is_max_allowed_space_reached_including_compactions(
+        rocksdb_sstfilemanager_t* manager);
+
+extern ROCKSDB_LIBRARY_API
+    uint64_t rocksdb_sstfilemanager_get_total_size(rocksdb_sstfilemanager_t* manager);
+
+extern ROCKSDB_LIBRARY_API
+    int64_t rocksdb_sstfilemanager_get_delete_rate_bytes_per_second(
+        rocksdb_sstfilemanager_t* manager);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_sstfilemanager_set_delete_rate_bytes_per_second(
+        rocksdb_sstfilemanager_t* manager, int64_t delete_rate);
+
+extern ROCKSDB_LIBRARY_API
+    double rocksdb_sstfilemanager_get_max_trash_db_ratio(rocksdb_sstfilemanager_t* manager);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_sstfilemanager_set_max_trash_db_ratio(
+        rocksdb_sstfilemanager_t* manager, double ratio);
+
+extern ROCKSDB_LIBRARY_API
+    uint64_t rocksdb_sstfilemanager_get_total_trash_size(rocksdb_sstfilemanager_t* manager);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_set_sst_file_manager(
+        rocksdb_options_t* opt, rocksdb_sstfilemanager_t* manager);
+
+/* Write buffer manager, shared between databases through their options. */
+
+typedef struct rocksdb_write_buffer_manager_t rocksdb_write_buffer_manager_t;
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_write_buffer_manager_t* rocksdb_write_buffer_manager_create(
+        size_t buffer_size, unsigned char allow_stall);
+
+extern ROCKSDB_LIBRARY_API
+    rocksdb_write_buffer_manager_t* rocksdb_write_buffer_manager_create_with_cache(
+        size_t buffer_size, const rocksdb_cache_t* cache, unsigned char allow_stall);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_write_buffer_manager_destroy(rocksdb_write_buffer_manager_t* wbm);
+
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_write_buffer_manager_enabled(rocksdb_write_buffer_manager_t* wbm);
+
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_write_buffer_manager_cost_to_cache(
+        rocksdb_write_buffer_manager_t* wbm);
+
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_write_buffer_manager_memory_usage(rocksdb_write_buffer_manager_t* wbm);
+
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_write_buffer_manager_mutable_memtable_memory_usage(
+        rocksdb_write_buffer_manager_t* wbm);
+
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_write_buffer_manager_dummy_entries_in_cache_usage(
+        rocksdb_write_buffer_manager_t* wbm);
+
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_write_buffer_manager_buffer_size(rocksdb_write_buffer_manager_t* wbm);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_write_buffer_manager_set_buffer_size(
+        rocksdb_write_buffer_manager_t* wbm, size_t new_size);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_options_set_write_buffer_manager(
+        rocksdb_options_t* opt, rocksdb_write_buffer_manager_t* wbm);
+
+/* Cache */
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_lru_cache_options_set_num_shard_bits(
+        rocksdb_lru_cache_options_t* opt, int num_shard_bits);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_lru_cache_options_set_strict_capacity_limit(
+        rocksdb_lru_cache_options_t* opt, unsigned char strict_capacity_limit);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_lru_cache_options_set_high_pri_pool_ratio(
+        rocksdb_lru_cache_options_t* opt, double high_pri_pool_ratio);
+
+/* Returns NULL if RocksDB was built without clock cache support. */
+extern ROCKSDB_LIBRARY_API
+    rocksdb_cache_t* rocksdb_cache_create_clock(
+        size_t capacity, int num_shard_bits, unsigned char strict_capacity_limit);
+
+extern ROCKSDB_LIBRARY_API
+    void rocksdb_cache_set_strict_capacity_limit(
+        rocksdb_cache_t* cache, unsigned char strict_capacity_limit);
+
+extern ROCKSDB_LIBRARY_API
+    unsigned char rocksdb_cache_has_strict_capacity_limit(rocksdb_cache_t* cache);
+
+/* Fills `entries` and `charges`, both of `num_roles` elements, with the
+   number and total charge of the cache entries of each role, in the order
+   of rocksdb::CacheEntryRole. Returns the number of roles RocksDB knows. */
+extern ROCKSDB_LIBRARY_API
+    size_t rocksdb_cache_get_entry_stats(
+        rocksdb_cache_t* cache, uint64_t* entries, uint64_t* charges, size_t num_roles);
+
 #ifdef __cplusplus
 }  /* end extern "C" */
 #endif
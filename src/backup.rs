@@ -13,7 +13,7 @@
 // limitations under the License.
 //
 
-use crate::{ffi, Error, DB};
+use crate::{ffi, handle::Handle, Error};
 
 use libc::{c_int, c_uchar};
 use std::ffi::CString;
@@ -76,7 +76,7 @@ impl BackupEngine {
     ///
     /// Note: no flush before backup is performed. User might want to
     /// use `create_new_backup_flush` instead.
-    pub fn create_new_backup(&mut self, db: &DB) -> Result<(), Error> {
+    pub fn create_new_backup<D: Handle<ffi::rocksdb_t>>(&mut self, db: &D) -> Result<(), Error> {
         self.create_new_backup_flush(db, false)
     }
 
@@ -84,15 +84,15 @@ impl BackupEngine {
     ///
     /// Set flush_before_backup=true to avoid losing unflushed key/value
     /// pairs from the memtable.
-    pub fn create_new_backup_flush(
+    pub fn create_new_backup_flush<D: Handle<ffi::rocksdb_t>>(
         &mut self,
-        db: &DB,
+        db: &D,
         flush_before_backup: bool,
     ) -> Result<(), Error> {
         unsafe {
             ffi_try!(ffi::rocksdb_backup_engine_create_new_backup_flush(
                 self.inner,
-                db.inner,
+                db.handle(),
                 flush_before_backup as c_uchar,
             ));
             Ok(())
@@ -324,7 +324,7 @@ impl Drop for RestoreOptions {
 #[test]
 fn restore_from_latest() {
     use crate::ops::{Get, Open, Put};
-    use crate::TemporaryDBPath;
+    use crate::{TemporaryDBPath, DB};
 
     // create backup
     let path = TemporaryDBPath::new();
@@ -367,7 +367,7 @@ fn restore_from_latest() {
 #[test]
 fn restore_from_backup() {
     use crate::ops::{Get, Open, Put};
-    use crate::TemporaryDBPath;
+    use crate::{TemporaryDBPath, DB};
 
     // create backup
     let path = TemporaryDBPath::new();
@@ -38,6 +38,10 @@ impl ColumnFamilyDescriptor {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
 }
 
 impl ColumnFamily {
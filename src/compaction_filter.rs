@@ -17,6 +17,8 @@ use libc::{c_char, c_int, c_uchar, c_void, size_t};
 use std::ffi::{CStr, CString};
 use std::slice;
 
+use crate::ffi_util::abort_on_panic;
+
 /// Decision about how to handle compacting an object
 ///
 /// This is returned by a compaction filter callback. Depending
@@ -100,7 +102,9 @@ pub unsafe extern "C" fn destructor_callback<F>(raw_cb: *mut c_void)
 where
     F: CompactionFilter,
 {
-    Box::from_raw(raw_cb as *mut F);
+    abort_on_panic(|| {
+        Box::from_raw(raw_cb as *mut F);
+    })
 }
 
 pub unsafe extern "C" fn name_callback<F>(raw_cb: *mut c_void) -> *const c_char
@@ -125,22 +129,24 @@ pub unsafe extern "C" fn filter_callback<F>(
 where
     F: CompactionFilter,
 {
-    use self::Decision::{Change, Keep, Remove};
+    abort_on_panic(|| {
+        use self::Decision::{Change, Keep, Remove};
 
-    let cb = &mut *(raw_cb as *mut F);
-    let key = slice::from_raw_parts(raw_key as *const u8, key_length as usize);
-    let oldval = slice::from_raw_parts(existing_value as *const u8, value_length as usize);
-    let result = cb.filter(level as u32, key, oldval);
-    match result {
-        Keep => 0,
-        Remove => 1,
-        Change(newval) => {
-            *new_value = newval.as_ptr() as *mut c_char;
-            *new_value_length = newval.len() as size_t;
-            *value_changed = 1_u8;
-            0
+        let cb = &mut *(raw_cb as *mut F);
+        let key = slice::from_raw_parts(raw_key as *const u8, key_length as usize);
+        let oldval = slice::from_raw_parts(existing_value as *const u8, value_length as usize);
+        let result = cb.filter(level as u32, key, oldval);
+        match result {
+            Keep => 0,
+            Remove => 1,
+            Change(newval) => {
+                *new_value = newval.as_ptr() as *mut c_char;
+                *new_value_length = newval.len() as size_t;
+                *value_changed = 1_u8;
+                0
+            }
         }
-    }
+    })
 }
 
 #[cfg(test)]
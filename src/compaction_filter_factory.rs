@@ -5,6 +5,7 @@ use libc::{self, c_char, c_void};
 use crate::{
     compaction_filter::{self, CompactionFilter},
     ffi,
+    ffi_util::abort_on_panic,
 };
 
 /// Each compaction will create a new CompactionFilter allowing the
@@ -30,7 +31,9 @@ pub unsafe extern "C" fn destructor_callback<F>(raw_self: *mut c_void)
 where
     F: CompactionFilterFactory,
 {
-    Box::from_raw(raw_self as *mut F);
+    abort_on_panic(|| {
+        Box::from_raw(raw_self as *mut F);
+    })
 }
 
 pub unsafe extern "C" fn name_callback<F>(raw_self: *mut c_void) -> *const c_char
@@ -70,18 +73,20 @@ pub unsafe extern "C" fn create_compaction_filter_callback<F>(
 where
     F: CompactionFilterFactory,
 {
-    let self_ = &mut *(raw_self as *mut F);
-    let context = CompactionFilterContext::from_raw(context);
-    let filter = Box::new(self_.create(context));
-
-    let filter_ptr = Box::into_raw(filter);
-
-    ffi::rocksdb_compactionfilter_create(
-        filter_ptr as *mut c_void,
-        Some(compaction_filter::destructor_callback::<F::Filter>),
-        Some(compaction_filter::filter_callback::<F::Filter>),
-        Some(compaction_filter::name_callback::<F::Filter>),
-    )
+    abort_on_panic(|| {
+        let self_ = &mut *(raw_self as *mut F);
+        let context = CompactionFilterContext::from_raw(context);
+        let filter = Box::new(self_.create(context));
+
+        let filter_ptr = Box::into_raw(filter);
+
+        ffi::rocksdb_compactionfilter_create(
+            filter_ptr as *mut c_void,
+            Some(compaction_filter::destructor_callback::<F::Filter>),
+            Some(compaction_filter::filter_callback::<F::Filter>),
+            Some(compaction_filter::name_callback::<F::Filter>),
+        )
+    })
 }
 
 #[cfg(test)]
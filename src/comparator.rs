@@ -16,17 +16,61 @@
 use libc::{c_char, c_int, c_void, size_t};
 use std::cmp::Ordering;
 use std::ffi::CString;
+use std::ptr;
 use std::slice;
 
-pub type CompareFn = fn(&[u8], &[u8]) -> Ordering;
+use crate::ffi_util::abort_on_panic;
+
+/// Function used to order keys. It must define a total order and must be
+/// stable across database restarts.
+pub type CompareFn = dyn Fn(&[u8], &[u8]) -> Ordering + Send + Sync;
+
+/// Given `start` and `limit` with `start < limit`, returns a shorter key in
+/// `[start, limit)`, or `None` to keep `start` unchanged.
+pub type ShortestSeparatorFn = dyn Fn(&[u8], &[u8]) -> Option<Vec<u8>> + Send + Sync;
+
+/// Returns a short key that is greater than or equal to `key`, or `None` to
+/// keep `key` unchanged.
+pub type ShortSuccessorFn = dyn Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync;
+
+/// Comparators implemented natively in RocksDB, which avoid calling back
+/// into Rust for every key comparison.
+///
+/// The big-endian integer comparators order keys by their fixed-width
+/// prefix, then by the remaining bytes. Keys shorter than the prefix sort
+/// before all others, byte-wise among themselves.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(C)]
+pub enum BuiltinComparator {
+    /// Lexicographic byte-wise ordering, the RocksDB default.
+    Bytewise = 0,
+    /// Reverse lexicographic byte-wise ordering.
+    ReverseBytewise = 1,
+    /// Orders keys by their leading 4 bytes read as a big-endian `u32`.
+    BigEndianU32 = 2,
+    /// Orders keys by their leading 8 bytes read as a big-endian `u64`.
+    BigEndianU64 = 3,
+    /// Orders keys by their leading 4 bytes read as a big-endian `i32`.
+    BigEndianI32 = 4,
+    /// Orders keys by their leading 8 bytes read as a big-endian `i64`.
+    BigEndianI64 = 5,
+    /// Byte-wise ordering of keys carrying a user-defined timestamp: an
+    /// 8-byte little-endian `u64` suffix, with newer versions first. Keys
+    /// are then written and read with the `*_with_ts` operations.
+    BytewiseWithU64Ts = 6,
+}
 
 pub struct ComparatorCallback {
     pub name: CString,
-    pub f: CompareFn,
+    pub compare_fn: Box<CompareFn>,
+    pub shortest_separator_fn: Option<Box<ShortestSeparatorFn>>,
+    pub short_successor_fn: Option<Box<ShortSuccessorFn>>,
 }
 
 pub unsafe extern "C" fn destructor_callback(raw_cb: *mut c_void) {
-    Box::from_raw(raw_cb as *mut ComparatorCallback);
+    abort_on_panic(|| {
+        drop(Box::from_raw(raw_cb as *mut ComparatorCallback));
+    })
 }
 
 pub unsafe extern "C" fn name_callback(raw_cb: *mut c_void) -> *const c_char {
@@ -42,12 +86,61 @@ pub unsafe extern "C" fn compare_callback(
     b_raw: *const c_char,
     b_len: size_t,
 ) -> c_int {
-    let cb: &mut ComparatorCallback = &mut *(raw_cb as *mut ComparatorCallback);
-    let a: &[u8] = slice::from_raw_parts(a_raw as *const u8, a_len as usize);
-    let b: &[u8] = slice::from_raw_parts(b_raw as *const u8, b_len as usize);
-    match (cb.f)(a, b) {
-        Ordering::Less => -1,
-        Ordering::Equal => 0,
-        Ordering::Greater => 1,
+    abort_on_panic(|| {
+        let cb: &mut ComparatorCallback = &mut *(raw_cb as *mut ComparatorCallback);
+        let a: &[u8] = slice::from_raw_parts(a_raw as *const u8, a_len as usize);
+        let b: &[u8] = slice::from_raw_parts(b_raw as *const u8, b_len as usize);
+        match (cb.compare_fn)(a, b) {
+            Ordering::Less => -1,
+            Ordering::Equal => 0,
+            Ordering::Greater => 1,
+        }
+    })
+}
+
+pub unsafe extern "C" fn shortest_separator_callback(
+    raw_cb: *mut c_void,
+    start_raw: *const c_char,
+    start_len: size_t,
+    limit_raw: *const c_char,
+    limit_len: size_t,
+    result_len: *mut size_t,
+) -> *mut c_char {
+    abort_on_panic(|| {
+        let cb: &mut ComparatorCallback = &mut *(raw_cb as *mut ComparatorCallback);
+        let start: &[u8] = slice::from_raw_parts(start_raw as *const u8, start_len as usize);
+        let limit: &[u8] = slice::from_raw_parts(limit_raw as *const u8, limit_len as usize);
+        let result = cb
+            .shortest_separator_fn
+            .as_ref()
+            .and_then(|f| f(start, limit));
+        to_malloced(result, result_len)
+    })
+}
+
+pub unsafe extern "C" fn short_successor_callback(
+    raw_cb: *mut c_void,
+    key_raw: *const c_char,
+    key_len: size_t,
+    result_len: *mut size_t,
+) -> *mut c_char {
+    abort_on_panic(|| {
+        let cb: &mut ComparatorCallback = &mut *(raw_cb as *mut ComparatorCallback);
+        let key: &[u8] = slice::from_raw_parts(key_raw as *const u8, key_len as usize);
+        let result = cb.short_successor_fn.as_ref().and_then(|f| f(key));
+        to_malloced(result, result_len)
+    })
+}
+
+// The returned buffer is released with `free` on the C++ side.
+unsafe fn to_malloced(value: Option<Vec<u8>>, value_len: *mut size_t) -> *mut c_char {
+    match value {
+        Some(value) => {
+            let buf = libc::malloc(value.len().max(1)) as *mut u8;
+            ptr::copy_nonoverlapping(value.as_ptr(), buf, value.len());
+            *value_len = value.len() as size_t;
+            buf as *mut c_char
+        }
+        None => ptr::null_mut(),
     }
 }
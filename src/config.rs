@@ -0,0 +1,634 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarative configuration of a database and its column families, which
+//! can be read with serde from TOML, JSON or any other format and turned into
+//! `FullOptions`. Available with the `serde` feature.
+//!
+//! Every option is optional and keeps the RocksDB default when left out.
+//! Caches are declared once under `caches` and referred to by name, so that
+//! several column families can share one.
+//!
+//! # Examples
+//!
+//! ```
+//! use ckb_rocksdb::{config::FullOptionsConfig, prelude::*, TemporaryDBPath};
+//!
+//! let config: FullOptionsConfig = toml::from_str(
+//!     r#"
+//!     [caches.shared]
+//!     capacity = 67108864
+//!
+//!     [options]
+//!     create_if_missing = true
+//!     create_missing_column_families = true
+//!     max_background_jobs = 4
+//!     compaction_style = "Level"
+//!
+//!     [options.block_based]
+//!     block_size = 16384
+//!     block_cache = "shared"
+//!     filter = { type = "bloom", bits_per_key = 10.0 }
+//!
+//!     [column_families.index]
+//!     write_buffer_size = 8388608
+//!     "#,
+//! )
+//! .unwrap();
+//!
+//! let full_opts = config.to_full_options().unwrap();
+//! let path = TemporaryDBPath::new();
+//! let db = DB::open_cf_descriptors(&full_opts.db_opts, &path, full_opts.cf_descriptors).unwrap();
+//! assert!(db.cf_handle("index").is_some());
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AccessHint, BlockBasedIndexType, BlockBasedOptions, Cache, ColumnFamilyDescriptor,
+    DBCompactionStyle, DBCompressionType, DBRecoveryMode, DataBlockIndexType, Error,
+    FifoCompactOptions, FullOptions, LogLevel, LruCacheOptions, Options, UniversalCompactOptions,
+    UniversalCompactionStopStyle,
+};
+
+/// Generates a config struct from a table of `field: type => setter`, with
+/// the optional fields calling the setter of `$target` with the value, or a
+/// reference to it, when set. Other fields are declared as is.
+macro_rules! options_config {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident for $target:ty {
+            $($(#[$field_attr:meta])* pub $field:ident: $ty:ty,)*
+        }
+        by_value {
+            $($value_field:ident: $value_ty:ty => $value_setter:ident,)*
+        }
+        by_ref {
+            $($ref_field:ident: $ref_ty:ty => $ref_setter:ident,)*
+        }
+    ) => {
+        $(#[$attr])*
+        pub struct $name {
+            $(
+                #[doc = concat!("See `", stringify!($value_setter), "`.")]
+                pub $value_field: Option<$value_ty>,
+            )*
+            $(
+                #[doc = concat!("See `", stringify!($ref_setter), "`.")]
+                pub $ref_field: Option<$ref_ty>,
+            )*
+            $($(#[$field_attr])* pub $field: $ty,)*
+        }
+
+        impl $name {
+            fn apply_table(&self, opts: &mut $target) {
+                $(
+                    if let Some(value) = self.$value_field {
+                        opts.$value_setter(value);
+                    }
+                )*
+                $(
+                    if let Some(value) = &self.$ref_field {
+                        opts.$ref_setter(value);
+                    }
+                )*
+            }
+        }
+    };
+}
+
+/// The configuration of a database and its column families.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FullOptionsConfig {
+    /// Caches by name, for `block_cache`, `block_cache_compressed` and
+    /// `row_cache`
+    pub caches: BTreeMap<String, CacheConfig>,
+    /// Options of the database, which every column family starts from
+    pub options: OptionsConfig,
+    /// Options of the column families by name, overriding `options`
+    pub column_families: BTreeMap<String, OptionsConfig>,
+}
+
+impl FullOptionsConfig {
+    /// Creates the caches and the options of the database and the column
+    /// families.
+    ///
+    /// The error of an invalid value names the field, such as
+    /// `column_families.index.block_based.block_cache`.
+    ///
+    /// The options of a column family are those of `options` overridden by
+    /// its own, where `block_based`, `compression_options` and the other
+    /// sections replace those of `options` as a whole. A "default" column
+    /// family with the options of `options` is added if column families are
+    /// listed without it.
+    pub fn to_full_options(&self) -> Result<FullOptions, Error> {
+        let mut caches = BTreeMap::new();
+        for (name, config) in &self.caches {
+            caches.insert(name.as_str(), config.build(&format!("caches.{}", name))?);
+        }
+
+        let mut db_opts = Options::default();
+        self.options.apply(&mut db_opts, &caches, "options")?;
+
+        let mut cf_descriptors = Vec::new();
+        if !self.column_families.is_empty() && !self.column_families.contains_key("default") {
+            cf_descriptors.push(ColumnFamilyDescriptor::new("default", db_opts.clone()));
+        }
+        for (name, config) in &self.column_families {
+            let mut cf_opts = db_opts.clone();
+            config.apply(&mut cf_opts, &caches, &format!("column_families.{}", name))?;
+            cf_descriptors.push(ColumnFamilyDescriptor::new(name.clone(), cf_opts));
+        }
+
+        Ok(FullOptions {
+            db_opts,
+            cf_descriptors,
+        })
+    }
+}
+
+fn invalid<M: Display>(path: &str, field: &str, message: M) -> Error {
+    Error::new(format!("{}.{}: {}", path, field, message))
+}
+
+fn check_ratio(path: &str, field: &str, value: Option<f64>, max: f64) -> Result<(), Error> {
+    match value {
+        Some(value) if !(0.0..=max).contains(&value) => Err(invalid(
+            path,
+            field,
+            format!("{} is not between 0 and {}", value, max),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn find_cache(
+    caches: &BTreeMap<&str, Cache>,
+    path: &str,
+    field: &str,
+    name: &str,
+) -> Result<Cache, Error> {
+    caches
+        .get(name)
+        .cloned()
+        .ok_or_else(|| invalid(path, field, format!("unknown cache \"{}\"", name)))
+}
+
+/// An LRU cache, see `LruCacheOptions`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Capacity in bytes
+    pub capacity: usize,
+    /// See `LruCacheOptions::set_num_shard_bits`, at most 19
+    pub num_shard_bits: Option<i32>,
+    /// See `LruCacheOptions::set_strict_capacity_limit`
+    pub strict_capacity_limit: Option<bool>,
+    /// See `LruCacheOptions::set_high_pri_pool_ratio`, between 0 and 1
+    pub high_pri_pool_ratio: Option<f64>,
+}
+
+impl CacheConfig {
+    fn build(&self, path: &str) -> Result<Cache, Error> {
+        if let Some(bits) = self.num_shard_bits {
+            if bits >= 20 {
+                return Err(invalid(path, "num_shard_bits", "must be less than 20"));
+            }
+        }
+        check_ratio(path, "high_pri_pool_ratio", self.high_pri_pool_ratio, 1.0)?;
+
+        let mut opts = LruCacheOptions::default();
+        opts.set_capacity(self.capacity);
+        if let Some(bits) = self.num_shard_bits {
+            opts.set_num_shard_bits(bits);
+        }
+        if let Some(strict) = self.strict_capacity_limit {
+            opts.set_strict_capacity_limit(strict);
+        }
+        if let Some(ratio) = self.high_pri_pool_ratio {
+            opts.set_high_pri_pool_ratio(ratio);
+        }
+        Cache::new_lru_cache_opts(&opts)
+    }
+}
+
+options_config! {
+    /// The options of a database or a column family, see `Options`.
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct OptionsConfig for Options {
+        /// See `set_compression_options` and `set_zstd_max_train_bytes`
+        pub compression_options: Option<CompressionOptionsConfig>,
+        /// See `set_bottommost_compression_options` and
+        /// `set_bottommost_zstd_max_train_bytes`, enabled when set
+        pub bottommost_compression_options: Option<CompressionOptionsConfig>,
+        /// See `set_universal_compaction_options`
+        pub universal_compaction: Option<UniversalCompactionConfig>,
+        /// See `set_fifo_compaction_options`
+        pub fifo_compaction: Option<FifoCompactionConfig>,
+        /// See `set_block_based_table_factory`
+        pub block_based: Option<BlockBasedConfig>,
+        /// Name of the cache in `caches` to use as row cache
+        pub row_cache: Option<String>,
+    }
+    by_value {
+        create_if_missing: bool => create_if_missing,
+        create_missing_column_families: bool => create_missing_column_families,
+        error_if_exists: bool => set_error_if_exists,
+        paranoid_checks: bool => set_paranoid_checks,
+        compression_type: DBCompressionType => set_compression_type,
+        bottommost_compression_type: DBCompressionType => set_bottommost_compression_type,
+        compaction_readahead_size: usize => set_compaction_readahead_size,
+        enable_blob_files: bool => set_enable_blob_files,
+        min_blob_size: u64 => set_min_blob_size,
+        blob_file_size: u64 => set_blob_file_size,
+        blob_compression_type: DBCompressionType => set_blob_compression_type,
+        enable_blob_gc: bool => set_enable_blob_gc,
+        blob_gc_age_cutoff: f64 => set_blob_gc_age_cutoff,
+        blob_gc_force_threshold: f64 => set_blob_gc_force_threshold,
+        level_compaction_dynamic_level_bytes: bool => set_level_compaction_dynamic_level_bytes,
+        optimize_filters_for_hits: bool => set_optimize_filters_for_hits,
+        delete_obsolete_files_period_micros: u64 => set_delete_obsolete_files_period_micros,
+        max_open_files: i32 => set_max_open_files,
+        max_file_opening_threads: i32 => set_max_file_opening_threads,
+        use_fsync: bool => set_use_fsync,
+        log_level: LogLevel => set_log_level,
+        bytes_per_sync: u64 => set_bytes_per_sync,
+        wal_bytes_per_sync: u64 => set_wal_bytes_per_sync,
+        writable_file_max_buffer_size: u64 => set_writable_file_max_buffer_size,
+        allow_concurrent_memtable_write: bool => set_allow_concurrent_memtable_write,
+        enable_write_thread_adaptive_yield: bool => set_enable_write_thread_adaptive_yield,
+        max_sequential_skip_in_iterations: u64 => set_max_sequential_skip_in_iterations,
+        use_direct_reads: bool => set_use_direct_reads,
+        use_direct_io_for_flush_and_compaction: bool => set_use_direct_io_for_flush_and_compaction,
+        is_fd_close_on_exec: bool => set_is_fd_close_on_exec,
+        table_cache_num_shard_bits: i32 => set_table_cache_num_shard_bits,
+        target_file_size_multiplier: i32 => set_target_file_size_multiplier,
+        max_write_buffer_number: i32 => set_max_write_buffer_number,
+        write_buffer_size: usize => set_write_buffer_size,
+        db_write_buffer_size: usize => set_db_write_buffer_size,
+        max_bytes_for_level_base: u64 => set_max_bytes_for_level_base,
+        max_bytes_for_level_multiplier: f64 => set_max_bytes_for_level_multiplier,
+        max_manifest_file_size: usize => set_max_manifest_file_size,
+        target_file_size_base: u64 => set_target_file_size_base,
+        min_write_buffer_number_to_merge: i32 => set_min_write_buffer_number_to_merge,
+        level_zero_file_num_compaction_trigger: i32 => set_level_zero_file_num_compaction_trigger,
+        level_zero_slowdown_writes_trigger: i32 => set_level_zero_slowdown_writes_trigger,
+        level_zero_stop_writes_trigger: i32 => set_level_zero_stop_writes_trigger,
+        compaction_style: DBCompactionStyle => set_compaction_style,
+        unordered_write: bool => set_unordered_write,
+        max_subcompactions: u32 => set_max_subcompactions,
+        max_background_jobs: i32 => set_max_background_jobs,
+        disable_auto_compactions: bool => set_disable_auto_compactions,
+        memtable_huge_page_size: usize => set_memtable_huge_page_size,
+        max_successive_merges: usize => set_max_successive_merges,
+        bloom_locality: u32 => set_bloom_locality,
+        inplace_update_support: bool => set_inplace_update_support,
+        inplace_update_locks: usize => set_inplace_update_locks,
+        skip_checking_sst_file_sizes_on_db_open: bool => set_skip_checking_sst_file_sizes_on_db_open,
+        max_write_buffer_size_to_maintain: i64 => set_max_write_buffer_size_to_maintain,
+        enable_pipelined_write: bool => set_enable_pipelined_write,
+        report_bg_io_stats: bool => set_report_bg_io_stats,
+        max_total_wal_size: u64 => set_max_total_wal_size,
+        wal_recovery_mode: DBRecoveryMode => set_wal_recovery_mode,
+        stats_dump_period_sec: u32 => set_stats_dump_period_sec,
+        stats_persist_period_sec: u32 => set_stats_persist_period_sec,
+        advise_random_on_open: bool => set_advise_random_on_open,
+        access_hint_on_compaction_start: AccessHint => set_access_hint_on_compaction_start,
+        use_adaptive_mutex: bool => set_use_adaptive_mutex,
+        num_levels: i32 => set_num_levels,
+        memtable_prefix_bloom_ratio: f64 => set_memtable_prefix_bloom_ratio,
+        max_compaction_bytes: u64 => set_max_compaction_bytes,
+        wal_ttl_seconds: u64 => set_wal_ttl_seconds,
+        wal_size_limit_mb: u64 => set_wal_size_limit_mb,
+        manifest_preallocation_size: usize => set_manifest_preallocation_size,
+        skip_stats_update_on_db_open: bool => set_skip_stats_update_on_db_open,
+        keep_log_file_num: usize => set_keep_log_file_num,
+        allow_mmap_writes: bool => set_allow_mmap_writes,
+        allow_mmap_reads: bool => set_allow_mmap_reads,
+        manual_wal_flush: bool => set_manual_wal_flush,
+        atomic_flush: bool => set_atomic_flush,
+        max_log_file_size: usize => set_max_log_file_size,
+        log_file_time_to_roll: usize => set_log_file_time_to_roll,
+        recycle_log_file_num: usize => set_recycle_log_file_num,
+        soft_pending_compaction_bytes_limit: usize => set_soft_pending_compaction_bytes_limit,
+        hard_pending_compaction_bytes_limit: usize => set_hard_pending_compaction_bytes_limit,
+        arena_block_size: usize => set_arena_block_size,
+        dump_malloc_stats: bool => set_dump_malloc_stats,
+        memtable_whole_key_filtering: bool => set_memtable_whole_key_filtering,
+    }
+    by_ref {
+        compression_per_level: Vec<DBCompressionType> => set_compression_per_level,
+        max_bytes_for_level_multiplier_additional: Vec<i32> =>
+            set_max_bytes_for_level_multiplier_additional,
+        db_log_dir: PathBuf => set_db_log_dir,
+        wal_dir: PathBuf => set_wal_dir,
+    }
+}
+
+impl OptionsConfig {
+    fn apply(
+        &self,
+        opts: &mut Options,
+        caches: &BTreeMap<&str, Cache>,
+        path: &str,
+    ) -> Result<(), Error> {
+        if let Some(num_levels) = self.num_levels {
+            if num_levels < 1 {
+                return Err(invalid(path, "num_levels", "must be at least 1"));
+            }
+        }
+        if let Some(multiplier) = self.max_bytes_for_level_multiplier {
+            if multiplier <= 0.0 {
+                return Err(invalid(
+                    path,
+                    "max_bytes_for_level_multiplier",
+                    "must be positive",
+                ));
+            }
+        }
+        check_ratio(path, "blob_gc_age_cutoff", self.blob_gc_age_cutoff, 1.0)?;
+        check_ratio(
+            path,
+            "blob_gc_force_threshold",
+            self.blob_gc_force_threshold,
+            1.0,
+        )?;
+        check_ratio(
+            path,
+            "memtable_prefix_bloom_ratio",
+            self.memtable_prefix_bloom_ratio,
+            0.25,
+        )?;
+        if let Some(universal) = &self.universal_compaction {
+            universal.check(&format!("{}.universal_compaction", path))?;
+        }
+        let block_opts = match &self.block_based {
+            Some(block_based) => Some(block_based.build(caches, &format!("{}.block_based", path))?),
+            None => None,
+        };
+        let row_cache = match &self.row_cache {
+            Some(name) => Some(find_cache(caches, path, "row_cache", name)?),
+            None => None,
+        };
+
+        self.apply_table(opts);
+        if let Some(compression) = &self.compression_options {
+            opts.set_compression_options(
+                compression.window_bits,
+                compression.level,
+                compression.strategy,
+                compression.max_dict_bytes,
+            );
+            if let Some(bytes) = compression.zstd_max_train_bytes {
+                opts.set_zstd_max_train_bytes(bytes);
+            }
+        }
+        if let Some(compression) = &self.bottommost_compression_options {
+            opts.set_bottommost_compression_options(
+                compression.window_bits,
+                compression.level,
+                compression.strategy,
+                compression.max_dict_bytes,
+                true,
+            );
+            if let Some(bytes) = compression.zstd_max_train_bytes {
+                opts.set_bottommost_zstd_max_train_bytes(bytes, true);
+            }
+        }
+        if let Some(universal) = &self.universal_compaction {
+            opts.set_universal_compaction_options(&universal.build());
+        }
+        if let Some(fifo) = &self.fifo_compaction {
+            let mut fifo_opts = FifoCompactOptions::default();
+            fifo_opts.set_max_table_files_size(fifo.max_table_files_size);
+            opts.set_fifo_compaction_options(&fifo_opts);
+        }
+        if let Some(block_opts) = block_opts {
+            opts.set_block_based_table_factory(&block_opts);
+        }
+        if let Some(row_cache) = row_cache {
+            opts.set_row_cache(&row_cache);
+        }
+        Ok(())
+    }
+}
+
+/// See `Options::set_compression_options`, with the RocksDB defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompressionOptionsConfig {
+    pub window_bits: i32,
+    pub level: i32,
+    pub strategy: i32,
+    pub max_dict_bytes: i32,
+    pub zstd_max_train_bytes: Option<i32>,
+}
+
+impl Default for CompressionOptionsConfig {
+    fn default() -> Self {
+        Self {
+            window_bits: -14,
+            level: 32767,
+            strategy: 0,
+            max_dict_bytes: 0,
+            zstd_max_train_bytes: None,
+        }
+    }
+}
+
+/// See `UniversalCompactOptions`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UniversalCompactionConfig {
+    pub size_ratio: Option<i32>,
+    pub min_merge_width: Option<i32>,
+    pub max_merge_width: Option<i32>,
+    pub max_size_amplification_percent: Option<i32>,
+    pub compression_size_percent: Option<i32>,
+    pub stop_style: Option<UniversalCompactionStopStyle>,
+}
+
+impl UniversalCompactionConfig {
+    fn check(&self, path: &str) -> Result<(), Error> {
+        if let Some(width) = self.min_merge_width {
+            if width < 2 {
+                return Err(invalid(path, "min_merge_width", "must be at least 2"));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_merge_width, self.max_merge_width) {
+            if max < min {
+                return Err(invalid(
+                    path,
+                    "max_merge_width",
+                    "must not be less than min_merge_width",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn build(&self) -> UniversalCompactOptions {
+        let mut opts = UniversalCompactOptions::default();
+        if let Some(ratio) = self.size_ratio {
+            opts.set_size_ratio(ratio);
+        }
+        if let Some(width) = self.min_merge_width {
+            opts.set_min_merge_width(width);
+        }
+        if let Some(width) = self.max_merge_width {
+            opts.set_max_merge_width(width);
+        }
+        if let Some(percent) = self.max_size_amplification_percent {
+            opts.set_max_size_amplification_percent(percent);
+        }
+        if let Some(percent) = self.compression_size_percent {
+            opts.set_compression_size_percent(percent);
+        }
+        if let Some(style) = self.stop_style {
+            opts.set_stop_style(style);
+        }
+        opts
+    }
+}
+
+/// See `FifoCompactOptions`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FifoCompactionConfig {
+    pub max_table_files_size: u64,
+}
+
+/// The filter policy of the block-based tables.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum FilterConfig {
+    /// See `BlockBasedOptions::set_bloom_filter`
+    Bloom {
+        bits_per_key: f64,
+        #[serde(default)]
+        block_based: bool,
+    },
+    /// See `BlockBasedOptions::set_ribbon_filter`
+    Ribbon { bits_per_key: f64 },
+    /// See `BlockBasedOptions::set_hybrid_ribbon_filter`
+    HybridRibbon {
+        bits_per_key: f64,
+        bloom_before_level: i32,
+    },
+}
+
+options_config! {
+    /// The options of the block-based tables, see `BlockBasedOptions`.
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct BlockBasedConfig for BlockBasedOptions {
+        /// Name of the cache in `caches` to use as block cache
+        pub block_cache: Option<String>,
+        /// Name of the cache in `caches` to use as compressed block cache
+        pub block_cache_compressed: Option<String>,
+        /// See `BlockBasedOptions::disable_cache`
+        pub disable_cache: bool,
+        /// The filter policy, none by default
+        pub filter: Option<FilterConfig>,
+    }
+    by_value {
+        block_size: usize => set_block_size,
+        metadata_block_size: usize => set_metadata_block_size,
+        partition_filters: bool => set_partition_filters,
+        cache_index_and_filter_blocks: bool => set_cache_index_and_filter_blocks,
+        index_type: BlockBasedIndexType => set_index_type,
+        pin_l0_filter_and_index_blocks_in_cache: bool =>
+            set_pin_l0_filter_and_index_blocks_in_cache,
+        pin_top_level_index_and_filter: bool => set_pin_top_level_index_and_filter,
+        format_version: i32 => set_format_version,
+        block_restart_interval: i32 => set_block_restart_interval,
+        index_block_restart_interval: i32 => set_index_block_restart_interval,
+        data_block_index_type: DataBlockIndexType => set_data_block_index_type,
+        data_block_hash_ratio: f64 => set_data_block_hash_ratio,
+        whole_key_filtering: bool => set_whole_key_filtering,
+    }
+    by_ref {}
+}
+
+impl BlockBasedConfig {
+    fn build(
+        &self,
+        caches: &BTreeMap<&str, Cache>,
+        path: &str,
+    ) -> Result<BlockBasedOptions, Error> {
+        if self.block_size == Some(0) {
+            return Err(invalid(path, "block_size", "must be positive"));
+        }
+        check_ratio(
+            path,
+            "data_block_hash_ratio",
+            self.data_block_hash_ratio,
+            1.0,
+        )?;
+        if self.disable_cache && self.block_cache.is_some() {
+            return Err(invalid(
+                path,
+                "block_cache",
+                "cannot be set with disable_cache",
+            ));
+        }
+        let bits_per_key = match self.filter {
+            Some(FilterConfig::Bloom { bits_per_key, .. })
+            | Some(FilterConfig::Ribbon { bits_per_key })
+            | Some(FilterConfig::HybridRibbon { bits_per_key, .. }) => Some(bits_per_key),
+            None => None,
+        };
+        if let Some(bits_per_key) = bits_per_key {
+            if bits_per_key <= 0.0 {
+                return Err(invalid(path, "filter.bits_per_key", "must be positive"));
+            }
+        }
+
+        let mut opts = BlockBasedOptions::default();
+        self.apply_table(&mut opts);
+        if let Some(name) = &self.block_cache {
+            opts.set_block_cache(&find_cache(caches, path, "block_cache", name)?);
+        }
+        if let Some(name) = &self.block_cache_compressed {
+            opts.set_block_cache_compressed(&find_cache(
+                caches,
+                path,
+                "block_cache_compressed",
+                name,
+            )?);
+        }
+        if self.disable_cache {
+            opts.disable_cache();
+        }
+        match self.filter {
+            Some(FilterConfig::Bloom {
+                bits_per_key,
+                block_based,
+            }) => opts.set_bloom_filter(bits_per_key, block_based),
+            Some(FilterConfig::Ribbon { bits_per_key }) => opts.set_ribbon_filter(bits_per_key),
+            Some(FilterConfig::HybridRibbon {
+                bits_per_key,
+                bloom_before_level,
+            }) => opts.set_hybrid_ribbon_filter(bits_per_key, bloom_before_level),
+            None => {}
+        }
+        Ok(opts)
+    }
+}
@@ -50,6 +50,7 @@ impl Handle<ffi::rocksdb_t> for DB {
 
 impl ops::Open for DB {}
 impl ops::OpenCF for DB {}
+impl ops::OpenWithLatestOptions for DB {}
 
 impl OpenRaw for DB {
     type Pointer = ffi::rocksdb_t;
@@ -186,6 +187,7 @@ impl Iterate for DB {
             DBRawIterator {
                 inner: ffi::rocksdb_create_iterator(self.inner, readopts.handle()),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             }
         }
     }
@@ -205,6 +207,7 @@ impl IterateCF for DB {
                     cf_handle.inner,
                 ),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             })
         }
     }
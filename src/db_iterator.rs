@@ -13,6 +13,7 @@
 // limitations under the License.
 //
 
+use crate::db_options::ReadTimestamps;
 use crate::ops::Iterate;
 use libc::{c_char, c_uchar, size_t};
 use std::marker::PhantomData;
@@ -75,6 +76,7 @@ unsafe impl<'a> Sync for DBRawIterator<'a> {}
 pub struct DBRawIterator<'a> {
     pub(crate) inner: *mut ffi::rocksdb_iterator_t,
     pub(crate) db: PhantomData<&'a dyn Iterate>,
+    pub(crate) _timestamps: ReadTimestamps,
 }
 
 /// An iterator over a database or column family, with specifiable
@@ -353,6 +355,25 @@ impl<'a> DBRawIterator<'a> {
             None
         }
     }
+
+    /// Returns a slice of the user-defined timestamp of the current entry.
+    ///
+    /// Only meaningful when the column family comparator uses timestamps and
+    /// the iterator was created with `ReadOptions::set_timestamp`.
+    pub fn timestamp(&self) -> Option<&[u8]> {
+        if self.valid() {
+            // Safety Note: This is safe as all methods that may invalidate the buffer returned
+            // take `&mut self`, so borrow checker will prevent use of buffer after seek.
+            unsafe {
+                let mut ts_len: size_t = 0;
+                let ts_ptr = ffi::rocksdb_iter_timestamp(self.inner, &mut ts_len) as *const c_uchar;
+
+                Some(slice::from_raw_parts(ts_ptr, ts_len as usize))
+            }
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a> Drop for DBRawIterator<'a> {
@@ -390,6 +411,19 @@ impl<'a> DBIterator<'a> {
     pub fn valid(&self) -> bool {
         self.raw.valid()
     }
+
+    /// Returns the user-defined timestamp of the entry last returned by
+    /// `next`, or `None` if there is none since the last seek.
+    ///
+    /// Only meaningful when the column family comparator uses timestamps and
+    /// the iterator was created with `ReadOptions::set_timestamp`.
+    pub fn timestamp(&self) -> Option<&[u8]> {
+        if self.just_seeked {
+            None
+        } else {
+            self.raw.timestamp()
+        }
+    }
 }
 
 impl<'a> Iterator for DBIterator<'a> {
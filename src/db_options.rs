@@ -18,15 +18,23 @@ use crate::{
 };
 
 use std::ffi::{CStr, CString};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::ptr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use libc::{self, c_char, c_double, c_int, c_uchar, c_uint, c_void, size_t};
 
 use crate::compaction_filter::{self, CompactionFilterCallback, CompactionFilterFn};
 use crate::compaction_filter_factory::{self, CompactionFilterFactory};
-use crate::comparator::{self, ComparatorCallback, CompareFn};
+use crate::comparator::{
+    self, BuiltinComparator, ComparatorCallback, CompareFn, ShortSuccessorFn, ShortestSeparatorFn,
+};
+use crate::event_listener::{self, EventListener};
 use crate::ffi;
+use crate::ffi_util::{error_message, take_string, to_cstring};
+use crate::file_system::{self, FileSystem};
+use crate::logger;
 use crate::merge_operator::{
     self, full_merge_callback, partial_merge_callback, MergeFn, MergeOperatorCallback,
 };
@@ -54,7 +62,42 @@ pub struct Cache(pub(crate) Arc<CacheWrapper>);
 impl Cache {
     /// Create a lru cache with capacity
     pub fn new_lru_cache(capacity: size_t) -> Result<Cache, Error> {
-        let cache = new_cache(capacity);
+        Cache::from_raw(new_cache(capacity))
+    }
+
+    /// Creates a lru cache configured by `opts`.
+    pub fn new_lru_cache_opts(opts: &LruCacheOptions) -> Result<Cache, Error> {
+        Cache::from_raw(unsafe { ffi::rocksdb_cache_create_lru_opts(opts.inner) })
+    }
+
+    /// Creates a clock cache with capacity.
+    ///
+    /// The cache is split into `2^num_shard_bits` shards, `-1` picks the
+    /// number automatically. Clock cache support needs RocksDB to be built
+    /// with Intel TBB; otherwise, as with the bundled build, an error is
+    /// returned.
+    pub fn new_clock_cache(
+        capacity: size_t,
+        num_shard_bits: c_int,
+        strict_capacity_limit: bool,
+    ) -> Result<Cache, Error> {
+        let cache = unsafe {
+            ffi::rocksdb_cache_create_clock(
+                capacity,
+                num_shard_bits,
+                strict_capacity_limit as c_uchar,
+            )
+        };
+        if cache.is_null() {
+            Err(Error::new(
+                "Clock cache is not supported by this build of RocksDB".to_owned(),
+            ))
+        } else {
+            Ok(Cache(Arc::new(CacheWrapper { inner: cache })))
+        }
+    }
+
+    fn from_raw(cache: *mut ffi::rocksdb_cache_t) -> Result<Cache, Error> {
         if cache.is_null() {
             Err(Error::new("Could not create Cache".to_owned()))
         } else {
@@ -62,6 +105,61 @@ impl Cache {
         }
     }
 
+    /// Returns the cache capacity
+    pub fn get_capacity(&self) -> usize {
+        unsafe { ffi::rocksdb_cache_get_capacity(self.0.inner) }
+    }
+
+    /// Sets whether inserts fail, instead of exceeding the capacity, when
+    /// the cache is full of pinned entries
+    pub fn set_strict_capacity_limit(&mut self, strict_capacity_limit: bool) {
+        unsafe {
+            ffi::rocksdb_cache_set_strict_capacity_limit(
+                self.0.inner,
+                strict_capacity_limit as c_uchar,
+            );
+        }
+    }
+
+    /// Returns whether the capacity limit is strict
+    pub fn has_strict_capacity_limit(&self) -> bool {
+        unsafe { ffi::rocksdb_cache_has_strict_capacity_limit(self.0.inner) != 0 }
+    }
+
+    /// Returns the number and total charge of the entries of each role,
+    /// for every `CacheEntryRole`.
+    ///
+    /// Entries are attributed like in the `rocksdb.block-cache-entry-stats`
+    /// property. This walks the whole cache, so it shouldn't be called too
+    /// often on large caches.
+    pub fn get_entry_stats(&self) -> Vec<CacheEntryStats> {
+        let mut entries = [0u64; CacheEntryRole::ALL.len()];
+        let mut charges = [0u64; CacheEntryRole::ALL.len()];
+        let num_roles = unsafe {
+            ffi::rocksdb_cache_get_entry_stats(
+                self.0.inner,
+                entries.as_mut_ptr(),
+                charges.as_mut_ptr(),
+                CacheEntryRole::ALL.len(),
+            )
+        };
+        // A new role in RocksDB would shift the ones after it.
+        assert_eq!(
+            num_roles,
+            CacheEntryRole::ALL.len(),
+            "RocksDB has a different number of cache entry roles"
+        );
+        CacheEntryRole::ALL
+            .iter()
+            .zip(entries.iter().zip(charges.iter()))
+            .map(|(&role, (&entries, &charge))| CacheEntryStats {
+                role,
+                entries,
+                charge,
+            })
+            .collect()
+    }
+
     /// Returns the Cache memory usage
     pub fn get_usage(&self) -> usize {
         unsafe { ffi::rocksdb_cache_get_usage(self.0.inner) }
@@ -80,6 +178,507 @@ impl Cache {
     }
 }
 
+/// The kind of data a cache entry holds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CacheEntryRole {
+    /// Block-based table data block
+    DataBlock,
+    /// Block-based table filter block, full or partitioned
+    FilterBlock,
+    /// Block-based table metadata block for a partitioned filter
+    FilterMetaBlock,
+    /// Block-based table deprecated block-based filter block
+    DeprecatedFilterBlock,
+    /// Block-based table index block
+    IndexBlock,
+    /// Other kinds of block-based table block
+    OtherBlock,
+    /// `WriteBufferManager` reservations for the memory of the memtables
+    WriteBuffer,
+    /// Reservations for the buffers used to build compression dictionaries
+    CompressionDictionaryBuildingBuffer,
+    /// Reservations for the memory used to build filters
+    FilterConstruction,
+    /// Anything else, including the entries of a row cache
+    Misc,
+}
+
+impl CacheEntryRole {
+    /// Every role, in the order RocksDB numbers them.
+    pub const ALL: [CacheEntryRole; 10] = [
+        CacheEntryRole::DataBlock,
+        CacheEntryRole::FilterBlock,
+        CacheEntryRole::FilterMetaBlock,
+        CacheEntryRole::DeprecatedFilterBlock,
+        CacheEntryRole::IndexBlock,
+        CacheEntryRole::OtherBlock,
+        CacheEntryRole::WriteBuffer,
+        CacheEntryRole::CompressionDictionaryBuildingBuffer,
+        CacheEntryRole::FilterConstruction,
+        CacheEntryRole::Misc,
+    ];
+}
+
+/// The entries of a cache with a given role, see `Cache::get_entry_stats`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CacheEntryStats {
+    pub role: CacheEntryRole,
+    /// Number of entries
+    pub entries: u64,
+    /// Total charge of the entries, in bytes
+    pub charge: u64,
+}
+
+/// Options to create a lru cache with `Cache::new_lru_cache_opts`.
+///
+/// # Examples
+///
+/// ```
+/// use ckb_rocksdb::{Cache, LruCacheOptions};
+///
+/// let mut opts = LruCacheOptions::default();
+/// opts.set_capacity(512 * 1024 * 1024);
+/// opts.set_num_shard_bits(6);
+/// opts.set_high_pri_pool_ratio(0.2);
+/// let cache = Cache::new_lru_cache_opts(&opts).unwrap();
+/// ```
+pub struct LruCacheOptions {
+    inner: *mut ffi::rocksdb_lru_cache_options_t,
+}
+
+impl LruCacheOptions {
+    /// Sets the capacity of the cache.
+    ///
+    /// Default: 0
+    pub fn set_capacity(&mut self, capacity: size_t) {
+        unsafe {
+            ffi::rocksdb_lru_cache_options_set_capacity(self.inner, capacity);
+        }
+    }
+
+    /// The cache is split into `2^num_shard_bits` shards, by hash of the key.
+    /// `-1` picks the number automatically from the capacity.
+    ///
+    /// Default: -1
+    pub fn set_num_shard_bits(&mut self, num_shard_bits: c_int) {
+        unsafe {
+            ffi::rocksdb_lru_cache_options_set_num_shard_bits(self.inner, num_shard_bits);
+        }
+    }
+
+    /// If set, inserts fail, instead of exceeding the capacity, when the
+    /// cache is full of pinned entries.
+    ///
+    /// Default: false
+    pub fn set_strict_capacity_limit(&mut self, strict_capacity_limit: bool) {
+        unsafe {
+            ffi::rocksdb_lru_cache_options_set_strict_capacity_limit(
+                self.inner,
+                strict_capacity_limit as c_uchar,
+            );
+        }
+    }
+
+    /// Ratio of the capacity reserved for high priority entries, such as
+    /// the index and filter blocks with
+    /// `BlockBasedOptions::set_cache_index_and_filter_blocks_with_high_priority`.
+    ///
+    /// Default: 0.5
+    pub fn set_high_pri_pool_ratio(&mut self, high_pri_pool_ratio: c_double) {
+        unsafe {
+            ffi::rocksdb_lru_cache_options_set_high_pri_pool_ratio(self.inner, high_pri_pool_ratio);
+        }
+    }
+
+    /// Allocates the memory of the cached blocks with `allocator` instead of
+    /// the system allocator.
+    pub fn set_memory_allocator(&mut self, allocator: &MemoryAllocator) {
+        unsafe {
+            ffi::rocksdb_lru_cache_options_set_memory_allocator(self.inner, allocator.inner);
+        }
+    }
+}
+
+impl Default for LruCacheOptions {
+    fn default() -> LruCacheOptions {
+        let inner = unsafe { ffi::rocksdb_lru_cache_options_create() };
+        assert!(
+            !inner.is_null(),
+            "Could not create RocksDB lru cache options"
+        );
+        LruCacheOptions { inner }
+    }
+}
+
+impl Drop for LruCacheOptions {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_lru_cache_options_destroy(self.inner);
+        }
+    }
+}
+
+/// A memory allocator for the blocks of a cache, see
+/// `LruCacheOptions::set_memory_allocator`.
+pub struct MemoryAllocator {
+    inner: *mut ffi::rocksdb_memory_allocator_t,
+}
+
+impl MemoryAllocator {
+    /// Creates a jemalloc allocator which excludes the cached blocks from
+    /// core dumps.
+    ///
+    /// Returns an error if RocksDB wasn't built with jemalloc.
+    pub fn jemalloc_nodump() -> Result<MemoryAllocator, Error> {
+        let mut err: *mut c_char = ptr::null_mut();
+        let inner = unsafe { ffi::rocksdb_jemalloc_nodump_allocator_create(&mut err) };
+        // The allocator is returned even when its creation failed.
+        let allocator = MemoryAllocator { inner };
+        if err.is_null() {
+            Ok(allocator)
+        } else {
+            Err(Error::new(error_message(err)))
+        }
+    }
+}
+
+impl Drop for MemoryAllocator {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_memory_allocator_destroy(self.inner);
+        }
+    }
+}
+
+pub(crate) struct RateLimiterWrapper {
+    inner: *mut ffi::rocksdb_ratelimiter_t,
+}
+
+impl Drop for RateLimiterWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_ratelimiter_destroy(self.inner);
+        }
+    }
+}
+
+/// Which I/O a `RateLimiter` throttles.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(i32)]
+pub enum RateLimiterMode {
+    ReadsOnly = 0,
+    WritesOnly,
+    AllIo,
+}
+
+/// Limits the rate of the I/O of flushes and compactions.
+///
+/// A rate limiter can be shared by several databases through
+/// `Options::set_rate_limiter`, to give them a common I/O budget, and its
+/// rate can be changed while they are open.
+#[derive(Clone)]
+pub struct RateLimiter(Arc<RateLimiterWrapper>);
+
+impl RateLimiter {
+    /// Creates a rate limiter for writes, allowing `rate_bytes_per_sec`.
+    ///
+    /// `refill_period_us` controls how often tokens are refilled, and
+    /// `fairness` how often low priority requests (compactions) get a chance
+    /// to run before high priority ones (flushes); see
+    /// `Options::set_ratelimiter`.
+    pub fn new(
+        rate_bytes_per_sec: i64,
+        refill_period_us: i64,
+        fairness: i32,
+    ) -> Result<RateLimiter, Error> {
+        RateLimiter::new_with_mode(
+            rate_bytes_per_sec,
+            refill_period_us,
+            fairness,
+            RateLimiterMode::WritesOnly,
+            false,
+        )
+    }
+
+    /// Creates a rate limiter for the I/O selected by `mode`.
+    ///
+    /// If `auto_tuned` is set, `rate_bytes_per_sec` is only an upper bound
+    /// and the actual rate is adjusted between a twentieth of it and the
+    /// bound, according to the recent demand for background I/O.
+    pub fn new_with_mode(
+        rate_bytes_per_sec: i64,
+        refill_period_us: i64,
+        fairness: i32,
+        mode: RateLimiterMode,
+        auto_tuned: bool,
+    ) -> Result<RateLimiter, Error> {
+        let limiter = unsafe {
+            ffi::rocksdb_ratelimiter_create_with_mode(
+                rate_bytes_per_sec,
+                refill_period_us,
+                fairness,
+                mode as c_int,
+                auto_tuned as c_uchar,
+            )
+        };
+        if limiter.is_null() {
+            Err(Error::new("Could not create RateLimiter".to_owned()))
+        } else {
+            Ok(RateLimiter(Arc::new(RateLimiterWrapper { inner: limiter })))
+        }
+    }
+
+    /// Changes the rate, taking effect for every database using the limiter.
+    pub fn set_bytes_per_second(&self, bytes_per_second: i64) {
+        unsafe {
+            ffi::rocksdb_ratelimiter_set_bytes_per_second(self.0.inner, bytes_per_second);
+        }
+    }
+
+    pub fn get_bytes_per_second(&self) -> i64 {
+        unsafe { ffi::rocksdb_ratelimiter_get_bytes_per_second(self.0.inner) }
+    }
+
+    /// Returns the maximal number of bytes granted by a single request.
+    pub fn get_single_burst_bytes(&self) -> i64 {
+        unsafe { ffi::rocksdb_ratelimiter_get_single_burst_bytes(self.0.inner) }
+    }
+
+    /// Returns the number of bytes which went through the limiter.
+    pub fn get_total_bytes_through(&self) -> i64 {
+        unsafe { ffi::rocksdb_ratelimiter_get_total_bytes_through(self.0.inner) }
+    }
+
+    /// Returns the number of requests which went through the limiter.
+    pub fn get_total_requests(&self) -> i64 {
+        unsafe { ffi::rocksdb_ratelimiter_get_total_requests(self.0.inner) }
+    }
+}
+
+pub(crate) struct SstFileManagerWrapper {
+    inner: *mut ffi::rocksdb_sstfilemanager_t,
+    // The manager keeps a raw pointer to the Env.
+    _env: Env,
+}
+
+impl Drop for SstFileManagerWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_sstfilemanager_destroy(self.inner);
+        }
+    }
+}
+
+/// Tracks the SST files of the databases it is attached to, through
+/// `Options::set_sst_file_manager`, and controls how they are deleted.
+///
+/// Once the total size of the tracked files reaches the maximal allowed
+/// space, flushes and compactions fail and the database stops accepting
+/// writes. Deletions of obsolete files can be rate limited to avoid I/O
+/// spikes.
+#[derive(Clone)]
+pub struct SstFileManager(Arc<SstFileManagerWrapper>);
+
+impl SstFileManager {
+    /// Creates a manager which accesses the files through `env`.
+    pub fn new(env: &Env) -> Result<SstFileManager, Error> {
+        let manager = unsafe { ffi_try!(ffi::rocksdb_sstfilemanager_create(env.0.inner)) };
+        if manager.is_null() {
+            Err(Error::new("Could not create SstFileManager".to_owned()))
+        } else {
+            Ok(SstFileManager(Arc::new(SstFileManagerWrapper {
+                inner: manager,
+                _env: env.clone(),
+            })))
+        }
+    }
+
+    /// Sets the maximal size, in bytes, the tracked SST files may use.
+    ///
+    /// When it is reached, flushes and compactions fail with a background
+    /// error and writes are stopped. `0` disables the limit, which is the
+    /// default.
+    pub fn set_max_allowed_space_usage(&self, max_allowed_space: u64) {
+        unsafe {
+            ffi::rocksdb_sstfilemanager_set_max_allowed_space_usage(
+                self.0.inner,
+                max_allowed_space,
+            );
+        }
+    }
+
+    /// Sets the space, in bytes, kept free for compactions.
+    ///
+    /// A compaction is not started if the space it may need, plus this
+    /// buffer, would exceed the maximal allowed space.
+    pub fn set_compaction_buffer_size(&self, compaction_buffer_size: u64) {
+        unsafe {
+            ffi::rocksdb_sstfilemanager_set_compaction_buffer_size(
+                self.0.inner,
+                compaction_buffer_size,
+            );
+        }
+    }
+
+    /// Returns true if the total size of the tracked files reached the
+    /// maximal allowed space.
+    pub fn is_max_allowed_space_reached(&self) -> bool {
+        unsafe { ffi::rocksdb_sstfilemanager_is_max_allowed_space_reached(self.0.inner) != 0 }
+    }
+
+    /// Like `is_max_allowed_space_reached`, also counting the space
+    /// reserved by running compactions.
+    pub fn is_max_allowed_space_reached_including_compactions(&self) -> bool {
+        unsafe {
+            ffi::rocksdb_sstfilemanager_is_max_allowed_space_reached_including_compactions(
+                self.0.inner,
+            ) != 0
+        }
+    }
+
+    /// Returns the total size, in bytes, of the tracked files.
+    pub fn get_total_size(&self) -> u64 {
+        unsafe { ffi::rocksdb_sstfilemanager_get_total_size(self.0.inner) }
+    }
+
+    /// Sets the rate, in bytes per second, at which obsolete files are
+    /// deleted. `0` deletes them immediately, which is the default.
+    pub fn set_delete_rate_bytes_per_second(&self, delete_rate: i64) {
+        unsafe {
+            ffi::rocksdb_sstfilemanager_set_delete_rate_bytes_per_second(self.0.inner, delete_rate);
+        }
+    }
+
+    /// Returns the rate at which obsolete files are deleted.
+    pub fn get_delete_rate_bytes_per_second(&self) -> i64 {
+        unsafe { ffi::rocksdb_sstfilemanager_get_delete_rate_bytes_per_second(self.0.inner) }
+    }
+
+    /// Sets the ratio of pending deletions to live data above which files
+    /// are deleted immediately, despite the delete rate. Defaults to `0.25`.
+    pub fn set_max_trash_db_ratio(&self, ratio: f64) {
+        unsafe {
+            ffi::rocksdb_sstfilemanager_set_max_trash_db_ratio(self.0.inner, ratio);
+        }
+    }
+
+    /// Returns the ratio set by `set_max_trash_db_ratio`.
+    pub fn get_max_trash_db_ratio(&self) -> f64 {
+        unsafe { ffi::rocksdb_sstfilemanager_get_max_trash_db_ratio(self.0.inner) }
+    }
+
+    /// Returns the total size, in bytes, of the files waiting to be deleted.
+    pub fn get_total_trash_size(&self) -> u64 {
+        unsafe { ffi::rocksdb_sstfilemanager_get_total_trash_size(self.0.inner) }
+    }
+}
+
+pub(crate) struct WriteBufferManagerWrapper {
+    inner: *mut ffi::rocksdb_write_buffer_manager_t,
+}
+
+impl Drop for WriteBufferManagerWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_write_buffer_manager_destroy(self.inner);
+        }
+    }
+}
+
+/// Limits the memory used by the memtables of all the databases it is
+/// attached to, through `Options::set_write_buffer_manager`.
+///
+/// Unlike `Options::set_db_write_buffer_size`, which limits each database on
+/// its own, the limit is shared: once the memtables of all the databases
+/// together exceed it, the databases flush. The memory can also be charged
+/// to a `Cache`, so that memtables and cached blocks share one budget.
+#[derive(Clone)]
+pub struct WriteBufferManager(Arc<WriteBufferManagerWrapper>);
+
+impl WriteBufferManager {
+    /// Creates a manager limiting the memtables to `buffer_size` bytes;
+    /// `0` disables the limit.
+    ///
+    /// If `allow_stall` is set, writes are stalled while the memory usage
+    /// exceeds `buffer_size`, until flushes bring it back below.
+    pub fn new(buffer_size: usize, allow_stall: bool) -> Result<WriteBufferManager, Error> {
+        let wbm = unsafe {
+            ffi::rocksdb_write_buffer_manager_create(buffer_size, allow_stall as c_uchar)
+        };
+        WriteBufferManager::from_raw(wbm)
+    }
+
+    /// Creates a manager like `new`, which also charges the memory of the
+    /// memtables to `cache`, by inserting dummy entries into it.
+    ///
+    /// The charging happens even if `buffer_size` is `0`.
+    pub fn new_with_cache(
+        buffer_size: usize,
+        cache: &Cache,
+        allow_stall: bool,
+    ) -> Result<WriteBufferManager, Error> {
+        let wbm = unsafe {
+            ffi::rocksdb_write_buffer_manager_create_with_cache(
+                buffer_size,
+                cache.0.inner,
+                allow_stall as c_uchar,
+            )
+        };
+        WriteBufferManager::from_raw(wbm)
+    }
+
+    fn from_raw(
+        wbm: *mut ffi::rocksdb_write_buffer_manager_t,
+    ) -> Result<WriteBufferManager, Error> {
+        if wbm.is_null() {
+            Err(Error::new("Could not create WriteBufferManager".to_owned()))
+        } else {
+            Ok(WriteBufferManager(Arc::new(WriteBufferManagerWrapper {
+                inner: wbm,
+            })))
+        }
+    }
+
+    /// Returns true if the memory of the memtables is limited.
+    pub fn enabled(&self) -> bool {
+        unsafe { ffi::rocksdb_write_buffer_manager_enabled(self.0.inner) != 0 }
+    }
+
+    /// Returns true if the memory of the memtables is charged to a cache.
+    pub fn cost_to_cache(&self) -> bool {
+        unsafe { ffi::rocksdb_write_buffer_manager_cost_to_cache(self.0.inner) != 0 }
+    }
+
+    /// Returns the memory used by all the memtables. Only tracked if the
+    /// manager is enabled.
+    pub fn get_usage(&self) -> usize {
+        unsafe { ffi::rocksdb_write_buffer_manager_memory_usage(self.0.inner) }
+    }
+
+    /// Returns the memory used by the active memtables.
+    pub fn get_mutable_memtable_usage(&self) -> usize {
+        unsafe { ffi::rocksdb_write_buffer_manager_mutable_memtable_memory_usage(self.0.inner) }
+    }
+
+    /// Returns the memory charged to the cache.
+    pub fn get_dummy_entries_in_cache_usage(&self) -> usize {
+        unsafe { ffi::rocksdb_write_buffer_manager_dummy_entries_in_cache_usage(self.0.inner) }
+    }
+
+    /// Returns the memory limit.
+    pub fn get_buffer_size(&self) -> usize {
+        unsafe { ffi::rocksdb_write_buffer_manager_buffer_size(self.0.inner) }
+    }
+
+    /// Changes the memory limit, taking effect for every database using the
+    /// manager.
+    pub fn set_buffer_size(&self, buffer_size: usize) {
+        unsafe {
+            ffi::rocksdb_write_buffer_manager_set_buffer_size(self.0.inner, buffer_size);
+        }
+    }
+}
+
 /// An Env is an interface used by the rocksdb implementation to access
 /// operating system functionality like the filesystem etc.  Callers
 /// may wish to provide a custom Env object when opening a database to
@@ -91,16 +690,22 @@ impl Cache {
 /// Note: currently, C API behinds C++ API for various settings.
 /// See also: `rocksdb/include/env.h`
 #[derive(Clone)]
-pub struct Env(Arc<EnvWrapper>);
+pub struct Env(pub(crate) Arc<EnvWrapper>);
 
 pub(crate) struct EnvWrapper {
-    inner: *mut ffi::rocksdb_env_t,
+    pub(crate) inner: *mut ffi::rocksdb_env_t,
+    // The clock of an environment created by `mock_clock_env`, null for
+    // any other one.
+    mock_clock: *mut ffi::rocksdb_mock_clock_t,
 }
 
 impl Drop for EnvWrapper {
     fn drop(&mut self) {
         unsafe {
             ffi::rocksdb_env_destroy(self.inner);
+            if !self.mock_clock.is_null() {
+                ffi::rocksdb_mock_clock_destroy(self.mock_clock);
+            }
         }
     }
 }
@@ -112,7 +717,10 @@ impl Env {
         if env.is_null() {
             Err(Error::new("Could not create mem env".to_owned()))
         } else {
-            Ok(Self(Arc::new(EnvWrapper { inner: env })))
+            Ok(Self(Arc::new(EnvWrapper {
+                inner: env,
+                mock_clock: ptr::null_mut(),
+            })))
         }
     }
 
@@ -123,7 +731,81 @@ impl Env {
         if env.is_null() {
             Err(Error::new("Could not create mem env".to_owned()))
         } else {
-            Ok(Self(Arc::new(EnvWrapper { inner: env })))
+            Ok(Self(Arc::new(EnvWrapper {
+                inner: env,
+                mock_clock: ptr::null_mut(),
+            })))
+        }
+    }
+
+    /// Returns a new environment like the default one, except that its clock
+    /// stands still at the time it was created and only moves when
+    /// [`advance`](#method.advance) is called.
+    ///
+    /// RocksDB reads this clock for the expiry of `DBWithTTL` entries and the
+    /// age of files for periodic compaction and `ttl`, which is checked
+    /// whenever a flush or compaction installs new files. It does not drive
+    /// timers: the statistics dumps of `set_stats_dump_period_sec` run on a
+    /// scheduler shared by all databases in the process which always uses the
+    /// real time. The WAL TTL compares this clock with the modification times
+    /// of the files, which come from the file system.
+    pub fn mock_clock_env() -> Result<Self, Error> {
+        unsafe {
+            let mock_clock = ffi::rocksdb_mock_clock_create();
+            let env = ffi::rocksdb_create_mock_clock_env(mock_clock);
+            if env.is_null() {
+                ffi::rocksdb_mock_clock_destroy(mock_clock);
+                Err(Error::new("Could not create mock clock env".to_owned()))
+            } else {
+                Ok(Self(Arc::new(EnvWrapper {
+                    inner: env,
+                    mock_clock,
+                })))
+            }
+        }
+    }
+
+    /// Moves the clock of an environment created by `mock_clock_env` forward
+    /// by `duration`.
+    ///
+    /// Fails for any other environment.
+    pub fn advance(&self, duration: Duration) -> Result<(), Error> {
+        if self.0.mock_clock.is_null() {
+            return Err(Error::new("Env has no mock clock".to_owned()));
+        }
+        unsafe {
+            ffi::rocksdb_mock_clock_advance(self.0.mock_clock, duration.as_micros() as u64);
+        }
+        Ok(())
+    }
+
+    /// Returns a new environment doing its file and directory operations
+    /// through `file_system`, and delegating everything else, like threads
+    /// and time, to the default environment.
+    pub fn from_file_system<F>(file_system: F) -> Result<Self, Error>
+    where
+        F: FileSystem + 'static,
+    {
+        let name = to_cstring(
+            file_system.name(),
+            "Failed to convert file system name to CString",
+        )?;
+        let callbacks = file_system::callbacks::<F>();
+        let env = unsafe {
+            ffi::rocksdb_create_filesystem_env(
+                Box::into_raw(Box::new(file_system)).cast::<c_void>(),
+                Some(file_system::destructor_callback::<F>),
+                name.as_ptr(),
+                &callbacks,
+            )
+        };
+        if env.is_null() {
+            Err(Error::new("Could not create file system env".to_owned()))
+        } else {
+            Ok(Self(Arc::new(EnvWrapper {
+                inner: env,
+                mock_clock: ptr::null_mut(),
+            })))
         }
     }
 
@@ -205,6 +887,7 @@ impl Env {
 pub struct OptionsMustOutliveDB {
     pub(crate) env: Option<Env>,
     pub(crate) row_cache: Option<Cache>,
+    pub(crate) sst_file_manager: Option<SstFileManager>,
     pub(crate) block_based: Option<BlockBasedOptionsMustOutliveDB>,
 }
 
@@ -213,6 +896,7 @@ impl OptionsMustOutliveDB {
         Self {
             env: self.env.as_ref().map(Env::clone),
             row_cache: self.row_cache.as_ref().map(Cache::clone),
+            sst_file_manager: self.sst_file_manager.as_ref().map(SstFileManager::clone),
             block_based: self
                 .block_based
                 .as_ref()
@@ -344,9 +1028,39 @@ pub struct ReadOptions {
     option_set_prefix_same_as_start: Option<bool>,
     option_set_total_order_seek: Option<bool>,
     option_set_readahead_size: Option<usize>,
+    option_set_timestamp: Option<Arc<ReadTimestamp>>,
+    option_set_iter_start_ts: Option<Arc<ReadTimestamp>>,
     inner: *mut ffi::rocksdb_readoptions_t,
 }
 
+/// A timestamp set on `ReadOptions`. RocksDB only keeps a pointer to it, so
+/// the iterators created with the options share it to keep it alive.
+pub(crate) struct ReadTimestamp {
+    inner: *mut ffi::rocksdb_timestamp_t,
+}
+
+/// The timestamps an iterator was created with, see `ReadTimestamp`.
+pub(crate) type ReadTimestamps = [Option<Arc<ReadTimestamp>>; 2];
+
+impl ReadTimestamp {
+    fn new(ts: &[u8]) -> Arc<ReadTimestamp> {
+        let inner =
+            unsafe { ffi::rocksdb_timestamp_create(ts.as_ptr() as *const c_char, ts.len()) };
+        Arc::new(ReadTimestamp { inner })
+    }
+}
+
+impl Drop for ReadTimestamp {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_timestamp_destroy(self.inner);
+        }
+    }
+}
+
+unsafe impl Send for ReadTimestamp {}
+unsafe impl Sync for ReadTimestamp {}
+
 /// Configuration of cuckoo-based storage.
 pub struct CuckooTableOptions {
     pub(crate) inner: *mut ffi::rocksdb_cuckoo_table_options_t,
@@ -390,7 +1104,12 @@ unsafe impl Send for CuckooTableOptions {}
 unsafe impl Send for ReadOptions {}
 unsafe impl Send for IngestExternalFileOptions {}
 unsafe impl Send for CacheWrapper {}
+unsafe impl Send for LruCacheOptions {}
+unsafe impl Send for MemoryAllocator {}
 unsafe impl Send for EnvWrapper {}
+unsafe impl Send for RateLimiterWrapper {}
+unsafe impl Send for SstFileManagerWrapper {}
+unsafe impl Send for WriteBufferManagerWrapper {}
 
 // Sync is similarly safe for many types because they do not expose interior mutability, and their
 // use within the rocksdb library is generally behind a const reference
@@ -401,7 +1120,12 @@ unsafe impl Sync for CuckooTableOptions {}
 unsafe impl Sync for ReadOptions {}
 unsafe impl Sync for IngestExternalFileOptions {}
 unsafe impl Sync for CacheWrapper {}
+unsafe impl Sync for LruCacheOptions {}
+unsafe impl Sync for MemoryAllocator {}
 unsafe impl Sync for EnvWrapper {}
+unsafe impl Sync for RateLimiterWrapper {}
+unsafe impl Sync for SstFileManagerWrapper {}
+unsafe impl Sync for WriteBufferManagerWrapper {}
 
 impl Drop for Options {
     fn drop(&mut self) {
@@ -745,6 +1469,41 @@ impl BlockBasedOptions {
             ffi::rocksdb_block_based_options_set_whole_key_filtering(self.inner, v as u8);
         }
     }
+
+    /// Serializes the options as a string of `name=value;` pairs.
+    pub fn to_options_string(&self) -> Result<String, Error> {
+        unsafe {
+            let value = ffi_try!(ffi::rocksdb_block_based_options_get_options_string(
+                self.inner
+            ));
+            Ok(take_string(value))
+        }
+    }
+
+    /// Creates options from `base`, overriding those set in `opts_str`, a
+    /// string of `name=value;` pairs such as `"block_size=16384;..."`.
+    pub fn from_options_string(
+        base: &BlockBasedOptions,
+        opts_str: &str,
+    ) -> Result<BlockBasedOptions, Error> {
+        let c_opts_str = to_cstring(opts_str, "Failed to convert options string to CString")?;
+        let opts = BlockBasedOptions {
+            inner: unsafe { ffi::rocksdb_block_based_options_create() },
+            outlive: base.outlive.clone(),
+        };
+        assert!(
+            !opts.inner.is_null(),
+            "Could not create RocksDB block based options"
+        );
+        unsafe {
+            ffi_try!(ffi::rocksdb_get_block_based_options_from_string(
+                base.inner,
+                c_opts_str.as_ptr(),
+                opts.inner,
+            ));
+        }
+        Ok(opts)
+    }
 }
 
 impl Default for BlockBasedOptions {
@@ -832,6 +1591,7 @@ impl Default for CuckooTableOptions {
 
 // Verbosity of the LOG.
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum LogLevel {
     Debug = 0,
@@ -842,6 +1602,20 @@ pub enum LogLevel {
     Header,
 }
 
+impl LogLevel {
+    fn from_raw(value: c_int) -> Option<Self> {
+        match value {
+            0 => Some(Self::Debug),
+            1 => Some(Self::Info),
+            2 => Some(Self::Warn),
+            3 => Some(Self::Error),
+            4 => Some(Self::Fatal),
+            5 => Some(Self::Header),
+            _ => None,
+        }
+    }
+}
+
 impl Options {
     /// By default, RocksDB uses only one background thread for flush and
     /// compaction. Calling this function will set it up such that total of
@@ -1231,6 +2005,106 @@ impl Options {
         }
     }
 
+    /// If true, values of at least `min_blob_size` bytes are written to
+    /// separate blob files by flushes and compactions, and only a reference
+    /// to them is kept in the SST files. This reduces the write
+    /// amplification of compactions for large values.
+    ///
+    /// Can be changed dynamically with `set_options`.
+    ///
+    /// Default: false
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{DBCompressionType, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_enable_blob_files(true);
+    /// opts.set_min_blob_size(4096);
+    /// opts.set_blob_compression_type(DBCompressionType::Lz4);
+    /// opts.set_enable_blob_gc(true);
+    /// ```
+    pub fn set_enable_blob_files(&mut self, val: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_enable_blob_files(self.inner, val as c_uchar);
+        }
+    }
+
+    /// Sets the size, in bytes, from which values are written to blob files,
+    /// when they are enabled.
+    ///
+    /// Can be changed dynamically with `set_options`.
+    ///
+    /// Default: 0
+    pub fn set_min_blob_size(&mut self, val: u64) {
+        unsafe {
+            ffi::rocksdb_options_set_min_blob_size(self.inner, val);
+        }
+    }
+
+    /// Sets the size, in bytes, from which a new blob file is started.
+    ///
+    /// Can be changed dynamically with `set_options`.
+    ///
+    /// Default: 256MB
+    pub fn set_blob_file_size(&mut self, val: u64) {
+        unsafe {
+            ffi::rocksdb_options_set_blob_file_size(self.inner, val);
+        }
+    }
+
+    /// Sets the compression algorithm of the values in blob files.
+    ///
+    /// Can be changed dynamically with `set_options`.
+    ///
+    /// Default: `DBCompressionType::None`
+    pub fn set_blob_compression_type(&mut self, val: DBCompressionType) {
+        unsafe {
+            ffi::rocksdb_options_set_blob_compression_type(self.inner, val as c_int);
+        }
+    }
+
+    /// If true, compactions relocate the values still in use from the oldest
+    /// blob files, as selected by `set_blob_gc_age_cutoff`, so that these
+    /// files can be deleted.
+    ///
+    /// Can be changed dynamically with `set_options`.
+    ///
+    /// Default: false
+    pub fn set_enable_blob_gc(&mut self, val: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_enable_blob_gc(self.inner, val as c_uchar);
+        }
+    }
+
+    /// Sets the fraction, between 0 and 1, of the oldest blob files which are
+    /// garbage collected when `enable_blob_gc` is set.
+    ///
+    /// Can be changed dynamically with `set_options`.
+    ///
+    /// Default: 0.25
+    pub fn set_blob_gc_age_cutoff(&mut self, val: c_double) {
+        unsafe {
+            ffi::rocksdb_options_set_blob_gc_age_cutoff(self.inner, val);
+        }
+    }
+
+    /// If the ratio of garbage in the oldest blob files, those selected by
+    /// `set_blob_gc_age_cutoff`, exceeds this threshold, compactions are
+    /// scheduled for the SST files referencing them, to force their garbage
+    /// collection. A value of 1 disables forced collection. Only supported
+    /// with level compaction.
+    ///
+    /// Can be changed dynamically with `set_options`.
+    ///
+    /// Default: 1.0
+    pub fn set_blob_gc_force_threshold(&mut self, val: c_double) {
+        unsafe {
+            ffi::rocksdb_options_set_blob_gc_force_threshold(self.inner, val);
+        }
+    }
+
     /// Allow RocksDB to pick dynamic base of bytes for levels.
     /// With this feature turned on, RocksDB will automatically adjust max bytes for each level.
     /// The goal of this feature is to have lower bound on size amplification.
@@ -1355,26 +2229,122 @@ impl Options {
         }
     }
 
+    /// Registers a listener for background events such as flushes,
+    /// compactions and write stalls.
+    ///
+    /// Several listeners may be added; they are shared by the databases and
+    /// column families opened with these options.
+    pub fn add_event_listener<L>(&mut self, listener: L)
+    where
+        L: EventListener + 'static,
+    {
+        let listener = Box::new(listener);
+
+        unsafe {
+            let el = ffi::rocksdb_eventlistener_create(
+                Box::into_raw(listener).cast::<c_void>(),
+                Some(event_listener::destructor_callback::<L>),
+                Some(event_listener::on_flush_begin_callback::<L>),
+                Some(event_listener::on_flush_completed_callback::<L>),
+                Some(event_listener::on_compaction_begin_callback::<L>),
+                Some(event_listener::on_compaction_completed_callback::<L>),
+                Some(event_listener::on_table_file_created_callback::<L>),
+                Some(event_listener::on_table_file_deleted_callback::<L>),
+                Some(event_listener::on_background_error_callback::<L>),
+                Some(event_listener::on_stall_conditions_changed_callback::<L>),
+                Some(event_listener::on_memtable_sealed_callback::<L>),
+            );
+            ffi::rocksdb_options_add_eventlistener(self.inner, el);
+        }
+    }
+
     /// Sets the comparator used to define the order of keys in the table.
     /// Default: a comparator that uses lexicographic byte-wise ordering
     ///
     /// The client must ensure that the comparator supplied here has the same
     /// name and orders keys *exactly* the same as the comparator provided to
     /// previous open calls on the same DB.
-    pub fn set_comparator(&mut self, name: &str, compare_fn: CompareFn) {
+    pub fn set_comparator(&mut self, name: &str, compare_fn: Box<CompareFn>) {
+        let cb = Box::new(ComparatorCallback {
+            name: CString::new(name.as_bytes()).unwrap(),
+            compare_fn,
+            shortest_separator_fn: None,
+            short_successor_fn: None,
+        });
+
+        unsafe {
+            let cmp = ffi::rocksdb_comparator_create(
+                Box::into_raw(cb).cast::<c_void>(),
+                Some(comparator::destructor_callback),
+                Some(comparator::compare_callback),
+                Some(comparator::name_callback),
+            );
+            ffi::rocksdb_options_set_comparator(self.inner, cmp);
+        }
+    }
+
+    /// Sets a comparator together with the hooks RocksDB uses to shorten
+    /// index block keys.
+    ///
+    /// `shortest_separator_fn` is given `start` and `limit` and may return a
+    /// shorter key in `[start, limit)`. `short_successor_fn` may return a
+    /// shorter key that is greater than or equal to its argument. A hook
+    /// that is `None`, or returns `None`, leaves the key unchanged.
+    ///
+    /// See [`set_comparator`](#method.set_comparator) for the compatibility
+    /// requirements on reopening.
+    pub fn set_comparator_with_hooks(
+        &mut self,
+        name: &str,
+        compare_fn: Box<CompareFn>,
+        shortest_separator_fn: Option<Box<ShortestSeparatorFn>>,
+        short_successor_fn: Option<Box<ShortSuccessorFn>>,
+    ) {
+        let has_separator = shortest_separator_fn.is_some();
+        let has_successor = short_successor_fn.is_some();
         let cb = Box::new(ComparatorCallback {
             name: CString::new(name.as_bytes()).unwrap(),
-            f: compare_fn,
+            compare_fn,
+            shortest_separator_fn,
+            short_successor_fn,
         });
 
         unsafe {
-            let cmp = ffi::rocksdb_comparator_create(
+            ffi::rocksdb_options_set_comparator_with_hooks(
+                self.inner,
                 Box::into_raw(cb).cast::<c_void>(),
                 Some(comparator::destructor_callback),
                 Some(comparator::compare_callback),
                 Some(comparator::name_callback),
+                if has_separator {
+                    Some(comparator::shortest_separator_callback)
+                } else {
+                    None
+                },
+                if has_successor {
+                    Some(comparator::short_successor_callback)
+                } else {
+                    None
+                },
             );
-            ffi::rocksdb_options_set_comparator(self.inner, cmp);
+        }
+    }
+
+    /// Sets one of the comparators implemented natively in RocksDB.
+    ///
+    /// These avoid a call into Rust for every key comparison.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{BuiltinComparator, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_builtin_comparator(BuiltinComparator::ReverseBytewise);
+    /// ```
+    pub fn set_builtin_comparator(&mut self, comparator: BuiltinComparator) {
+        unsafe {
+            ffi::rocksdb_options_set_builtin_comparator(self.inner, comparator as c_int);
         }
     }
 
@@ -1388,7 +2358,7 @@ impl Options {
         since = "0.5.0",
         note = "add_comparator has been renamed to set_comparator"
     )]
-    pub fn add_comparator(&mut self, name: &str, compare_fn: CompareFn) {
+    pub fn add_comparator(&mut self, name: &str, compare_fn: Box<CompareFn>) {
         self.set_comparator(name, compare_fn);
     }
 
@@ -1531,6 +2501,44 @@ impl Options {
         }
     }
 
+    /// Sends the info log to `logger` instead of the `LOG` file, so
+    /// `set_db_log_dir`, `set_max_log_file_size` and the other log file
+    /// settings no longer apply.
+    ///
+    /// Messages below `level` are dropped before being formatted; `Header`
+    /// messages, which describe the options a database was opened with, are
+    /// always passed. This also sets the log level like `set_log_level`.
+    ///
+    /// The logger is shared by every database opened with these options, and
+    /// is called from RocksDB background threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{LogLevel, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_logger(LogLevel::Warn, |level, msg| {
+    ///     eprintln!("rocksdb {:?}: {}", level, msg);
+    /// });
+    /// ```
+    pub fn set_logger<F>(&mut self, level: LogLevel, logger: F)
+    where
+        F: Fn(LogLevel, &str) + Send + Sync + 'static,
+    {
+        let logger = Box::new(logger);
+
+        unsafe {
+            ffi::rocksdb_options_set_callback_logger(
+                self.inner,
+                Box::into_raw(logger).cast::<c_void>(),
+                Some(logger::destructor_callback::<F>),
+                Some(logger::log_callback::<F>),
+                level as c_int,
+            );
+        }
+    }
+
     /// Allows OS to incrementally sync files to disk while they are being
     /// written, asynchronously, in the background. This operation can be used
     /// to smooth out write I/Os over time. Users shouldn't rely on it for
@@ -1865,6 +2873,29 @@ impl Options {
         }
     }
 
+    /// Attaches `manager` to limit the memory of the memtables, together
+    /// with the other databases using it. Overrides `db_write_buffer_size`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{BlockBasedOptions, Cache, Options, WriteBufferManager};
+    ///
+    /// let cache = Cache::new_lru_cache(1024 * 1024 * 1024).unwrap();
+    /// let manager = WriteBufferManager::new_with_cache(256 * 1024 * 1024, &cache, false).unwrap();
+    ///
+    /// let mut block_opts = BlockBasedOptions::default();
+    /// block_opts.set_block_cache(&cache);
+    /// let mut opts = Options::default();
+    /// opts.set_block_based_table_factory(&block_opts);
+    /// opts.set_write_buffer_manager(&manager);
+    /// ```
+    pub fn set_write_buffer_manager(&mut self, manager: &WriteBufferManager) {
+        unsafe {
+            ffi::rocksdb_options_set_write_buffer_manager(self.inner, manager.0.inner);
+        }
+    }
+
     /// Control maximum total data size for a level.
     /// max_bytes_for_level_base is the max total for level-1.
     /// Maximum number of bytes for level L can be calculated as
@@ -2568,6 +3599,7 @@ impl Options {
     }
 
     /// If not zero, dump `rocksdb.stats` to LOG every `stats_dump_period_sec`.
+    /// The period is measured in real time, even with `Env::mock_clock_env`.
     ///
     /// Default: `600` (10 mins)
     ///
@@ -2680,6 +3712,20 @@ impl Options {
         }
     }
 
+    /// Sets the age in seconds after which a file is compacted again even if
+    /// nothing else picks it, so that all the data goes through the compaction
+    /// filter every so often. The age is measured with the clock of the `Env`.
+    ///
+    /// Only works with level and FIFO compaction, and a `max_open_files` of -1.
+    /// 0 turns periodic compactions off.
+    ///
+    /// Default: `0xfffffffffffffffe`, which RocksDB turns into 30 days
+    pub fn set_periodic_compaction_seconds(&mut self, secs: u64) {
+        unsafe {
+            ffi::rocksdb_options_set_periodic_compaction_seconds(self.inner, secs);
+        }
+    }
+
     /// Specifies the absolute path of the directory the
     /// write-ahead log (WAL) should be written to.
     ///
@@ -2893,6 +3939,51 @@ impl Options {
         }
     }
 
+    /// Uses `rate_limiter` to control the write rate of flush and compaction,
+    /// like `set_ratelimiter`, but with a limiter which may be shared with
+    /// other databases and adjusted at runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{Options, RateLimiter};
+    ///
+    /// let limiter = RateLimiter::new(16 * 1024 * 1024, 100 * 1000, 10).unwrap();
+    /// let mut opts_a = Options::default();
+    /// opts_a.set_rate_limiter(&limiter);
+    /// let mut opts_b = Options::default();
+    /// opts_b.set_rate_limiter(&limiter);
+    ///
+    /// limiter.set_bytes_per_second(8 * 1024 * 1024);
+    /// ```
+    pub fn set_rate_limiter(&mut self, rate_limiter: &RateLimiter) {
+        unsafe {
+            ffi::rocksdb_options_set_ratelimiter(self.inner, rate_limiter.0.inner);
+        }
+    }
+
+    /// Attaches `manager` to track the SST files of the database, limit the
+    /// space they use and rate limit their deletion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{Env, Options, SstFileManager};
+    ///
+    /// let manager = SstFileManager::new(&Env::default().unwrap()).unwrap();
+    /// manager.set_max_allowed_space_usage(64 << 30);
+    /// manager.set_delete_rate_bytes_per_second(32 << 20);
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_sst_file_manager(&manager);
+    /// ```
+    pub fn set_sst_file_manager(&mut self, manager: &SstFileManager) {
+        unsafe {
+            ffi::rocksdb_options_set_sst_file_manager(self.inner, manager.0.inner);
+        }
+        self.outlive.sst_file_manager = Some(manager.clone());
+    }
+
     /// Sets the maximal size of the info log file.
     ///
     /// If the log file is larger than `max_log_file_size`, a new info log file
@@ -2991,6 +4082,37 @@ impl Options {
         }
     }
 
+    /// Enables two-phase commit, so that the transactions of a
+    /// `TransactionDB` can be prepared with `Transaction::prepare` and those
+    /// prepared but not yet committed are recovered when reopening.
+    ///
+    /// Default: false
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::Options;
+    ///
+    /// let mut options = Options::default();
+    /// options.set_allow_2pc(true);
+    /// ```
+    pub fn set_allow_2pc(&mut self, allow_2pc: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_allow_2pc(self.inner, allow_2pc as c_uchar);
+        }
+    }
+
+    /// Uses a second write queue for the writes that skip the memtable, such
+    /// as the prepare phase of `TxnDBWritePolicy::WritePrepared` transactions,
+    /// so they don't wait behind the writes to the memtable.
+    ///
+    /// Default: false
+    pub fn set_two_write_queues(&mut self, two_write_queues: bool) {
+        unsafe {
+            ffi::rocksdb_options_set_two_write_queues(self.inner, two_write_queues as c_uchar);
+        }
+    }
+
     /// Enable whole key bloom filter in memtable. Note this will only take effect
     /// if memtable_prefix_bloom_size_ratio is not 0. Enabling whole key filtering
     /// can potentially reduce CPU usage for point-look-ups.
@@ -3006,6 +4128,537 @@ impl Options {
             );
         }
     }
+
+    /// Serializes the database and column family options as a string of
+    /// `name=value;` pairs, such as `"write_buffer_size=67108864;..."`.
+    ///
+    /// Comparators, merge operators and other objects implemented in Rust
+    /// are only written by name, and cannot be read back by
+    /// `from_options_string`.
+    pub fn to_options_string(&self) -> Result<String, Error> {
+        unsafe {
+            let value = ffi_try!(ffi::rocksdb_options_get_options_string(self.inner));
+            Ok(take_string(value))
+        }
+    }
+
+    /// Creates options from `base`, overriding those set in `opts_str`, a
+    /// string of `name=value;` pairs such as `to_options_string` writes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::Options;
+    ///
+    /// let base = Options::default();
+    /// let opts =
+    ///     Options::from_options_string(&base, "write_buffer_size=1048576;max_open_files=100")
+    ///         .unwrap();
+    /// assert!(opts.to_options_string().unwrap().contains("max_open_files=100;"));
+    /// ```
+    pub fn from_options_string(base: &Options, opts_str: &str) -> Result<Options, Error> {
+        let c_opts_str = to_cstring(opts_str, "Failed to convert options string to CString")?;
+        let opts = Options {
+            inner: unsafe { ffi::rocksdb_options_create() },
+            outlive: base.outlive.clone(),
+        };
+        assert!(!opts.inner.is_null(), "Could not create RocksDB options");
+        unsafe {
+            ffi_try!(ffi::rocksdb_get_options_from_string(
+                base.inner,
+                c_opts_str.as_ptr(),
+                opts.inner,
+            ));
+        }
+        Ok(opts)
+    }
+}
+
+/// Generates the `Options` getters from a table of `getter -> type = C getter,
+/// setter`, converting the C value with `as` or the given closure. Extra doc
+/// lines go before an entry.
+///
+/// Getters returning several values or objects are written by hand after the
+/// table. `set_min_level_to_compress` and `set_allow_os_buffer` have no getter
+/// of their own, as they only set other options: `get_compression_per_level`,
+/// and `get_use_direct_reads` with `get_use_direct_io_for_flush_and_compaction`.
+macro_rules! options_getters {
+    ($(
+        $(#[$attr:meta])*
+        $name:ident -> $ty:ty = $ffi:ident,
+            $setter:ident($value:expr) $(== $expected:expr)? $(, |$v:ident| $conv:expr)?;
+    )*) => {
+        impl Options {
+            $(
+                #[doc = concat!("Returns the value set with `", stringify!($setter), "`.")]
+                $(#[$attr])*
+                ///
+                /// # Examples
+                ///
+                /// ```
+                /// use ckb_rocksdb::*;
+                ///
+                /// let mut opts = Options::default();
+                #[doc = concat!("opts.", stringify!($setter), "(", stringify!($value), ");")]
+                #[doc = concat!(
+                    "assert_eq!(opts.", stringify!($name), "(), ",
+                    options_getters!(@expected $value $(, $expected)?), ");"
+                )]
+                /// ```
+                pub fn $name(&self) -> $ty {
+                    let value = unsafe { ffi::$ffi(self.inner) };
+                    options_getters!(@convert value, $ty $(, |$v| $conv)?)
+                }
+            )*
+        }
+    };
+    (@expected $value:expr) => {
+        stringify!($value)
+    };
+    (@expected $value:expr, $expected:expr) => {
+        stringify!($expected)
+    };
+    (@convert $value:ident, $ty:ty) => {
+        $value as $ty
+    };
+    (@convert $value:ident, $ty:ty, |$v:ident| $conv:expr) => {{
+        let $v = $value;
+        $conv
+    }};
+}
+
+options_getters! {
+    get_create_if_missing -> bool =
+        rocksdb_options_get_create_if_missing, create_if_missing(true), |v| v != 0;
+    get_create_missing_column_families -> bool =
+        rocksdb_options_get_create_missing_column_families,
+        create_missing_column_families(true), |v| v != 0;
+    get_error_if_exists -> bool =
+        rocksdb_options_get_error_if_exists, set_error_if_exists(true), |v| v != 0;
+    get_paranoid_checks -> bool =
+        rocksdb_options_get_paranoid_checks, set_paranoid_checks(false), |v| v != 0;
+    get_enable_blob_files -> bool =
+        rocksdb_options_get_enable_blob_files, set_enable_blob_files(true), |v| v != 0;
+    get_enable_blob_gc -> bool =
+        rocksdb_options_get_enable_blob_gc, set_enable_blob_gc(true), |v| v != 0;
+    get_level_compaction_dynamic_level_bytes -> bool =
+        rocksdb_options_get_level_compaction_dynamic_level_bytes,
+        set_level_compaction_dynamic_level_bytes(true), |v| v != 0;
+    get_optimize_filters_for_hits -> bool =
+        rocksdb_options_get_optimize_filters_for_hits, set_optimize_filters_for_hits(true),
+        |v| v != 0;
+    get_use_fsync -> bool = rocksdb_options_get_use_fsync, set_use_fsync(true), |v| v != 0;
+    get_allow_concurrent_memtable_write -> bool =
+        rocksdb_options_get_allow_concurrent_memtable_write,
+        set_allow_concurrent_memtable_write(false), |v| v != 0;
+    get_enable_write_thread_adaptive_yield -> bool =
+        rocksdb_options_get_enable_write_thread_adaptive_yield,
+        set_enable_write_thread_adaptive_yield(false), |v| v != 0;
+    get_use_direct_reads -> bool =
+        rocksdb_options_get_use_direct_reads, set_use_direct_reads(true), |v| v != 0;
+    get_use_direct_io_for_flush_and_compaction -> bool =
+        rocksdb_options_get_use_direct_io_for_flush_and_compaction,
+        set_use_direct_io_for_flush_and_compaction(true), |v| v != 0;
+    get_is_fd_close_on_exec -> bool =
+        rocksdb_options_get_is_fd_close_on_exec, set_is_fd_close_on_exec(false), |v| v != 0;
+    get_unordered_write -> bool =
+        rocksdb_options_get_unordered_write, set_unordered_write(true), |v| v != 0;
+    get_disable_auto_compactions -> bool =
+        rocksdb_options_get_disable_auto_compactions, set_disable_auto_compactions(true),
+        |v| v != 0;
+    get_inplace_update_support -> bool =
+        rocksdb_options_get_inplace_update_support, set_inplace_update_support(true), |v| v != 0;
+    get_skip_checking_sst_file_sizes_on_db_open -> bool =
+        rocksdb_options_get_skip_checking_sst_file_sizes_on_db_open,
+        set_skip_checking_sst_file_sizes_on_db_open(true), |v| v != 0;
+    get_enable_pipelined_write -> bool =
+        rocksdb_options_get_enable_pipelined_write, set_enable_pipelined_write(true), |v| v != 0;
+    get_report_bg_io_stats -> bool =
+        rocksdb_options_get_report_bg_io_stats, set_report_bg_io_stats(true), |v| v != 0;
+    get_advise_random_on_open -> bool =
+        rocksdb_options_get_advise_random_on_open, set_advise_random_on_open(false), |v| v != 0;
+    get_use_adaptive_mutex -> bool =
+        rocksdb_options_get_use_adaptive_mutex, set_use_adaptive_mutex(true), |v| v != 0;
+    get_skip_stats_update_on_db_open -> bool =
+        rocksdb_options_get_skip_stats_update_on_db_open,
+        set_skip_stats_update_on_db_open(true), |v| v != 0;
+    get_allow_mmap_writes -> bool =
+        rocksdb_options_get_allow_mmap_writes, set_allow_mmap_writes(true), |v| v != 0;
+    get_allow_mmap_reads -> bool =
+        rocksdb_options_get_allow_mmap_reads, set_allow_mmap_reads(true), |v| v != 0;
+    get_manual_wal_flush -> bool =
+        rocksdb_options_get_manual_wal_flush, set_manual_wal_flush(true), |v| v != 0;
+    get_atomic_flush -> bool = rocksdb_options_get_atomic_flush, set_atomic_flush(true), |v| v != 0;
+    get_allow_2pc -> bool = rocksdb_options_get_allow_2pc, set_allow_2pc(true), |v| v != 0;
+    get_two_write_queues -> bool =
+        rocksdb_options_get_two_write_queues, set_two_write_queues(true), |v| v != 0;
+    get_dump_malloc_stats -> bool =
+        rocksdb_options_get_dump_malloc_stats, set_dump_malloc_stats(true), |v| v != 0;
+    get_memtable_whole_key_filtering -> bool =
+        rocksdb_options_get_memtable_whole_key_filtering,
+        set_memtable_whole_key_filtering(true), |v| v != 0;
+    get_zstd_max_train_bytes -> c_int =
+        rocksdb_options_get_compression_options_zstd_max_train_bytes,
+        set_zstd_max_train_bytes(1 << 20);
+    get_compaction_readahead_size -> usize =
+        rocksdb_options_get_compaction_readahead_size, set_compaction_readahead_size(2 << 20);
+    get_min_blob_size -> u64 = rocksdb_options_get_min_blob_size, set_min_blob_size(1024);
+    get_blob_file_size -> u64 = rocksdb_options_get_blob_file_size, set_blob_file_size(1 << 30);
+    get_blob_gc_age_cutoff -> c_double =
+        rocksdb_options_get_blob_gc_age_cutoff, set_blob_gc_age_cutoff(0.5);
+    get_blob_gc_force_threshold -> c_double =
+        rocksdb_options_get_blob_gc_force_threshold, set_blob_gc_force_threshold(0.8);
+    get_delete_obsolete_files_period_micros -> u64 =
+        rocksdb_options_get_delete_obsolete_files_period_micros,
+        set_delete_obsolete_files_period_micros(1000);
+    get_max_open_files -> c_int = rocksdb_options_get_max_open_files, set_max_open_files(123);
+    get_max_file_opening_threads -> c_int =
+        rocksdb_options_get_max_file_opening_threads, set_max_file_opening_threads(4);
+    get_bytes_per_sync -> u64 = rocksdb_options_get_bytes_per_sync, set_bytes_per_sync(1 << 20);
+    get_wal_bytes_per_sync -> u64 =
+        rocksdb_options_get_wal_bytes_per_sync, set_wal_bytes_per_sync(2 << 20);
+    get_writable_file_max_buffer_size -> u64 =
+        rocksdb_options_get_writable_file_max_buffer_size,
+        set_writable_file_max_buffer_size(3 << 20);
+    get_max_sequential_skip_in_iterations -> u64 =
+        rocksdb_options_get_max_sequential_skip_in_iterations,
+        set_max_sequential_skip_in_iterations(16);
+    get_table_cache_num_shard_bits -> c_int =
+        rocksdb_options_get_table_cache_numshardbits, set_table_cache_num_shard_bits(5);
+    get_target_file_size_multiplier -> i32 =
+        rocksdb_options_get_target_file_size_multiplier, set_target_file_size_multiplier(2);
+    get_max_write_buffer_number -> c_int =
+        rocksdb_options_get_max_write_buffer_number, set_max_write_buffer_number(4);
+    get_write_buffer_size -> usize =
+        rocksdb_options_get_write_buffer_size, set_write_buffer_size(4 * 1024 * 1024);
+    get_db_write_buffer_size -> usize =
+        rocksdb_options_get_db_write_buffer_size, set_db_write_buffer_size(128 << 20);
+    get_max_bytes_for_level_base -> u64 =
+        rocksdb_options_get_max_bytes_for_level_base, set_max_bytes_for_level_base(512 << 20);
+    get_max_bytes_for_level_multiplier -> f64 =
+        rocksdb_options_get_max_bytes_for_level_multiplier, set_max_bytes_for_level_multiplier(8.0);
+    get_max_manifest_file_size -> usize =
+        rocksdb_options_get_max_manifest_file_size, set_max_manifest_file_size(64 << 20);
+    get_target_file_size_base -> u64 =
+        rocksdb_options_get_target_file_size_base, set_target_file_size_base(128 << 20);
+    get_min_write_buffer_number_to_merge -> c_int =
+        rocksdb_options_get_min_write_buffer_number_to_merge,
+        set_min_write_buffer_number_to_merge(2);
+    get_min_write_buffer_number -> c_int =
+        rocksdb_options_get_min_write_buffer_number_to_merge, set_min_write_buffer_number(2);
+    get_level_zero_file_num_compaction_trigger -> c_int =
+        rocksdb_options_get_level0_file_num_compaction_trigger,
+        set_level_zero_file_num_compaction_trigger(8);
+    get_level_zero_slowdown_writes_trigger -> c_int =
+        rocksdb_options_get_level0_slowdown_writes_trigger,
+        set_level_zero_slowdown_writes_trigger(17);
+    get_level_zero_stop_writes_trigger -> c_int =
+        rocksdb_options_get_level0_stop_writes_trigger, set_level_zero_stop_writes_trigger(24);
+    get_max_subcompactions -> u32 =
+        rocksdb_options_get_max_subcompactions, set_max_subcompactions(2);
+    get_max_background_jobs -> c_int =
+        rocksdb_options_get_max_background_jobs, set_max_background_jobs(3);
+    get_max_background_compactions -> c_int =
+        rocksdb_options_get_max_background_compactions, set_max_background_compactions(2);
+    get_max_background_flushes -> c_int =
+        rocksdb_options_get_max_background_flushes, set_max_background_flushes(2);
+    get_memtable_huge_page_size -> size_t =
+        rocksdb_options_get_memtable_huge_page_size, set_memtable_huge_page_size(2 << 20);
+    get_max_successive_merges -> usize =
+        rocksdb_options_get_max_successive_merges, set_max_successive_merges(10);
+    get_bloom_locality -> u32 = rocksdb_options_get_bloom_locality, set_bloom_locality(1);
+    get_inplace_update_locks -> usize =
+        rocksdb_options_get_inplace_update_num_locks, set_inplace_update_locks(100);
+    get_max_write_buffer_size_to_maintain -> i64 =
+        rocksdb_options_get_max_write_buffer_size_to_maintain,
+        set_max_write_buffer_size_to_maintain(64 << 20);
+    get_max_total_wal_size -> u64 =
+        rocksdb_options_get_max_total_wal_size, set_max_total_wal_size(1 << 30);
+    get_stats_dump_period_sec -> c_uint =
+        rocksdb_options_get_stats_dump_period_sec, set_stats_dump_period_sec(300);
+    get_stats_persist_period_sec -> c_uint =
+        rocksdb_options_get_stats_persist_period_sec, set_stats_persist_period_sec(60);
+    get_num_levels -> c_int = rocksdb_options_get_num_levels, set_num_levels(2);
+    get_memtable_prefix_bloom_ratio -> f64 =
+        rocksdb_options_get_memtable_prefix_bloom_size_ratio, set_memtable_prefix_bloom_ratio(0.1);
+    get_max_compaction_bytes -> u64 =
+        rocksdb_options_get_max_compaction_bytes, set_max_compaction_bytes(1 << 30);
+    get_periodic_compaction_seconds -> u64 =
+        rocksdb_options_get_periodic_compaction_seconds, set_periodic_compaction_seconds(3600);
+    get_wal_ttl_seconds -> u64 = rocksdb_options_get_WAL_ttl_seconds, set_wal_ttl_seconds(3600);
+    get_wal_size_limit_mb -> u64 =
+        rocksdb_options_get_WAL_size_limit_MB, set_wal_size_limit_mb(1024);
+    get_manifest_preallocation_size -> usize =
+        rocksdb_options_get_manifest_preallocation_size, set_manifest_preallocation_size(1 << 20);
+    get_keep_log_file_num -> usize =
+        rocksdb_options_get_keep_log_file_num, set_keep_log_file_num(10);
+    get_max_log_file_size -> usize =
+        rocksdb_options_get_max_log_file_size, set_max_log_file_size(1 << 20);
+    get_log_file_time_to_roll -> usize =
+        rocksdb_options_get_log_file_time_to_roll, set_log_file_time_to_roll(86400);
+    get_recycle_log_file_num -> usize =
+        rocksdb_options_get_recycle_log_file_num, set_recycle_log_file_num(2);
+    get_soft_pending_compaction_bytes_limit -> usize =
+        rocksdb_options_get_soft_pending_compaction_bytes_limit,
+        set_soft_pending_compaction_bytes_limit(1 << 30);
+    get_hard_pending_compaction_bytes_limit -> usize =
+        rocksdb_options_get_hard_pending_compaction_bytes_limit,
+        set_hard_pending_compaction_bytes_limit(2 << 30);
+    get_arena_block_size -> usize =
+        rocksdb_options_get_arena_block_size, set_arena_block_size(1 << 20);
+    ///
+    /// `None` if the compression type has no `DBCompressionType` counterpart.
+    get_compression_type -> Option<DBCompressionType> =
+        rocksdb_options_get_compression,
+        set_compression_type(DBCompressionType::Lz4) == Some(DBCompressionType::Lz4),
+        |v| DBCompressionType::from_raw(v);
+    ///
+    /// `None` if disabled, the default, or if the compression type has no
+    /// `DBCompressionType` counterpart.
+    get_bottommost_compression_type -> Option<DBCompressionType> =
+        rocksdb_options_get_bottommost_compression,
+        set_bottommost_compression_type(DBCompressionType::Zstd) == Some(DBCompressionType::Zstd),
+        |v| DBCompressionType::from_raw(v);
+    ///
+    /// `None` if the compression type has no `DBCompressionType` counterpart.
+    get_blob_compression_type -> Option<DBCompressionType> =
+        rocksdb_options_get_blob_compression_type,
+        set_blob_compression_type(DBCompressionType::Snappy) == Some(DBCompressionType::Snappy),
+        |v| DBCompressionType::from_raw(v);
+    ///
+    /// `None` if the compaction style has no `DBCompactionStyle` counterpart.
+    get_compaction_style -> Option<DBCompactionStyle> =
+        rocksdb_options_get_compaction_style,
+        set_compaction_style(DBCompactionStyle::Universal) == Some(DBCompactionStyle::Universal),
+        |v| DBCompactionStyle::from_raw(v);
+    ///
+    /// `None` if the log level has no `LogLevel` counterpart.
+    get_log_level -> Option<LogLevel> =
+        rocksdb_options_get_info_log_level,
+        set_log_level(LogLevel::Warn) == Some(LogLevel::Warn),
+        |v| LogLevel::from_raw(v);
+    ///
+    /// `None` if the recovery mode has no `DBRecoveryMode` counterpart.
+    get_wal_recovery_mode -> Option<DBRecoveryMode> =
+        rocksdb_options_get_wal_recovery_mode,
+        set_wal_recovery_mode(DBRecoveryMode::AbsoluteConsistency)
+            == Some(DBRecoveryMode::AbsoluteConsistency),
+        |v| DBRecoveryMode::from_raw(v);
+    ///
+    /// `None` if the access hint has no `AccessHint` counterpart.
+    get_access_hint_on_compaction_start -> Option<AccessHint> =
+        rocksdb_options_get_access_hint_on_compaction_start,
+        set_access_hint_on_compaction_start(AccessHint::Sequential) == Some(AccessHint::Sequential),
+        |v| AccessHint::from_raw(v);
+}
+
+impl Options {
+    /// Returns the compression types set with `set_compression_per_level`,
+    /// `None` for those without a `DBCompressionType` counterpart.
+    pub fn get_compression_per_level(&self) -> Vec<Option<DBCompressionType>> {
+        let mut levels = vec![0; self.get_num_levels().max(0) as usize];
+        unsafe {
+            let count = ffi::rocksdb_options_get_compression_per_level(
+                self.inner,
+                levels.as_mut_ptr(),
+                levels.len(),
+            );
+            if count > levels.len() {
+                levels.resize(count, 0);
+                ffi::rocksdb_options_get_compression_per_level(
+                    self.inner,
+                    levels.as_mut_ptr(),
+                    levels.len(),
+                );
+            }
+            levels.truncate(count);
+        }
+        levels
+            .into_iter()
+            .map(DBCompressionType::from_raw)
+            .collect()
+    }
+
+    /// Returns the window bits, level, strategy and maximum dictionary size
+    /// set with `set_compression_options`.
+    pub fn get_compression_options(&self) -> (c_int, c_int, c_int, c_int) {
+        let (mut w_bits, mut level, mut strategy, mut max_dict_bytes) = (0, 0, 0, 0);
+        unsafe {
+            ffi::rocksdb_options_get_compression_options(
+                self.inner,
+                &mut w_bits,
+                &mut level,
+                &mut strategy,
+                &mut max_dict_bytes,
+            );
+        }
+        (w_bits, level, strategy, max_dict_bytes)
+    }
+
+    /// Returns the window bits, level, strategy and maximum dictionary size
+    /// set with `set_bottommost_compression_options`, `None` if disabled.
+    pub fn get_bottommost_compression_options(&self) -> Option<(c_int, c_int, c_int, c_int)> {
+        self.get_bottommost_compression_opts().map(
+            |(w_bits, level, strategy, max_dict_bytes, _)| {
+                (w_bits, level, strategy, max_dict_bytes)
+            },
+        )
+    }
+
+    /// Returns the value set with `set_bottommost_zstd_max_train_bytes`,
+    /// `None` if the bottommost compression options are disabled.
+    pub fn get_bottommost_zstd_max_train_bytes(&self) -> Option<c_int> {
+        self.get_bottommost_compression_opts()
+            .map(|(_, _, _, _, zstd_max_train_bytes)| zstd_max_train_bytes)
+    }
+
+    fn get_bottommost_compression_opts(&self) -> Option<(c_int, c_int, c_int, c_int, c_int)> {
+        let (mut w_bits, mut level, mut strategy, mut max_dict_bytes) = (0, 0, 0, 0);
+        let mut zstd_max_train_bytes = 0;
+        let enabled = unsafe {
+            ffi::rocksdb_options_get_bottommost_compression_options(
+                self.inner,
+                &mut w_bits,
+                &mut level,
+                &mut strategy,
+                &mut max_dict_bytes,
+                &mut zstd_max_train_bytes,
+            )
+        };
+        if enabled != 0 {
+            Some((
+                w_bits,
+                level,
+                strategy,
+                max_dict_bytes,
+                zstd_max_train_bytes,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the values set with
+    /// `set_max_bytes_for_level_multiplier_additional`.
+    pub fn get_max_bytes_for_level_multiplier_additional(&self) -> Vec<i32> {
+        let mut levels = vec![0; self.get_num_levels().max(0) as usize];
+        unsafe {
+            let count = ffi::rocksdb_options_get_max_bytes_for_level_multiplier_additional(
+                self.inner,
+                levels.as_mut_ptr(),
+                levels.len(),
+            );
+            if count > levels.len() {
+                levels.resize(count, 0);
+                ffi::rocksdb_options_get_max_bytes_for_level_multiplier_additional(
+                    self.inner,
+                    levels.as_mut_ptr(),
+                    levels.len(),
+                );
+            }
+            levels.truncate(count);
+        }
+        levels
+    }
+
+    /// Returns the directory set with `set_db_log_dir`, `None` if the logs
+    /// are kept with the data.
+    pub fn get_db_log_dir(&self) -> Option<PathBuf> {
+        unsafe { non_empty_path(ffi::rocksdb_options_get_db_log_dir(self.inner)) }
+    }
+
+    /// Returns the directory set with `set_wal_dir`, `None` if the write-ahead
+    /// logs are kept with the data.
+    pub fn get_wal_dir(&self) -> Option<PathBuf> {
+        unsafe { non_empty_path(ffi::rocksdb_options_get_wal_dir(self.inner)) }
+    }
+
+    /// Returns the name of the prefix extractor set with
+    /// `set_prefix_extractor`, `None` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{Options, SliceTransform};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(3));
+    /// assert_eq!(opts.get_prefix_extractor_name().as_deref(), Some("rocksdb.FixedPrefix"));
+    /// ```
+    pub fn get_prefix_extractor_name(&self) -> Option<String> {
+        unsafe {
+            let name = ffi::rocksdb_options_get_prefix_extractor_name(self.inner);
+            if name.is_null() {
+                None
+            } else {
+                Some(take_string(name))
+            }
+        }
+    }
+
+    /// Returns a copy of the options set with
+    /// `set_universal_compaction_options`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{Options, UniversalCompactOptions};
+    ///
+    /// let mut opts = Options::default();
+    /// let mut uco = UniversalCompactOptions::default();
+    /// uco.set_size_ratio(5);
+    /// opts.set_universal_compaction_options(&uco);
+    /// assert_eq!(opts.get_universal_compaction_options().get_size_ratio(), 5);
+    /// ```
+    pub fn get_universal_compaction_options(&self) -> UniversalCompactOptions {
+        let inner = unsafe { ffi::rocksdb_options_get_universal_compaction_options(self.inner) };
+        UniversalCompactOptions { inner }
+    }
+
+    /// Returns a copy of the options set with `set_fifo_compaction_options`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{FifoCompactOptions, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// let mut fco = FifoCompactOptions::default();
+    /// fco.set_max_table_files_size(1 << 20);
+    /// opts.set_fifo_compaction_options(&fco);
+    /// assert_eq!(opts.get_fifo_compaction_options().get_max_table_files_size(), 1 << 20);
+    /// ```
+    pub fn get_fifo_compaction_options(&self) -> FifoCompactOptions {
+        let inner = unsafe { ffi::rocksdb_options_get_fifo_compaction_options(self.inner) };
+        FifoCompactOptions { inner }
+    }
+
+    /// Returns the paths and target sizes set with `set_db_paths`.
+    pub fn get_db_paths(&self) -> Vec<(PathBuf, u64)> {
+        unsafe {
+            let count = ffi::rocksdb_options_get_db_paths_count(self.inner);
+            (0..count)
+                .map(|index| {
+                    let mut target_size = 0;
+                    let path =
+                        ffi::rocksdb_options_get_db_path(self.inner, index, &mut target_size);
+                    (
+                        PathBuf::from(CStr::from_ptr(path).to_string_lossy().into_owned()),
+                        target_size,
+                    )
+                })
+                .collect()
+        }
+    }
+}
+
+unsafe fn non_empty_path(path: *const c_char) -> Option<PathBuf> {
+    let path = CStr::from_ptr(path).to_string_lossy();
+    if path.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(path.into_owned()))
+    }
 }
 
 impl Default for Options {
@@ -3228,6 +4881,43 @@ impl ReadOptions {
         self.option_set_readahead_size = Some(v);
     }
 
+    /// Sets the timestamp to read at, for column families whose comparator
+    /// uses user-defined timestamps.
+    ///
+    /// Only versions written at or before `ts` are visible. The timestamp must
+    /// have the size expected by the comparator, e.g. 8 little-endian bytes
+    /// for `BuiltinComparator::BytewiseWithU64Ts`. Reads from such column
+    /// families must always set a timestamp.
+    pub fn set_timestamp<T: AsRef<[u8]>>(&mut self, ts: T) {
+        let ts = ReadTimestamp::new(ts.as_ref());
+        unsafe {
+            ffi::rocksdb_readoptions_set_timestamp(self.inner, ts.inner);
+        }
+        self.option_set_timestamp = Some(ts);
+    }
+
+    /// Sets the oldest timestamp an iterator returns versions for.
+    ///
+    /// Combined with [`set_timestamp`](#method.set_timestamp), iterators return
+    /// every version with a timestamp in `[iter_start_ts, timestamp]` instead of
+    /// only the newest one. In this mode the keys returned by the iterator are
+    /// RocksDB internal keys: the user key followed by the timestamp and an
+    /// 8-byte sequence number and type.
+    pub fn set_iter_start_ts<T: AsRef<[u8]>>(&mut self, ts: T) {
+        let ts = ReadTimestamp::new(ts.as_ref());
+        unsafe {
+            ffi::rocksdb_readoptions_set_iter_start_ts(self.inner, ts.inner);
+        }
+        self.option_set_iter_start_ts = Some(ts);
+    }
+
+    pub(crate) fn timestamps(&self) -> ReadTimestamps {
+        [
+            self.option_set_timestamp.clone(),
+            self.option_set_iter_start_ts.clone(),
+        ]
+    }
+
     pub fn input_or_default(
         input: Option<&ReadOptions>,
         default_readopts: &mut Option<ReadOptions>,
@@ -3266,6 +4956,8 @@ impl Default for ReadOptions {
                 option_set_prefix_same_as_start: None,
                 option_set_total_order_seek: None,
                 option_set_readahead_size: None,
+                option_set_timestamp: None,
+                option_set_iter_start_ts: None,
                 inner: ffi::rocksdb_readoptions_create(),
             }
         }
@@ -3293,6 +4985,14 @@ impl Clone for ReadOptions {
         if let Some(set_readahead_size) = self.option_set_readahead_size {
             ops.set_readahead_size(set_readahead_size)
         };
+        if let Some(timestamp) = &self.option_set_timestamp {
+            unsafe { ffi::rocksdb_readoptions_set_timestamp(ops.inner, timestamp.inner) };
+            ops.option_set_timestamp = Some(timestamp.clone());
+        };
+        if let Some(iter_start_ts) = &self.option_set_iter_start_ts {
+            unsafe { ffi::rocksdb_readoptions_set_iter_start_ts(ops.inner, iter_start_ts.inner) };
+            ops.option_set_iter_start_ts = Some(iter_start_ts.clone());
+        };
         ops
     }
 }
@@ -3389,6 +5089,8 @@ impl Default for IngestExternalFileOptions {
 }
 
 /// Used by BlockBasedOptions::set_index_type.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlockBasedIndexType {
     /// A space efficient index block that is optimized for
     /// binary-search-based index.
@@ -3403,6 +5105,8 @@ pub enum BlockBasedIndexType {
 }
 
 /// Used by BlockBasedOptions::set_data_block_index_type.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub enum DataBlockIndexType {
     /// Use binary search when performing point lookup for keys in data blocks.
@@ -3445,6 +5149,7 @@ pub struct PlainTableFactoryOptions {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DBCompressionType {
     None = ffi::rocksdb_no_compression as isize,
     Snappy = ffi::rocksdb_snappy_compression as isize,
@@ -3455,14 +5160,42 @@ pub enum DBCompressionType {
     Zstd = ffi::rocksdb_zstd_compression as isize,
 }
 
+impl DBCompressionType {
+    fn from_raw(value: c_int) -> Option<Self> {
+        match value as u32 {
+            ffi::rocksdb_no_compression => Some(Self::None),
+            ffi::rocksdb_snappy_compression => Some(Self::Snappy),
+            ffi::rocksdb_zlib_compression => Some(Self::Zlib),
+            ffi::rocksdb_bz2_compression => Some(Self::Bz2),
+            ffi::rocksdb_lz4_compression => Some(Self::Lz4),
+            ffi::rocksdb_lz4hc_compression => Some(Self::Lz4hc),
+            ffi::rocksdb_zstd_compression => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DBCompactionStyle {
     Level = ffi::rocksdb_level_compaction as isize,
     Universal = ffi::rocksdb_universal_compaction as isize,
     Fifo = ffi::rocksdb_fifo_compaction as isize,
 }
 
+impl DBCompactionStyle {
+    fn from_raw(value: c_int) -> Option<Self> {
+        match value as u32 {
+            ffi::rocksdb_level_compaction => Some(Self::Level),
+            ffi::rocksdb_universal_compaction => Some(Self::Universal),
+            ffi::rocksdb_fifo_compaction => Some(Self::Fifo),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DBRecoveryMode {
     TolerateCorruptedTailRecords = ffi::rocksdb_tolerate_corrupted_tail_records_recovery as isize,
     AbsoluteConsistency = ffi::rocksdb_absolute_consistency_recovery as isize,
@@ -3470,8 +5203,23 @@ pub enum DBRecoveryMode {
     SkipAnyCorruptedRecord = ffi::rocksdb_skip_any_corrupted_records_recovery as isize,
 }
 
+impl DBRecoveryMode {
+    fn from_raw(value: c_int) -> Option<Self> {
+        match value as u32 {
+            ffi::rocksdb_tolerate_corrupted_tail_records_recovery => {
+                Some(Self::TolerateCorruptedTailRecords)
+            }
+            ffi::rocksdb_absolute_consistency_recovery => Some(Self::AbsoluteConsistency),
+            ffi::rocksdb_point_in_time_recovery => Some(Self::PointInTime),
+            ffi::rocksdb_skip_any_corrupted_records_recovery => Some(Self::SkipAnyCorruptedRecord),
+            _ => None,
+        }
+    }
+}
+
 /// File access pattern once a compaction has started
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum AccessHint {
     None = 0,
@@ -3480,6 +5228,18 @@ pub enum AccessHint {
     WillNeed,
 }
 
+impl AccessHint {
+    fn from_raw(value: c_int) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Normal),
+            2 => Some(Self::Sequential),
+            3 => Some(Self::WillNeed),
+            _ => None,
+        }
+    }
+}
+
 pub struct FifoCompactOptions {
     pub(crate) inner: *mut ffi::rocksdb_fifo_compaction_options_t,
 }
@@ -3516,10 +5276,15 @@ impl FifoCompactOptions {
             ffi::rocksdb_fifo_compaction_options_set_max_table_files_size(self.inner, nbytes);
         }
     }
+
+    /// Returns the value set with `set_max_table_files_size`.
+    pub fn get_max_table_files_size(&self) -> u64 {
+        unsafe { ffi::rocksdb_fifo_compaction_options_get_max_table_files_size(self.inner) }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
-#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UniversalCompactionStopStyle {
     Similar = ffi::rocksdb_similar_size_compaction_stop_style as isize,
     Total = ffi::rocksdb_total_size_compaction_stop_style as isize,
@@ -3632,10 +5397,54 @@ impl UniversalCompactOptions {
             ffi::rocksdb_universal_compaction_options_set_stop_style(self.inner, style as c_int);
         }
     }
+
+    /// Returns the value set with `set_size_ratio`.
+    pub fn get_size_ratio(&self) -> c_int {
+        unsafe { ffi::rocksdb_universal_compaction_options_get_size_ratio(self.inner) }
+    }
+
+    /// Returns the value set with `set_min_merge_width`.
+    pub fn get_min_merge_width(&self) -> c_int {
+        unsafe { ffi::rocksdb_universal_compaction_options_get_min_merge_width(self.inner) }
+    }
+
+    /// Returns the value set with `set_max_merge_width`.
+    pub fn get_max_merge_width(&self) -> c_int {
+        unsafe { ffi::rocksdb_universal_compaction_options_get_max_merge_width(self.inner) }
+    }
+
+    /// Returns the value set with `set_max_size_amplification_percent`.
+    pub fn get_max_size_amplification_percent(&self) -> c_int {
+        unsafe {
+            ffi::rocksdb_universal_compaction_options_get_max_size_amplification_percent(self.inner)
+        }
+    }
+
+    /// Returns the value set with `set_compression_size_percent`.
+    pub fn get_compression_size_percent(&self) -> c_int {
+        unsafe {
+            ffi::rocksdb_universal_compaction_options_get_compression_size_percent(self.inner)
+        }
+    }
+
+    /// Returns the value set with `set_stop_style`, `None` if the stop style
+    /// has no `UniversalCompactionStopStyle` counterpart.
+    pub fn get_stop_style(&self) -> Option<UniversalCompactionStopStyle> {
+        let style = unsafe { ffi::rocksdb_universal_compaction_options_get_stop_style(self.inner) };
+        match style as u32 {
+            ffi::rocksdb_similar_size_compaction_stop_style => {
+                Some(UniversalCompactionStopStyle::Similar)
+            }
+            ffi::rocksdb_total_size_compaction_stop_style => {
+                Some(UniversalCompactionStopStyle::Total)
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
-#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum BottommostLevelCompaction {
     /// Skip bottommost level compaction
@@ -184,6 +184,7 @@ impl ops::Iterate for DBWithTTL {
             DBRawIterator {
                 inner: ffi::rocksdb_create_iterator(self.inner, readopts.handle()),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             }
         }
     }
@@ -203,6 +204,7 @@ impl ops::IterateCF for DBWithTTL {
                     cf_handle.inner,
                 ),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             })
         }
     }
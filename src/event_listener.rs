@@ -0,0 +1,376 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Callbacks for background events of a database, such as flushes,
+//! compactions and write stalls.
+//!
+//! Listeners are registered with [`Options::add_event_listener`] and are
+//! invoked from RocksDB background threads, so they should return quickly.
+//!
+//! [`Options::add_event_listener`]: ../struct.Options.html#method.add_event_listener
+
+use libc::{c_char, c_int, c_void};
+use std::ffi::CStr;
+use std::slice;
+
+use crate::{ffi, ffi_util::abort_on_panic, Error};
+
+/// Information about a flush job.
+#[derive(Debug, Clone)]
+pub struct FlushJobInfo {
+    pub cf_name: String,
+    /// Path of the table file created by the flush.
+    pub file_path: String,
+    pub file_number: u64,
+    pub thread_id: u64,
+    pub job_id: i32,
+    /// Whether writes were slowed down because too many files are waiting
+    /// to be compacted.
+    pub triggered_writes_slowdown: bool,
+    /// Whether writes were stopped because too many files are waiting to be
+    /// compacted.
+    pub triggered_writes_stop: bool,
+    pub smallest_seqno: u64,
+    pub largest_seqno: u64,
+    /// The RocksDB `FlushReason` value.
+    pub flush_reason: u32,
+}
+
+/// Information about a compaction job.
+#[derive(Debug, Clone)]
+pub struct CompactionJobInfo {
+    pub cf_name: String,
+    pub status: Result<(), Error>,
+    pub thread_id: u64,
+    pub job_id: i32,
+    pub base_input_level: i32,
+    pub output_level: i32,
+    pub input_files: Vec<String>,
+    pub output_files: Vec<String>,
+    /// The RocksDB `CompactionReason` value.
+    pub compaction_reason: u32,
+    /// Only known once the compaction completed.
+    pub elapsed_micros: u64,
+    pub num_input_records: u64,
+    pub num_output_records: u64,
+    pub total_input_bytes: u64,
+    pub total_output_bytes: u64,
+}
+
+/// Why a table file was created.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TableFileCreationReason {
+    Flush,
+    Compaction,
+    Recovery,
+    Misc,
+}
+
+/// Information about a created table file.
+#[derive(Debug, Clone)]
+pub struct TableFileCreationInfo {
+    pub db_name: String,
+    pub cf_name: String,
+    pub file_path: String,
+    pub job_id: i32,
+    pub reason: TableFileCreationReason,
+    pub file_size: u64,
+    pub status: Result<(), Error>,
+}
+
+/// Information about a deleted table file.
+#[derive(Debug, Clone)]
+pub struct TableFileDeletionInfo {
+    pub db_name: String,
+    pub file_path: String,
+    pub job_id: i32,
+    pub status: Result<(), Error>,
+}
+
+/// The operation during which a background error occurred.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BackgroundErrorReason {
+    Flush,
+    Compaction,
+    WriteCallback,
+    MemTable,
+    ManifestWrite,
+    FlushNoWAL,
+    ManifestWriteNoWAL,
+}
+
+/// State of the write controller of a column family.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WriteStallCondition {
+    Normal,
+    Delayed,
+    Stopped,
+}
+
+/// Information about a change of the write stall condition.
+#[derive(Debug, Clone)]
+pub struct WriteStallInfo {
+    pub cf_name: String,
+    pub cur: WriteStallCondition,
+    pub prev: WriteStallCondition,
+}
+
+/// Information about a sealed memtable.
+#[derive(Debug, Clone)]
+pub struct MemTableInfo {
+    pub cf_name: String,
+    pub first_seqno: u64,
+    pub earliest_seqno: u64,
+    pub num_entries: u64,
+    pub num_deletes: u64,
+}
+
+/// Receives notifications of background events. Every method defaults to
+/// doing nothing.
+pub trait EventListener: Send + Sync {
+    /// Called before a flush job starts.
+    fn on_flush_begin(&self, _info: &FlushJobInfo) {}
+
+    /// Called after a flush job wrote its table file.
+    fn on_flush_completed(&self, _info: &FlushJobInfo) {}
+
+    /// Called before a compaction job starts.
+    fn on_compaction_begin(&self, _info: &CompactionJobInfo) {}
+
+    /// Called after a compaction job finished, successfully or not.
+    fn on_compaction_completed(&self, _info: &CompactionJobInfo) {}
+
+    /// Called after a flush or compaction created a table file.
+    fn on_table_file_created(&self, _info: &TableFileCreationInfo) {}
+
+    /// Called after a table file was deleted.
+    fn on_table_file_deleted(&self, _info: &TableFileDeletionInfo) {}
+
+    /// Called when a background operation failed. Writes will fail until
+    /// the error is recovered from.
+    fn on_background_error(&self, _reason: BackgroundErrorReason, _error: &Error) {}
+
+    /// Called when writes to a column family are delayed, stopped or
+    /// resumed.
+    fn on_stall_conditions_changed(&self, _info: &WriteStallInfo) {}
+
+    /// Called when a memtable became immutable and a new one was created.
+    fn on_memtable_sealed(&self, _info: &MemTableInfo) {}
+}
+
+unsafe fn to_string(ptr: *const c_char) -> String {
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+unsafe fn to_status(ptr: *const c_char) -> Result<(), Error> {
+    if ptr.is_null() {
+        Ok(())
+    } else {
+        Err(Error::new(to_string(ptr)))
+    }
+}
+
+unsafe fn to_strings(ptr: *const *const c_char, len: usize) -> Vec<String> {
+    if len == 0 {
+        return Vec::new();
+    }
+    slice::from_raw_parts(ptr, len)
+        .iter()
+        .map(|s| to_string(*s))
+        .collect()
+}
+
+unsafe fn flush_job_info(info: *const ffi::rocksdb_flush_job_info_t) -> FlushJobInfo {
+    let info = &*info;
+    FlushJobInfo {
+        cf_name: to_string(info.cf_name),
+        file_path: to_string(info.file_path),
+        file_number: info.file_number,
+        thread_id: info.thread_id,
+        job_id: info.job_id,
+        triggered_writes_slowdown: info.triggered_writes_slowdown != 0,
+        triggered_writes_stop: info.triggered_writes_stop != 0,
+        smallest_seqno: info.smallest_seqno,
+        largest_seqno: info.largest_seqno,
+        flush_reason: info.flush_reason as u32,
+    }
+}
+
+unsafe fn compaction_job_info(
+    info: *const ffi::rocksdb_compaction_job_info_t,
+) -> CompactionJobInfo {
+    let info = &*info;
+    CompactionJobInfo {
+        cf_name: to_string(info.cf_name),
+        status: to_status(info.status),
+        thread_id: info.thread_id,
+        job_id: info.job_id,
+        base_input_level: info.base_input_level,
+        output_level: info.output_level,
+        input_files: to_strings(info.input_files, info.num_input_files),
+        output_files: to_strings(info.output_files, info.num_output_files),
+        compaction_reason: info.compaction_reason as u32,
+        elapsed_micros: info.elapsed_micros,
+        num_input_records: info.num_input_records,
+        num_output_records: info.num_output_records,
+        total_input_bytes: info.total_input_bytes,
+        total_output_bytes: info.total_output_bytes,
+    }
+}
+
+fn write_stall_condition(condition: c_int) -> WriteStallCondition {
+    match condition {
+        1 => WriteStallCondition::Delayed,
+        2 => WriteStallCondition::Stopped,
+        _ => WriteStallCondition::Normal,
+    }
+}
+
+pub(crate) unsafe extern "C" fn destructor_callback<L: EventListener>(raw_cb: *mut c_void) {
+    abort_on_panic(|| {
+        drop(Box::from_raw(raw_cb as *mut L));
+    })
+}
+
+pub(crate) unsafe extern "C" fn on_flush_begin_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_flush_job_info_t,
+) {
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut L);
+        cb.on_flush_begin(&flush_job_info(info));
+    })
+}
+
+pub(crate) unsafe extern "C" fn on_flush_completed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_flush_job_info_t,
+) {
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut L);
+        cb.on_flush_completed(&flush_job_info(info));
+    })
+}
+
+pub(crate) unsafe extern "C" fn on_compaction_begin_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_compaction_job_info_t,
+) {
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut L);
+        cb.on_compaction_begin(&compaction_job_info(info));
+    })
+}
+
+pub(crate) unsafe extern "C" fn on_compaction_completed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_compaction_job_info_t,
+) {
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut L);
+        cb.on_compaction_completed(&compaction_job_info(info));
+    })
+}
+
+pub(crate) unsafe extern "C" fn on_table_file_created_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_table_file_creation_info_t,
+) {
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut L);
+        let info = &*info;
+        cb.on_table_file_created(&TableFileCreationInfo {
+            db_name: to_string(info.db_name),
+            cf_name: to_string(info.cf_name),
+            file_path: to_string(info.file_path),
+            job_id: info.job_id,
+            reason: match info.reason {
+                0 => TableFileCreationReason::Flush,
+                1 => TableFileCreationReason::Compaction,
+                2 => TableFileCreationReason::Recovery,
+                _ => TableFileCreationReason::Misc,
+            },
+            file_size: info.file_size,
+            status: to_status(info.status),
+        });
+    })
+}
+
+pub(crate) unsafe extern "C" fn on_table_file_deleted_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_table_file_deletion_info_t,
+) {
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut L);
+        let info = &*info;
+        cb.on_table_file_deleted(&TableFileDeletionInfo {
+            db_name: to_string(info.db_name),
+            file_path: to_string(info.file_path),
+            job_id: info.job_id,
+            status: to_status(info.status),
+        });
+    })
+}
+
+pub(crate) unsafe extern "C" fn on_background_error_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    reason: c_int,
+    status: *const c_char,
+) {
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut L);
+        let reason = match reason {
+            0 => BackgroundErrorReason::Flush,
+            1 => BackgroundErrorReason::Compaction,
+            2 => BackgroundErrorReason::WriteCallback,
+            3 => BackgroundErrorReason::MemTable,
+            4 => BackgroundErrorReason::ManifestWrite,
+            5 => BackgroundErrorReason::FlushNoWAL,
+            _ => BackgroundErrorReason::ManifestWriteNoWAL,
+        };
+        cb.on_background_error(reason, &Error::new(to_string(status)));
+    })
+}
+
+pub(crate) unsafe extern "C" fn on_stall_conditions_changed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_write_stall_info_t,
+) {
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut L);
+        let info = &*info;
+        cb.on_stall_conditions_changed(&WriteStallInfo {
+            cf_name: to_string(info.cf_name),
+            cur: write_stall_condition(info.cur),
+            prev: write_stall_condition(info.prev),
+        });
+    })
+}
+
+pub(crate) unsafe extern "C" fn on_memtable_sealed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rocksdb_memtable_info_t,
+) {
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut L);
+        let info = &*info;
+        cb.on_memtable_sealed(&MemTableInfo {
+            cf_name: to_string(info.cf_name),
+            first_seqno: info.first_seqno,
+            earliest_seqno: info.earliest_seqno,
+            num_entries: info.num_entries,
+            num_deletes: info.num_deletes,
+        });
+    })
+}
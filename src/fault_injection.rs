@@ -0,0 +1,412 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An environment injecting file system faults, to test how a database
+//! survives failed writes and crashes. Available with the `fault-injection`
+//! feature.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::file_system::{
+    FileSystem, RandomAccessFile, SequentialFile, StdFileSystem, WritableFile,
+};
+use crate::{Env, Error};
+
+/// An environment over a [`FileSystem`] which remembers what was synced,
+/// and can fail writes and syncs or lose everything that was not synced.
+///
+/// A power loss is simulated by calling [`crash`](#method.crash), dropping
+/// the database, calling [`recover`](#method.recover) and opening the
+/// database again:
+///
+/// ```
+/// use ckb_rocksdb::{prelude::*, FaultInjectionEnv, TemporaryDBPath, WriteOptions};
+///
+/// let path = TemporaryDBPath::new();
+/// let fault_env = FaultInjectionEnv::new().unwrap();
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// opts.set_env(fault_env.env());
+///
+/// {
+///     let db = DB::open(&opts, &path).unwrap();
+///     let mut sync_opts = WriteOptions::default();
+///     sync_opts.set_sync(true);
+///     db.put_opt(b"synced", b"value", &sync_opts).unwrap();
+///     db.put(b"unsynced", b"value").unwrap();
+///     fault_env.crash();
+/// }
+/// fault_env.recover().unwrap();
+///
+/// let db = DB::open(&opts, &path).unwrap();
+/// assert!(db.get(b"synced").unwrap().is_some());
+/// assert!(db.get(b"unsynced").unwrap().is_none());
+/// ```
+pub struct FaultInjectionEnv {
+    env: Env,
+    state: Arc<Mutex<FaultState>>,
+    file_system: Arc<dyn FileSystem>,
+}
+
+impl FaultInjectionEnv {
+    /// Returns an environment injecting faults into the files of the local
+    /// file system.
+    pub fn new() -> Result<FaultInjectionEnv, Error> {
+        FaultInjectionEnv::with_file_system(StdFileSystem::new())
+    }
+
+    /// Returns an environment injecting faults into `file_system`, which
+    /// must support `truncate_file` for [`recover`](#method.recover) to
+    /// drop the data that was not synced.
+    pub fn with_file_system<F>(file_system: F) -> Result<FaultInjectionEnv, Error>
+    where
+        F: FileSystem + 'static,
+    {
+        let state = Arc::new(Mutex::new(FaultState::default()));
+        let file_system: Arc<dyn FileSystem> = Arc::new(file_system);
+        let env = Env::from_file_system(FaultFileSystem {
+            inner: file_system.clone(),
+            state: state.clone(),
+        })?;
+        Ok(FaultInjectionEnv {
+            env,
+            state,
+            file_system,
+        })
+    }
+
+    /// The environment to pass to `Options::set_env`.
+    pub fn env(&self) -> &Env {
+        &self.env
+    }
+
+    /// Lets `n` more appends succeed, then fails every following append.
+    pub fn fail_writes_after(&self, n: u64) {
+        self.state().writes_left = Some(n);
+    }
+
+    /// Lets `n` more file syncs succeed, then fails every following sync.
+    pub fn fail_syncs_after(&self, n: u64) {
+        self.state().syncs_left = Some(n);
+    }
+
+    /// Makes appends fail with `ENOSPC`, as on a full disk.
+    pub fn set_no_space(&self, no_space: bool) {
+        self.state().no_space = no_space;
+    }
+
+    /// Removes the faults set by `fail_writes_after`, `fail_syncs_after`
+    /// and `set_no_space`.
+    pub fn clear_faults(&self) {
+        let mut state = self.state();
+        state.writes_left = None;
+        state.syncs_left = None;
+        state.no_space = false;
+    }
+
+    /// Simulates a power loss: from now on every file system operation that
+    /// would change a file fails, until [`recover`](#method.recover) is
+    /// called.
+    pub fn crash(&self) {
+        self.state().active = false;
+    }
+
+    /// Whether `crash` was called without `recover` since.
+    pub fn is_crashed(&self) -> bool {
+        !self.state().active
+    }
+
+    /// Drops the data that was not synced, clears the faults and accepts
+    /// writes again.
+    ///
+    /// Must be called once the database using the environment is closed.
+    pub fn recover(&self) -> io::Result<()> {
+        self.drop_unsynced_data()?;
+        self.clear_faults();
+        self.state().active = true;
+        Ok(())
+    }
+
+    /// Truncates every file written through the environment to its size at
+    /// the last sync, and deletes the files created since the last sync of
+    /// their directory.
+    pub fn drop_unsynced_data(&self) -> io::Result<()> {
+        let mut state = self.state();
+        let unsynced_files: Vec<PathBuf> = state.unsynced_dir_entries.drain().collect();
+        for path in unsynced_files {
+            state.files.remove(&path);
+            match self.file_system.delete_file(&path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        for (path, file) in state.files.iter_mut() {
+            if file.synced_len < file.len {
+                self.file_system.truncate_file(path, file.synced_len)?;
+                file.len = file.synced_len;
+            }
+        }
+        Ok(())
+    }
+
+    fn state(&self) -> MutexGuard<'_, FaultState> {
+        self.state.lock().unwrap()
+    }
+}
+
+struct FaultState {
+    active: bool,
+    writes_left: Option<u64>,
+    syncs_left: Option<u64>,
+    no_space: bool,
+    files: HashMap<PathBuf, FileState>,
+    // Files created or renamed into place since their directory was synced.
+    unsynced_dir_entries: HashSet<PathBuf>,
+}
+
+impl Default for FaultState {
+    fn default() -> FaultState {
+        FaultState {
+            active: true,
+            writes_left: None,
+            syncs_left: None,
+            no_space: false,
+            files: HashMap::new(),
+            unsynced_dir_entries: HashSet::new(),
+        }
+    }
+}
+
+impl FaultState {
+    fn check_active(&self) -> io::Result<()> {
+        if self.active {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                "file system inactive after simulated crash",
+            ))
+        }
+    }
+}
+
+#[derive(Default)]
+struct FileState {
+    len: u64,
+    synced_len: u64,
+}
+
+struct FaultFileSystem {
+    inner: Arc<dyn FileSystem>,
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl FaultFileSystem {
+    fn state(&self) -> MutexGuard<'_, FaultState> {
+        self.state.lock().unwrap()
+    }
+
+    fn wrap(&self, path: &Path, inner: Box<dyn WritableFile>) -> Box<dyn WritableFile> {
+        Box::new(FaultWritableFile {
+            inner,
+            path: path.to_path_buf(),
+            state: self.state.clone(),
+        })
+    }
+}
+
+impl FileSystem for FaultFileSystem {
+    fn name(&self) -> &str {
+        "FaultInjectionFileSystem"
+    }
+
+    fn new_sequential_file(&self, path: &Path) -> io::Result<Box<dyn SequentialFile>> {
+        self.inner.new_sequential_file(path)
+    }
+
+    fn new_random_access_file(&self, path: &Path) -> io::Result<Box<dyn RandomAccessFile>> {
+        self.inner.new_random_access_file(path)
+    }
+
+    fn new_writable_file(&self, path: &Path) -> io::Result<Box<dyn WritableFile>> {
+        let mut state = self.state();
+        state.check_active()?;
+        let file = self.inner.new_writable_file(path)?;
+        state.files.insert(path.to_path_buf(), FileState::default());
+        state.unsynced_dir_entries.insert(path.to_path_buf());
+        Ok(self.wrap(path, file))
+    }
+
+    fn reopen_writable_file(&self, path: &Path) -> io::Result<Box<dyn WritableFile>> {
+        let mut state = self.state();
+        state.check_active()?;
+        let file = self.inner.reopen_writable_file(path)?;
+        let len = self.inner.get_file_size(path)?;
+        let file_state = state.files.entry(path.to_path_buf()).or_default();
+        file_state.len = len;
+        file_state.synced_len = file_state.synced_len.min(len);
+        Ok(self.wrap(path, file))
+    }
+
+    fn file_exists(&self, path: &Path) -> io::Result<bool> {
+        self.inner.file_exists(path)
+    }
+
+    fn get_children(&self, dir: &Path) -> io::Result<Vec<OsString>> {
+        self.inner.get_children(dir)
+    }
+
+    fn delete_file(&self, path: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.check_active()?;
+        self.inner.delete_file(path)?;
+        state.files.remove(path);
+        state.unsynced_dir_entries.remove(path);
+        Ok(())
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.state().check_active()?;
+        self.inner.create_dir(path)
+    }
+
+    fn create_dir_if_missing(&self, path: &Path) -> io::Result<()> {
+        self.state().check_active()?;
+        self.inner.create_dir_if_missing(path)
+    }
+
+    fn delete_dir(&self, path: &Path) -> io::Result<()> {
+        self.state().check_active()?;
+        self.inner.delete_dir(path)
+    }
+
+    fn truncate_file(&self, path: &Path, len: u64) -> io::Result<()> {
+        let mut state = self.state();
+        state.check_active()?;
+        self.inner.truncate_file(path, len)?;
+        if let Some(file_state) = state.files.get_mut(path) {
+            file_state.len = len;
+            file_state.synced_len = file_state.synced_len.min(len);
+        }
+        Ok(())
+    }
+
+    fn get_file_size(&self, path: &Path) -> io::Result<u64> {
+        self.inner.get_file_size(path)
+    }
+
+    fn get_file_modification_time(&self, path: &Path) -> io::Result<u64> {
+        self.inner.get_file_modification_time(path)
+    }
+
+    fn rename_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.check_active()?;
+        self.inner.rename_file(src, target)?;
+        if let Some(file) = state.files.remove(src) {
+            state.files.insert(target.to_path_buf(), file);
+        }
+        state.unsynced_dir_entries.remove(src);
+        state.unsynced_dir_entries.insert(target.to_path_buf());
+        Ok(())
+    }
+
+    fn link_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.check_active()?;
+        self.inner.link_file(src, target)?;
+        state.unsynced_dir_entries.insert(target.to_path_buf());
+        Ok(())
+    }
+
+    fn lock_file(&self, path: &Path) -> io::Result<()> {
+        self.inner.lock_file(path)
+    }
+
+    fn unlock_file(&self, path: &Path) -> io::Result<()> {
+        self.inner.unlock_file(path)
+    }
+
+    fn sync_dir(&self, dir: &Path) -> io::Result<()> {
+        let mut state = self.state();
+        state.check_active()?;
+        self.inner.sync_dir(dir)?;
+        state
+            .unsynced_dir_entries
+            .retain(|path| path.parent() != Some(dir));
+        Ok(())
+    }
+
+    fn is_directory(&self, path: &Path) -> io::Result<bool> {
+        self.inner.is_directory(path)
+    }
+}
+
+struct FaultWritableFile {
+    inner: Box<dyn WritableFile>,
+    path: PathBuf,
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl WritableFile for FaultWritableFile {
+    fn append(&mut self, data: &[u8]) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.check_active()?;
+        if state.no_space {
+            return Err(io::Error::from_raw_os_error(libc::ENOSPC));
+        }
+        match state.writes_left {
+            Some(0) => {
+                return Err(io::Error::new(io::ErrorKind::Other, "injected write error"));
+            }
+            Some(ref mut n) => *n -= 1,
+            None => {}
+        }
+        self.inner.append(data)?;
+        if let Some(file) = state.files.get_mut(&self.path) {
+            file.len += data.len() as u64;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.state.lock().unwrap().check_active()?;
+        self.inner.flush()
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.check_active()?;
+        match state.syncs_left {
+            Some(0) => {
+                return Err(io::Error::new(io::ErrorKind::Other, "injected sync error"));
+            }
+            Some(ref mut n) => *n -= 1,
+            None => {}
+        }
+        self.inner.sync()?;
+        if let Some(file) = state.files.get_mut(&self.path) {
+            file.synced_len = file.len;
+        }
+        Ok(())
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        self.inner.close()
+    }
+}
@@ -16,7 +16,9 @@
 use libc::{self, c_char, c_void};
 
 use std::ffi::{CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
+use std::process;
 use std::ptr;
 
 use crate::Error;
@@ -30,6 +32,23 @@ pub fn error_message(ptr: *const c_char) -> String {
     s
 }
 
+/// Takes a string allocated by RocksDB, freeing it.
+///
+/// # Safety
+///
+/// `ptr` must be a non-null, nul-terminated string allocated with `malloc`.
+pub unsafe fn take_string(ptr: *mut c_char) -> String {
+    let s = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+    libc::free(ptr as *mut c_void);
+    s
+}
+
+/// Runs the body of a callback called by RocksDB, aborting the process if it
+/// panics since unwinding into C++ is undefined behavior.
+pub(crate) fn abort_on_panic<R, F: FnOnce() -> R>(f: F) -> R {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| process::abort())
+}
+
 pub fn opt_bytes_to_ptr<T: AsRef<[u8]>>(opt: Option<T>) -> *const c_char {
     match opt {
         Some(v) => v.as_ref().as_ptr() as *const c_char,
@@ -0,0 +1,924 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! File systems implemented in Rust.
+//!
+//! A [`FileSystem`] is installed by creating an environment with
+//! [`Env::from_file_system`] and passing it to [`Options::set_env`]. RocksDB
+//! then does all of its file and directory operations through it, including
+//! writing the info log, while background threads and the clock still come
+//! from the default environment.
+//!
+//! [`StdFileSystem`] implements the trait on top of `std::fs`; wrapping it is
+//! the simplest way to add instrumentation, quotas or fault injection.
+//!
+//! [`Env::from_file_system`]: ../struct.Env.html#method.from_file_system
+//! [`Options::set_env`]: ../struct.Options.html#method.set_env
+
+use libc::{c_char, c_int, c_uchar, c_void, size_t};
+use std::collections::HashMap;
+use std::ffi::{CStr, OsStr, OsString};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::slice;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use crate::{ffi, ffi_util::abort_on_panic};
+
+/// A file read from start to end, such as a write-ahead log or the manifest.
+pub trait SequentialFile: Send {
+    /// Reads up to `buf.len()` bytes, returning fewer only at the end of the
+    /// file.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Skips `n` bytes.
+    fn skip(&mut self, n: u64) -> io::Result<()>;
+}
+
+/// A file read at arbitrary offsets, such as a table file. It is shared by
+/// the threads reading the table.
+pub trait RandomAccessFile: Send + Sync {
+    /// Reads up to `buf.len()` bytes at `offset`, returning fewer only at
+    /// the end of the file.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+}
+
+/// A file written sequentially. RocksDB buffers the appended data itself.
+pub trait WritableFile: Send {
+    fn append(&mut self, data: &[u8]) -> io::Result<()>;
+
+    /// Hands the data appended so far to the operating system.
+    fn flush(&mut self) -> io::Result<()>;
+
+    /// Persists the data appended so far.
+    fn sync(&mut self) -> io::Result<()>;
+
+    fn close(&mut self) -> io::Result<()>;
+}
+
+/// The file and directory operations RocksDB needs.
+///
+/// Errors are passed on to RocksDB by kind: `NotFound`, `Unsupported` and
+/// errors with the `ENOSPC` OS code map to the matching RocksDB statuses,
+/// everything else to an I/O error. A panic in any method, or in the
+/// files it returns, aborts the process.
+pub trait FileSystem: Send + Sync {
+    /// The name reported to RocksDB, e.g. in the info log.
+    fn name(&self) -> &str {
+        "RustFileSystem"
+    }
+
+    fn new_sequential_file(&self, path: &Path) -> io::Result<Box<dyn SequentialFile>>;
+
+    fn new_random_access_file(&self, path: &Path) -> io::Result<Box<dyn RandomAccessFile>>;
+
+    /// Creates a file for writing, truncating any existing file.
+    fn new_writable_file(&self, path: &Path) -> io::Result<Box<dyn WritableFile>>;
+
+    /// Opens an existing file for appending.
+    ///
+    /// Default: unsupported
+    fn reopen_writable_file(&self, _path: &Path) -> io::Result<Box<dyn WritableFile>> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "reopen_writable_file is not supported",
+        ))
+    }
+
+    fn file_exists(&self, path: &Path) -> io::Result<bool>;
+
+    /// Returns the names of the entries of `dir`, without the directory.
+    fn get_children(&self, dir: &Path) -> io::Result<Vec<OsString>>;
+
+    fn delete_file(&self, path: &Path) -> io::Result<()>;
+
+    fn create_dir(&self, path: &Path) -> io::Result<()>;
+
+    fn create_dir_if_missing(&self, path: &Path) -> io::Result<()>;
+
+    fn delete_dir(&self, path: &Path) -> io::Result<()>;
+
+    /// Shortens the file at `path` to `len` bytes. Not called by RocksDB,
+    /// but by `FaultInjectionEnv` to drop the data that was not synced.
+    ///
+    /// Default: unsupported
+    fn truncate_file(&self, _path: &Path, _len: u64) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "truncate_file is not supported",
+        ))
+    }
+
+    fn get_file_size(&self, path: &Path) -> io::Result<u64>;
+
+    /// Returns the modification time in seconds since the Unix epoch.
+    fn get_file_modification_time(&self, path: &Path) -> io::Result<u64>;
+
+    /// Renames `src` to `target`, replacing `target` if it exists.
+    fn rename_file(&self, src: &Path, target: &Path) -> io::Result<()>;
+
+    /// Creates a hard link. When unsupported, checkpoints and backups copy
+    /// the files instead.
+    ///
+    /// Default: unsupported
+    fn link_file(&self, _src: &Path, _target: &Path) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "link_file is not supported",
+        ))
+    }
+
+    /// Locks `path`, creating the file if needed, so that a database is only
+    /// opened once. Fails if the lock is already held.
+    fn lock_file(&self, path: &Path) -> io::Result<()>;
+
+    fn unlock_file(&self, path: &Path) -> io::Result<()>;
+
+    /// Persists the entries of `dir`, e.g. after files were created or
+    /// renamed in it.
+    fn sync_dir(&self, dir: &Path) -> io::Result<()>;
+
+    fn is_directory(&self, path: &Path) -> io::Result<bool>;
+}
+
+/// A file system on top of `std::fs`, working like the default one of
+/// RocksDB.
+///
+/// Locking files is only supported on unix, so a database cannot be opened
+/// with it on other platforms.
+#[derive(Default)]
+pub struct StdFileSystem {
+    locks: Mutex<HashMap<PathBuf, File>>,
+}
+
+impl StdFileSystem {
+    pub fn new() -> StdFileSystem {
+        StdFileSystem::default()
+    }
+}
+
+struct StdSequentialFile(File);
+
+impl SequentialFile for StdSequentialFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.0.read(&mut buf[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(read)
+    }
+
+    fn skip(&mut self, n: u64) -> io::Result<()> {
+        self.0.seek(SeekFrom::Current(n as i64)).map(|_| ())
+    }
+}
+
+struct StdRandomAccessFile(File);
+
+impl StdRandomAccessFile {
+    #[cfg(unix)]
+    fn read_once(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        std::os::unix::fs::FileExt::read_at(&self.0, buf, offset)
+    }
+
+    #[cfg(windows)]
+    fn read_once(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        std::os::windows::fs::FileExt::seek_read(&self.0, buf, offset)
+    }
+}
+
+impl RandomAccessFile for StdRandomAccessFile {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.read_once(offset + read as u64, &mut buf[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(read)
+    }
+}
+
+struct StdWritableFile(File);
+
+impl WritableFile for StdWritableFile {
+    fn append(&mut self, data: &[u8]) -> io::Result<()> {
+        self.0.write_all(data)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.0.sync_data()
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl FileSystem for StdFileSystem {
+    fn name(&self) -> &str {
+        "StdFileSystem"
+    }
+
+    fn new_sequential_file(&self, path: &Path) -> io::Result<Box<dyn SequentialFile>> {
+        Ok(Box::new(StdSequentialFile(File::open(path)?)))
+    }
+
+    fn new_random_access_file(&self, path: &Path) -> io::Result<Box<dyn RandomAccessFile>> {
+        Ok(Box::new(StdRandomAccessFile(File::open(path)?)))
+    }
+
+    fn new_writable_file(&self, path: &Path) -> io::Result<Box<dyn WritableFile>> {
+        Ok(Box::new(StdWritableFile(File::create(path)?)))
+    }
+
+    fn reopen_writable_file(&self, path: &Path) -> io::Result<Box<dyn WritableFile>> {
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+        Ok(Box::new(StdWritableFile(file)))
+    }
+
+    fn file_exists(&self, path: &Path) -> io::Result<bool> {
+        match fs::metadata(path) {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn get_children(&self, dir: &Path) -> io::Result<Vec<OsString>> {
+        fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect()
+    }
+
+    fn delete_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir(path)
+    }
+
+    fn create_dir_if_missing(&self, path: &Path) -> io::Result<()> {
+        match fs::create_dir(path) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && path.is_dir() => Ok(()),
+            result => result,
+        }
+    }
+
+    fn delete_dir(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir(path)
+    }
+
+    fn truncate_file(&self, path: &Path, len: u64) -> io::Result<()> {
+        OpenOptions::new().write(true).open(path)?.set_len(len)
+    }
+
+    fn get_file_size(&self, path: &Path) -> io::Result<u64> {
+        Ok(fs::metadata(path)?.len())
+    }
+
+    fn get_file_modification_time(&self, path: &Path) -> io::Result<u64> {
+        let modified = fs::metadata(path)?.modified()?;
+        modified
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    fn rename_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        fs::rename(src, target)
+    }
+
+    fn link_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        fs::hard_link(src, target)
+    }
+
+    fn lock_file(&self, path: &Path) -> io::Result<()> {
+        let mut locks = self.locks.lock().unwrap();
+        if locks.contains_key(path) {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("lock {} already held by process", path.display()),
+            ));
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        lock(&file)?;
+        locks.insert(path.to_path_buf(), file);
+        Ok(())
+    }
+
+    fn unlock_file(&self, path: &Path) -> io::Result<()> {
+        // Closing the file releases the lock.
+        self.locks.lock().unwrap().remove(path);
+        Ok(())
+    }
+
+    fn sync_dir(&self, dir: &Path) -> io::Result<()> {
+        sync_dir(dir)
+    }
+
+    fn is_directory(&self, path: &Path) -> io::Result<bool> {
+        Ok(fs::metadata(path)?.is_dir())
+    }
+}
+
+#[cfg(unix)]
+fn lock(file: &File) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    unsafe {
+        let mut lock: libc::flock = std::mem::zeroed();
+        lock.l_type = libc::F_WRLCK as _;
+        lock.l_whence = libc::SEEK_SET as _;
+        if libc::fcntl(file.as_raw_fd(), libc::F_SETLK, &lock) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn lock(_file: &File) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "lock_file is only supported on unix",
+    ))
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+// Directories cannot be opened as files, their entries are persisted with
+// the files.
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+pub(crate) fn callbacks<F: FileSystem>() -> ffi::rocksdb_filesystem_callbacks_t {
+    ffi::rocksdb_filesystem_callbacks_t {
+        new_sequential_file: Some(new_sequential_file_callback::<F>),
+        new_random_access_file: Some(new_random_access_file_callback::<F>),
+        new_writable_file: Some(new_writable_file_callback::<F>),
+        reopen_writable_file: Some(reopen_writable_file_callback::<F>),
+        file_exists: Some(file_exists_callback::<F>),
+        get_children: Some(get_children_callback::<F>),
+        delete_file: Some(delete_file_callback::<F>),
+        create_dir: Some(create_dir_callback::<F>),
+        create_dir_if_missing: Some(create_dir_if_missing_callback::<F>),
+        delete_dir: Some(delete_dir_callback::<F>),
+        get_file_size: Some(get_file_size_callback::<F>),
+        get_file_modification_time: Some(get_file_modification_time_callback::<F>),
+        rename_file: Some(rename_file_callback::<F>),
+        link_file: Some(link_file_callback::<F>),
+        lock_file: Some(lock_file_callback::<F>),
+        unlock_file: Some(unlock_file_callback::<F>),
+        sync_dir: Some(sync_dir_callback::<F>),
+        is_directory: Some(is_directory_callback::<F>),
+        sequential_read: Some(sequential_read_callback),
+        sequential_skip: Some(sequential_skip_callback),
+        sequential_destroy: Some(sequential_destroy_callback),
+        random_access_read: Some(random_access_read_callback),
+        random_access_destroy: Some(random_access_destroy_callback),
+        writable_append: Some(writable_append_callback),
+        writable_flush: Some(writable_flush_callback),
+        writable_sync: Some(writable_sync_callback),
+        writable_close: Some(writable_close_callback),
+        writable_destroy: Some(writable_destroy_callback),
+    }
+}
+
+// RocksDB passes paths as bytes, which are paths as they are on unix and
+// UTF-8 elsewhere.
+#[cfg(unix)]
+unsafe fn to_path(path: *const c_char) -> io::Result<PathBuf> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Ok(PathBuf::from(OsStr::from_bytes(
+        CStr::from_ptr(path).to_bytes(),
+    )))
+}
+
+#[cfg(not(unix))]
+unsafe fn to_path(path: *const c_char) -> io::Result<PathBuf> {
+    let path = CStr::from_ptr(path);
+    path.to_str().map(PathBuf::from).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("path {:?} is not valid UTF-8", path),
+        )
+    })
+}
+
+#[cfg(unix)]
+fn to_bytes(name: &OsStr) -> io::Result<&[u8]> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Ok(name.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn to_bytes(name: &OsStr) -> io::Result<&[u8]> {
+    name.to_str().map(str::as_bytes).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("file name {:?} is not valid UTF-8", name),
+        )
+    })
+}
+
+unsafe fn to_malloc_string(s: &[u8]) -> *mut c_char {
+    let buf = libc::malloc(s.len() + 1) as *mut c_char;
+    ptr::copy_nonoverlapping(s.as_ptr() as *const c_char, buf, s.len());
+    *buf.add(s.len()) = 0;
+    buf
+}
+
+unsafe fn to_code<T>(
+    result: io::Result<T>,
+    errmsg: *mut *mut c_char,
+    out: impl FnOnce(T),
+) -> c_int {
+    match result {
+        Ok(value) => {
+            out(value);
+            ffi::rocksdb_fs_ok as c_int
+        }
+        Err(e) => {
+            *errmsg = to_malloc_string(e.to_string().as_bytes());
+            let code = if e.kind() == io::ErrorKind::NotFound {
+                ffi::rocksdb_fs_not_found
+            } else if e.kind() == io::ErrorKind::Unsupported {
+                ffi::rocksdb_fs_not_supported
+            } else if e.raw_os_error() == Some(libc::ENOSPC) {
+                ffi::rocksdb_fs_no_space
+            } else {
+                ffi::rocksdb_fs_io_error
+            };
+            code as c_int
+        }
+    }
+}
+
+pub(crate) unsafe extern "C" fn destructor_callback<F>(raw_cb: *mut c_void) {
+    abort_on_panic(|| {
+        drop(Box::from_raw(raw_cb as *mut F));
+    })
+}
+
+unsafe extern "C" fn new_sequential_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    file: *mut *mut c_void,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(fname).and_then(|path| fs.new_sequential_file(&path)),
+            errmsg,
+            |f| {
+                *file = Box::into_raw(Box::new(f)) as *mut c_void;
+            },
+        )
+    })
+}
+
+unsafe extern "C" fn new_random_access_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    file: *mut *mut c_void,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(fname).and_then(|path| fs.new_random_access_file(&path)),
+            errmsg,
+            |f| {
+                *file = Box::into_raw(Box::new(f)) as *mut c_void;
+            },
+        )
+    })
+}
+
+unsafe extern "C" fn new_writable_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    file: *mut *mut c_void,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(fname).and_then(|path| fs.new_writable_file(&path)),
+            errmsg,
+            |f| {
+                *file = Box::into_raw(Box::new(f)) as *mut c_void;
+            },
+        )
+    })
+}
+
+unsafe extern "C" fn reopen_writable_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    file: *mut *mut c_void,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(fname).and_then(|path| fs.reopen_writable_file(&path)),
+            errmsg,
+            |f| {
+                *file = Box::into_raw(Box::new(f)) as *mut c_void;
+            },
+        )
+    })
+}
+
+unsafe extern "C" fn file_exists_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        match to_path(fname).and_then(|path| fs.file_exists(&path)) {
+            Ok(false) => ffi::rocksdb_fs_not_found as c_int,
+            result => to_code(result, errmsg, |_| ()),
+        }
+    })
+}
+
+unsafe extern "C" fn get_children_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    dir: *const c_char,
+    names: *mut *mut *mut c_char,
+    num_names: *mut size_t,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        let children = to_path(dir).and_then(|dir| {
+            fs.get_children(&dir)?
+                .iter()
+                .map(|child| to_bytes(child).map(<[u8]>::to_vec))
+                .collect::<io::Result<Vec<_>>>()
+        });
+        to_code(children, errmsg, |children| {
+            let array = libc::malloc(children.len() * std::mem::size_of::<*mut c_char>())
+                as *mut *mut c_char;
+            for (i, child) in children.iter().enumerate() {
+                *array.add(i) = to_malloc_string(child);
+            }
+            *names = array;
+            *num_names = children.len();
+        })
+    })
+}
+
+unsafe extern "C" fn delete_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(fname).and_then(|path| fs.delete_file(&path)),
+            errmsg,
+            |_| (),
+        )
+    })
+}
+
+unsafe extern "C" fn create_dir_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    dirname: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(dirname).and_then(|path| fs.create_dir(&path)),
+            errmsg,
+            |_| (),
+        )
+    })
+}
+
+unsafe extern "C" fn create_dir_if_missing_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    dirname: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(dirname).and_then(|path| fs.create_dir_if_missing(&path)),
+            errmsg,
+            |_| (),
+        )
+    })
+}
+
+unsafe extern "C" fn delete_dir_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    dirname: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(dirname).and_then(|path| fs.delete_dir(&path)),
+            errmsg,
+            |_| (),
+        )
+    })
+}
+
+unsafe extern "C" fn get_file_size_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    size: *mut u64,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(fname).and_then(|path| fs.get_file_size(&path)),
+            errmsg,
+            |s| *size = s,
+        )
+    })
+}
+
+unsafe extern "C" fn get_file_modification_time_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    mtime: *mut u64,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(fname).and_then(|path| fs.get_file_modification_time(&path)),
+            errmsg,
+            |t| *mtime = t,
+        )
+    })
+}
+
+unsafe extern "C" fn rename_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    src: *const c_char,
+    target: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(src).and_then(|src| fs.rename_file(&src, &to_path(target)?)),
+            errmsg,
+            |_| (),
+        )
+    })
+}
+
+unsafe extern "C" fn link_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    src: *const c_char,
+    target: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(src).and_then(|src| fs.link_file(&src, &to_path(target)?)),
+            errmsg,
+            |_| (),
+        )
+    })
+}
+
+unsafe extern "C" fn lock_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(fname).and_then(|path| fs.lock_file(&path)),
+            errmsg,
+            |_| (),
+        )
+    })
+}
+
+unsafe extern "C" fn unlock_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(fname).and_then(|path| fs.unlock_file(&path)),
+            errmsg,
+            |_| (),
+        )
+    })
+}
+
+unsafe extern "C" fn sync_dir_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    dirname: *const c_char,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(dirname).and_then(|path| fs.sync_dir(&path)),
+            errmsg,
+            |_| (),
+        )
+    })
+}
+
+unsafe extern "C" fn is_directory_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    is_dir: *mut c_uchar,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let fs = &*(raw_cb as *mut F);
+        to_code(
+            to_path(path).and_then(|path| fs.is_directory(&path)),
+            errmsg,
+            |d| *is_dir = d as c_uchar,
+        )
+    })
+}
+
+unsafe extern "C" fn sequential_read_callback(
+    file: *mut c_void,
+    buf: *mut c_char,
+    n: size_t,
+    read: *mut size_t,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let file = &mut *(file as *mut Box<dyn SequentialFile>);
+        let buf = slice::from_raw_parts_mut(buf as *mut u8, n);
+        to_code(file.read(buf), errmsg, |r| *read = r)
+    })
+}
+
+unsafe extern "C" fn sequential_skip_callback(
+    file: *mut c_void,
+    n: u64,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let file = &mut *(file as *mut Box<dyn SequentialFile>);
+        to_code(file.skip(n), errmsg, |_| ())
+    })
+}
+
+unsafe extern "C" fn sequential_destroy_callback(file: *mut c_void) {
+    abort_on_panic(|| {
+        drop(Box::from_raw(file as *mut Box<dyn SequentialFile>));
+    })
+}
+
+unsafe extern "C" fn random_access_read_callback(
+    file: *mut c_void,
+    offset: u64,
+    buf: *mut c_char,
+    n: size_t,
+    read: *mut size_t,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let file = &*(file as *mut Box<dyn RandomAccessFile>);
+        let buf = slice::from_raw_parts_mut(buf as *mut u8, n);
+        to_code(file.read_at(offset, buf), errmsg, |r| *read = r)
+    })
+}
+
+unsafe extern "C" fn random_access_destroy_callback(file: *mut c_void) {
+    abort_on_panic(|| {
+        drop(Box::from_raw(file as *mut Box<dyn RandomAccessFile>));
+    })
+}
+
+unsafe extern "C" fn writable_append_callback(
+    file: *mut c_void,
+    data: *const c_char,
+    n: size_t,
+    errmsg: *mut *mut c_char,
+) -> c_int {
+    abort_on_panic(|| {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        let data = slice::from_raw_parts(data as *const u8, n);
+        to_code(file.append(data), errmsg, |_| ())
+    })
+}
+
+unsafe extern "C" fn writable_flush_callback(file: *mut c_void, errmsg: *mut *mut c_char) -> c_int {
+    abort_on_panic(|| {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        to_code(file.flush(), errmsg, |_| ())
+    })
+}
+
+unsafe extern "C" fn writable_sync_callback(file: *mut c_void, errmsg: *mut *mut c_char) -> c_int {
+    abort_on_panic(|| {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        to_code(file.sync(), errmsg, |_| ())
+    })
+}
+
+unsafe extern "C" fn writable_close_callback(file: *mut c_void, errmsg: *mut *mut c_char) -> c_int {
+    abort_on_panic(|| {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        to_code(file.close(), errmsg, |_| ())
+    })
+}
+
+unsafe extern "C" fn writable_destroy_callback(file: *mut c_void) {
+    abort_on_panic(|| {
+        drop(Box::from_raw(file as *mut Box<dyn WritableFile>));
+    })
+}
+
+#[cfg(all(test, unix))]
+#[test]
+fn get_children_keeps_non_utf8_names() {
+    use crate::TemporaryDBPath;
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = TemporaryDBPath::new();
+    let dir = path.as_ref().join(OsStr::from_bytes(b"dir\xff"));
+    fs::create_dir_all(&dir).unwrap();
+    File::create(dir.join(OsStr::from_bytes(b"file\xfe"))).unwrap();
+
+    let mut fs = StdFileSystem::new();
+    let cdir = CString::new(dir.as_os_str().as_bytes()).unwrap();
+    let mut names: *mut *mut c_char = ptr::null_mut();
+    let mut num_names: size_t = 0;
+    let mut errmsg: *mut c_char = ptr::null_mut();
+    unsafe {
+        let code = get_children_callback::<StdFileSystem>(
+            &mut fs as *mut StdFileSystem as *mut c_void,
+            cdir.as_ptr(),
+            &mut names,
+            &mut num_names,
+            &mut errmsg,
+        );
+        assert_eq!(code, 0);
+        assert_eq!(num_names, 1);
+        assert_eq!(CStr::from_ptr(*names).to_bytes(), b"file\xfe");
+        libc::free(*names as *mut c_void);
+        libc::free(names as *mut c_void);
+    }
+}
@@ -72,19 +72,28 @@ pub mod column_family;
 pub mod compaction_filter;
 pub mod compaction_filter_factory;
 mod comparator;
+#[cfg(feature = "serde")]
+pub mod config;
 mod db;
 mod db_iterator;
 mod db_options;
 mod db_pinnable_slice;
 mod db_vector;
 mod db_with_ttl;
+pub mod event_listener;
+#[cfg(feature = "fault-injection")]
+mod fault_injection;
+pub mod file_system;
 mod handle;
+pub mod logger;
+mod memory_usage;
 pub mod merge_operator;
 mod open_raw;
 pub mod ops;
 mod optimistic_transaction;
 mod optimistic_transaction_db;
 mod options;
+pub mod properties;
 mod read_only_db;
 mod secondary_db;
 mod slice_transform;
@@ -98,23 +107,31 @@ pub mod prelude;
 
 pub use crate::column_family::ColumnFamilyDescriptor;
 pub use crate::compaction_filter::Decision as CompactionDecision;
+pub use crate::comparator::{BuiltinComparator, CompareFn, ShortSuccessorFn, ShortestSeparatorFn};
 pub use crate::db::DB;
 pub use crate::db_iterator::{DBIterator, DBRawIterator, Direction, IteratorMode};
 pub use crate::db_options::{
-    BlockBasedIndexType, BlockBasedOptions, BottommostLevelCompaction, Cache, CompactOptions,
-    CuckooTableOptions, DBCompactionStyle, DBCompressionType, DBPath, DBRecoveryMode,
-    DataBlockIndexType, Env, FifoCompactOptions, FlushOptions, IngestExternalFileOptions, LogLevel,
-    MemtableFactory, Options, PlainTableFactoryOptions, ReadOptions, UniversalCompactOptions,
-    UniversalCompactionStopStyle, WriteOptions,
+    AccessHint, BlockBasedIndexType, BlockBasedOptions, BottommostLevelCompaction, Cache,
+    CacheEntryRole, CacheEntryStats, CompactOptions, CuckooTableOptions, DBCompactionStyle,
+    DBCompressionType, DBPath, DBRecoveryMode, DataBlockIndexType, Env, FifoCompactOptions,
+    FlushOptions, IngestExternalFileOptions, LogLevel, LruCacheOptions, MemoryAllocator,
+    MemtableFactory, Options, PlainTableFactoryOptions, RateLimiter, RateLimiterMode, ReadOptions,
+    SstFileManager, UniversalCompactOptions, UniversalCompactionStopStyle, WriteBufferManager,
+    WriteOptions,
 };
 pub use crate::db_pinnable_slice::DBPinnableSlice;
 pub use crate::db_vector::DBVector;
 pub use crate::db_with_ttl::{DBWithTTL, TTLOpenDescriptor};
+pub use crate::event_listener::EventListener;
+#[cfg(feature = "fault-injection")]
+pub use crate::fault_injection::FaultInjectionEnv;
+pub use crate::file_system::FileSystem;
 pub use crate::handle::{ConstHandle, Handle};
-pub use crate::options::FullOptions;
+pub use crate::memory_usage::{get_memory_usage_stats, MemoryUsageStats};
+pub use crate::options::{FullOptions, FullOptionsDiff, OptionChange, OptionsRegistry};
 pub use crate::read_only_db::ReadOnlyDB;
 pub use crate::secondary_db::{SecondaryDB, SecondaryOpenDescriptor};
-pub use crate::slice_transform::SliceTransform;
+pub use crate::slice_transform::{InDomainFn, InRangeFn, SliceTransform, TransformFn};
 pub use crate::snapshot::Snapshot;
 pub use crate::sst_file_writer::SstFileWriter;
 pub use crate::util::TemporaryDBPath;
@@ -127,7 +144,9 @@ use std::fmt;
 pub use crate::optimistic_transaction::{OptimisticTransaction, OptimisticTransactionSnapshot};
 pub use crate::optimistic_transaction_db::{OptimisticTransactionDB, OptimisticTransactionOptions};
 pub use crate::transaction::{Transaction, TransactionSnapshot};
-pub use crate::transaction_db::{TransactionDB, TransactionDBOptions, TransactionOptions};
+pub use crate::transaction_db::{
+    TransactionDB, TransactionDBOptions, TransactionOptions, TxnDBWritePolicy,
+};
 
 /// A simple wrapper round a string, used for errors reported from
 /// ffi calls.
@@ -0,0 +1,198 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Forwarding of the RocksDB info log to Rust code.
+//!
+//! A logger is installed with [`Options::set_logger`]; it replaces the
+//! `LOG` file RocksDB otherwise writes into the database directory.
+//! With the `log` feature enabled, [`log_adapter`] builds a logger which
+//! emits the messages through the `log` crate.
+//!
+//! [`Options::set_logger`]: ../struct.Options.html#method.set_logger
+
+use libc::{c_char, c_int, c_void, size_t};
+use std::slice;
+
+use crate::{ffi_util::abort_on_panic, LogLevel};
+
+pub(crate) unsafe extern "C" fn destructor_callback<F>(raw_cb: *mut c_void) {
+    abort_on_panic(|| {
+        drop(Box::from_raw(raw_cb as *mut F));
+    })
+}
+
+pub(crate) unsafe extern "C" fn log_callback<F>(
+    raw_cb: *mut c_void,
+    level: c_int,
+    msg: *const c_char,
+    len: size_t,
+) where
+    F: Fn(LogLevel, &str),
+{
+    abort_on_panic(|| {
+        let cb = &*(raw_cb as *mut F);
+        let level = match level {
+            0 => LogLevel::Debug,
+            1 => LogLevel::Info,
+            2 => LogLevel::Warn,
+            3 => LogLevel::Error,
+            4 => LogLevel::Fatal,
+            _ => LogLevel::Header,
+        };
+        let msg = String::from_utf8_lossy(slice::from_raw_parts(msg as *const u8, len));
+        cb(level, &msg);
+    })
+}
+
+/// Returns a logger for [`Options::set_logger`] which emits the RocksDB
+/// info log through the `log` crate.
+///
+/// Records use the `rocksdb` target. The source location RocksDB prefixes
+/// its messages with is moved into the record's file and line, and the
+/// message is prefixed with `db_path` and, when the message names one, the
+/// column family. `Fatal` maps to `log::Level::Error` and `Header` to
+/// `log::Level::Info`.
+///
+/// [`Options::set_logger`]: ../struct.Options.html#method.set_logger
+///
+/// # Examples
+///
+/// ```
+/// use ckb_rocksdb::{logger, LogLevel, Options};
+///
+/// let db_path = "/var/lib/service/db";
+/// let mut opts = Options::default();
+/// opts.set_logger(LogLevel::Info, logger::log_adapter(db_path));
+/// ```
+#[cfg(feature = "log")]
+pub fn log_adapter<P: AsRef<std::path::Path>>(
+    db_path: P,
+) -> impl Fn(LogLevel, &str) + Send + Sync + 'static {
+    let db_path = db_path.as_ref().to_string_lossy().into_owned();
+    move |level, msg| {
+        let level = match level {
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Info | LogLevel::Header => log::Level::Info,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Error | LogLevel::Fatal => log::Level::Error,
+        };
+        if level > log::max_level() {
+            return;
+        }
+        let line = LogLine::parse(msg);
+        let (file, line_no) = match line.source {
+            Some((file, line_no)) => (Some(file), Some(line_no)),
+            None => (None, None),
+        };
+        let logger = log::logger();
+        match line.cf_name {
+            Some(cf_name) => logger.log(
+                &log::Record::builder()
+                    .args(format_args!("[{}] [{}] {}", db_path, cf_name, line.message))
+                    .level(level)
+                    .target("rocksdb")
+                    .file(file)
+                    .line(line_no)
+                    .build(),
+            ),
+            None => logger.log(
+                &log::Record::builder()
+                    .args(format_args!("[{}] {}", db_path, line.message))
+                    .level(level)
+                    .target("rocksdb")
+                    .file(file)
+                    .line(line_no)
+                    .build(),
+            ),
+        }
+    }
+}
+
+/// A RocksDB log message split into its bracketed prefixes.
+///
+/// Messages look like `[db/flush_job.cc:123] [default] [JOB 2] Flushing`,
+/// where both the source location and the column family are optional.
+#[cfg(feature = "log")]
+#[derive(Debug, PartialEq)]
+struct LogLine<'a> {
+    source: Option<(&'a str, u32)>,
+    cf_name: Option<&'a str>,
+    message: &'a str,
+}
+
+#[cfg(feature = "log")]
+impl<'a> LogLine<'a> {
+    fn parse(msg: &'a str) -> LogLine<'a> {
+        fn bracketed(msg: &str) -> Option<(&str, &str)> {
+            let rest = msg.strip_prefix('[')?;
+            let end = rest.find("] ")?;
+            Some((&rest[..end], &rest[end + 2..]))
+        }
+
+        let mut line = LogLine {
+            source: None,
+            cf_name: None,
+            message: msg,
+        };
+        if let Some((inner, rest)) = bracketed(line.message) {
+            let source = inner
+                .rsplit_once(':')
+                .and_then(|(file, no)| no.parse().ok().map(|no| (file, no)));
+            if source.is_some() {
+                line.source = source;
+                line.message = rest;
+            }
+        }
+        if let Some((inner, rest)) = bracketed(line.message) {
+            if !inner.starts_with("JOB ") {
+                line.cf_name = Some(inner);
+                line.message = rest;
+            }
+        }
+        line
+    }
+}
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use super::LogLine;
+
+    #[test]
+    fn parse_log_line() {
+        assert_eq!(
+            LogLine::parse("[db/flush_job.cc:123] [default] [JOB 2] Flushing memtable"),
+            LogLine {
+                source: Some(("db/flush_job.cc", 123)),
+                cf_name: Some("default"),
+                message: "[JOB 2] Flushing memtable",
+            }
+        );
+        assert_eq!(
+            LogLine::parse("[db/db_impl/db_impl.cc:456] [JOB 3] Deleted file"),
+            LogLine {
+                source: Some(("db/db_impl/db_impl.cc", 456)),
+                cf_name: None,
+                message: "[JOB 3] Deleted file",
+            }
+        );
+        assert_eq!(
+            LogLine::parse("EVENT_LOG_v1 {}"),
+            LogLine {
+                source: None,
+                cf_name: None,
+                message: "EVENT_LOG_v1 {}",
+            }
+        );
+    }
+}
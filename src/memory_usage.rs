@@ -0,0 +1,113 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ffi;
+use crate::{handle::Handle, Cache, Error};
+
+/// Approximate memory usage, in bytes, of a set of databases and caches,
+/// see `get_memory_usage_stats`.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct MemoryUsageStats {
+    /// Memory used by all the memtables, including the flushed ones still
+    /// pinned by iterators
+    pub mem_table_total: u64,
+    /// Memory used by the memtables which are not flushed yet
+    pub mem_table_unflushed: u64,
+    /// Memory used by the table readers, excluding the block cache
+    pub mem_table_readers_total: u64,
+    /// Memory used by the caches
+    pub cache_total: u64,
+}
+
+struct MemoryConsumers {
+    inner: *mut ffi::rocksdb_memory_consumers_t,
+}
+
+impl Drop for MemoryConsumers {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_memory_consumers_destroy(self.inner);
+        }
+    }
+}
+
+struct MemoryUsage {
+    inner: *mut ffi::rocksdb_memory_usage_t,
+}
+
+impl Drop for MemoryUsage {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_approximate_memory_usage_destroy(self.inner);
+        }
+    }
+}
+
+/// Returns the approximate memory usage of `dbs` and `caches` together.
+///
+/// The caches of the databases are not looked up: pass the block and row
+/// caches they were opened with in `caches`. A cache passed several times is
+/// only counted once.
+///
+/// # Examples
+///
+/// ```
+/// use ckb_rocksdb::{get_memory_usage_stats, prelude::*, Cache, TemporaryDBPath};
+///
+/// let path = TemporaryDBPath::new();
+/// let db = DB::open_default(&path).unwrap();
+/// let cache = Cache::new_lru_cache(1024 * 1024).unwrap();
+///
+/// let stats = get_memory_usage_stats(&[&db], &[&cache]).unwrap();
+/// println!("memtables: {} bytes", stats.mem_table_total);
+/// ```
+pub fn get_memory_usage_stats(
+    dbs: &[&dyn Handle<ffi::rocksdb_t>],
+    caches: &[&Cache],
+) -> Result<MemoryUsageStats, Error> {
+    let consumers = unsafe { ffi::rocksdb_memory_consumers_create() };
+    if consumers.is_null() {
+        return Err(Error::new("Could not create memory consumers".to_owned()));
+    }
+    let consumers = MemoryConsumers { inner: consumers };
+    for db in dbs {
+        unsafe {
+            ffi::rocksdb_memory_consumers_add_db(consumers.inner, db.handle());
+        }
+    }
+    for cache in caches {
+        unsafe {
+            ffi::rocksdb_memory_consumers_add_cache(consumers.inner, cache.0.inner);
+        }
+    }
+
+    let usage = unsafe {
+        MemoryUsage {
+            inner: ffi_try!(ffi::rocksdb_approximate_memory_usage_create(
+                consumers.inner
+            )),
+        }
+    };
+    unsafe {
+        Ok(MemoryUsageStats {
+            mem_table_total: ffi::rocksdb_approximate_memory_usage_get_mem_table_total(usage.inner),
+            mem_table_unflushed: ffi::rocksdb_approximate_memory_usage_get_mem_table_unflushed(
+                usage.inner,
+            ),
+            mem_table_readers_total:
+                ffi::rocksdb_approximate_memory_usage_get_mem_table_readers_total(usage.inner),
+            cache_total: ffi::rocksdb_approximate_memory_usage_get_cache_total(usage.inner),
+        })
+    }
+}
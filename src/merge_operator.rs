@@ -65,6 +65,8 @@ use std::mem;
 use std::ptr;
 use std::slice;
 
+use crate::ffi_util::abort_on_panic;
+
 pub trait MergeFn:
     Fn(&[u8], Option<&[u8]>, &mut MergeOperands) -> Option<Vec<u8>> + Send + Sync + 'static
 {
@@ -81,8 +83,10 @@ pub struct MergeOperatorCallback<F: MergeFn, PF: MergeFn> {
 }
 
 pub unsafe extern "C" fn destructor_callback<F: MergeFn, PF: MergeFn>(raw_cb: *mut c_void) {
-    let _: Box<MergeOperatorCallback<F, PF>> =
-        Box::from_raw(raw_cb as *mut MergeOperatorCallback<F, PF>);
+    abort_on_panic(|| {
+        let _: Box<MergeOperatorCallback<F, PF>> =
+            Box::from_raw(raw_cb as *mut MergeOperatorCallback<F, PF>);
+    })
 }
 
 pub unsafe extern "C" fn delete_callback(
@@ -90,12 +94,14 @@ pub unsafe extern "C" fn delete_callback(
     value: *const c_char,
     value_length: size_t,
 ) {
-    if !value.is_null() {
-        let _ = Box::from_raw(slice::from_raw_parts_mut(
-            value as *mut u8,
-            value_length as usize,
-        ));
-    }
+    abort_on_panic(|| {
+        if !value.is_null() {
+            let _ = Box::from_raw(slice::from_raw_parts_mut(
+                value as *mut u8,
+                value_length as usize,
+            ));
+        }
+    })
 }
 
 pub unsafe extern "C" fn name_callback<F: MergeFn, PF: MergeFn>(
@@ -117,29 +123,31 @@ pub unsafe extern "C" fn full_merge_callback<F: MergeFn, PF: MergeFn>(
     success: *mut u8,
     new_value_length: *mut size_t,
 ) -> *mut c_char {
-    let cb = &mut *(raw_cb as *mut MergeOperatorCallback<F, PF>);
-    let operands = &mut MergeOperands::new(operands_list, operands_list_len, num_operands);
-    let key = slice::from_raw_parts(raw_key as *const u8, key_len as usize);
-    let oldval = if existing_value.is_null() {
-        None
-    } else {
-        Some(slice::from_raw_parts(
-            existing_value as *const u8,
-            existing_value_len as usize,
-        ))
-    };
-    (cb.full_merge_fn)(key, oldval, operands).map_or_else(
-        || {
-            *new_value_length = 0;
-            *success = 0_u8;
-            ptr::null_mut() as *mut c_char
-        },
-        |result| {
-            *new_value_length = result.len() as size_t;
-            *success = 1_u8;
-            Box::into_raw(result.into_boxed_slice()) as *mut c_char
-        },
-    )
+    abort_on_panic(|| {
+        let cb = &mut *(raw_cb as *mut MergeOperatorCallback<F, PF>);
+        let operands = &mut MergeOperands::new(operands_list, operands_list_len, num_operands);
+        let key = slice::from_raw_parts(raw_key as *const u8, key_len as usize);
+        let oldval = if existing_value.is_null() {
+            None
+        } else {
+            Some(slice::from_raw_parts(
+                existing_value as *const u8,
+                existing_value_len as usize,
+            ))
+        };
+        (cb.full_merge_fn)(key, oldval, operands).map_or_else(
+            || {
+                *new_value_length = 0;
+                *success = 0_u8;
+                ptr::null_mut() as *mut c_char
+            },
+            |result| {
+                *new_value_length = result.len() as size_t;
+                *success = 1_u8;
+                Box::into_raw(result.into_boxed_slice()) as *mut c_char
+            },
+        )
+    })
 }
 
 pub unsafe extern "C" fn partial_merge_callback<F: MergeFn, PF: MergeFn>(
@@ -152,21 +160,23 @@ pub unsafe extern "C" fn partial_merge_callback<F: MergeFn, PF: MergeFn>(
     success: *mut u8,
     new_value_length: *mut size_t,
 ) -> *mut c_char {
-    let cb = &mut *(raw_cb as *mut MergeOperatorCallback<F, PF>);
-    let operands = &mut MergeOperands::new(operands_list, operands_list_len, num_operands);
-    let key = slice::from_raw_parts(raw_key as *const u8, key_len as usize);
-    (cb.partial_merge_fn)(key, None, operands).map_or_else(
-        || {
-            *new_value_length = 0;
-            *success = 0_u8;
-            ptr::null_mut::<c_char>()
-        },
-        |result| {
-            *new_value_length = result.len() as size_t;
-            *success = 1_u8;
-            Box::into_raw(result.into_boxed_slice()) as *mut c_char
-        },
-    )
+    abort_on_panic(|| {
+        let cb = &mut *(raw_cb as *mut MergeOperatorCallback<F, PF>);
+        let operands = &mut MergeOperands::new(operands_list, operands_list_len, num_operands);
+        let key = slice::from_raw_parts(raw_key as *const u8, key_len as usize);
+        (cb.partial_merge_fn)(key, None, operands).map_or_else(
+            || {
+                *new_value_length = 0;
+                *success = 0_u8;
+                ptr::null_mut::<c_char>()
+            },
+            |result| {
+                *new_value_length = result.len() as size_t;
+                *success = 1_u8;
+                Box::into_raw(result.into_boxed_slice()) as *mut c_char
+            },
+        )
+    })
 }
 
 pub struct MergeOperands {
@@ -52,12 +52,17 @@ where
 {
     fn drop_cf(&mut self, name: &str) -> Result<(), Error> {
         let cf = self
-            .get_mut_cfs()
-            .remove(name)
+            .get_cfs()
+            .get(name)
             .ok_or_else(|| Error::new(format!("Invalid column family: {}", name)))?;
         unsafe {
             ffi_try!(ffi::rocksdb_drop_column_family(self.handle(), cf.inner,));
         }
+        if let Some(cf) = self.get_mut_cfs().remove(name) {
+            unsafe {
+                ffi::rocksdb_column_family_handle_destroy(cf.inner);
+            }
+        }
         Ok(())
     }
 }
@@ -0,0 +1,130 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ffi;
+use libc::{c_char, size_t};
+use std::ptr;
+
+use crate::{handle::Handle, ColumnFamily, Error, WriteOptions};
+
+pub trait DeleteWithTs<W> {
+    fn delete_with_ts_full<K, T>(&self, key: K, ts: T, writeopts: Option<&W>) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>;
+
+    /// Remove the database entry for key as of the user-defined timestamp
+    /// `ts`. Reads at earlier timestamps still see the previous versions.
+    fn delete_with_ts<K, T>(&self, key: K, ts: T) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+    {
+        self.delete_with_ts_full(key, ts, None)
+    }
+
+    fn delete_with_ts_opt<K, T>(&self, key: K, ts: T, writeopts: &W) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+    {
+        self.delete_with_ts_full(key, ts, Some(writeopts))
+    }
+}
+
+pub trait DeleteWithTsCF<W> {
+    fn delete_with_ts_cf_full<K, T>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        key: K,
+        ts: T,
+        writeopts: Option<&W>,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>;
+
+    fn delete_with_ts_cf<K, T>(&self, cf: &ColumnFamily, key: K, ts: T) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+    {
+        self.delete_with_ts_cf_full(Some(cf), key, ts, None)
+    }
+
+    fn delete_with_ts_cf_opt<K, T>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        ts: T,
+        writeopts: &W,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+    {
+        self.delete_with_ts_cf_full(Some(cf), key, ts, Some(writeopts))
+    }
+}
+
+impl<D, W> DeleteWithTs<W> for D
+where
+    D: DeleteWithTsCF<W>,
+{
+    fn delete_with_ts_full<K, T>(&self, key: K, ts: T, writeopts: Option<&W>) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+    {
+        self.delete_with_ts_cf_full(None, key, ts, writeopts)
+    }
+}
+
+impl<D> DeleteWithTsCF<WriteOptions> for D
+where
+    D: Handle<ffi::rocksdb_t> + super::Write,
+{
+    fn delete_with_ts_cf_full<K, T>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        key: K,
+        ts: T,
+        writeopts: Option<&WriteOptions>,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+    {
+        let mut default_writeopts = None;
+
+        let wo_handle = WriteOptions::input_or_default(writeopts, &mut default_writeopts)?;
+
+        let key = key.as_ref();
+        let ts = ts.as_ref();
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_delete_with_ts_cf(
+                self.handle(),
+                wo_handle,
+                cf.map_or(ptr::null_mut(), |cf| cf.handle()),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                ts.as_ptr() as *const c_char,
+                ts.len() as size_t,
+            ));
+
+            Ok(())
+        }
+    }
+}
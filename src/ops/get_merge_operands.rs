@@ -0,0 +1,150 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ffi;
+use libc::{c_char, c_int, size_t};
+use std::ptr;
+
+use crate::{handle::Handle, ColumnFamily, DBVector, Error, ReadOptions};
+
+pub trait GetMergeOperands<R> {
+    fn get_merge_operands_full<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        limit: usize,
+        readopts: Option<&R>,
+    ) -> Result<Vec<DBVector>, Error>;
+
+    /// Return the merge operands stored for a key, without merging them.
+    ///
+    /// The base value, if any, is returned as the first element. At most
+    /// `limit` operands are returned; if more are stored an error is returned.
+    fn get_merge_operands<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        limit: usize,
+    ) -> Result<Vec<DBVector>, Error> {
+        self.get_merge_operands_full(key, limit, None)
+    }
+
+    fn get_merge_operands_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        limit: usize,
+        readopts: &R,
+    ) -> Result<Vec<DBVector>, Error> {
+        self.get_merge_operands_full(key, limit, Some(readopts))
+    }
+}
+
+pub trait GetMergeOperandsCF<R> {
+    fn get_merge_operands_cf_full<K: AsRef<[u8]>>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        key: K,
+        limit: usize,
+        readopts: Option<&R>,
+    ) -> Result<Vec<DBVector>, Error>;
+
+    /// Return the merge operands stored for a key in a column family,
+    /// without merging them.
+    ///
+    /// The base value, if any, is returned as the first element. At most
+    /// `limit` operands are returned; if more are stored an error is returned.
+    fn get_merge_operands_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        limit: usize,
+    ) -> Result<Vec<DBVector>, Error> {
+        self.get_merge_operands_cf_full(Some(cf), key, limit, None)
+    }
+
+    fn get_merge_operands_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        limit: usize,
+        readopts: &R,
+    ) -> Result<Vec<DBVector>, Error> {
+        self.get_merge_operands_cf_full(Some(cf), key, limit, Some(readopts))
+    }
+}
+
+impl<T, R> GetMergeOperands<R> for T
+where
+    T: GetMergeOperandsCF<R>,
+{
+    fn get_merge_operands_full<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        limit: usize,
+        readopts: Option<&R>,
+    ) -> Result<Vec<DBVector>, Error> {
+        self.get_merge_operands_cf_full(None, key, limit, readopts)
+    }
+}
+
+impl<T> GetMergeOperandsCF<ReadOptions> for T
+where
+    T: Handle<ffi::rocksdb_t> + super::Read,
+{
+    fn get_merge_operands_cf_full<K: AsRef<[u8]>>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        key: K,
+        limit: usize,
+        readopts: Option<&ReadOptions>,
+    ) -> Result<Vec<DBVector>, Error> {
+        if limit == 0 || limit > c_int::MAX as usize {
+            return Err(Error::new(format!(
+                "Invalid limit for merge operands: {}",
+                limit
+            )));
+        }
+
+        let mut default_readopts = None;
+
+        let ro_handle = ReadOptions::input_or_default(readopts, &mut default_readopts)?;
+
+        let key = key.as_ref();
+        let key_ptr = key.as_ptr() as *const c_char;
+        let key_len = key.len() as size_t;
+
+        let mut values: Vec<*mut c_char> = vec![ptr::null_mut(); limit];
+        let mut values_sizes: Vec<size_t> = vec![0; limit];
+        let mut num_operands: c_int = 0;
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_get_merge_operands_cf(
+                self.handle(),
+                ro_handle,
+                cf.map_or(ptr::null_mut(), |cf| cf.handle()),
+                key_ptr,
+                key_len,
+                limit as c_int,
+                values.as_mut_ptr(),
+                values_sizes.as_mut_ptr(),
+                &mut num_operands,
+            ));
+
+            Ok(values
+                .into_iter()
+                .zip(values_sizes)
+                .take(num_operands as usize)
+                .map(|(val, val_len)| DBVector::from_c(val as *mut u8, val_len))
+                .collect())
+        }
+    }
+}
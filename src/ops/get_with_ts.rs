@@ -0,0 +1,138 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ffi;
+use libc::{c_char, size_t};
+use std::ptr;
+
+use crate::{handle::Handle, ColumnFamily, DBVector, Error, ReadOptions};
+
+pub trait GetWithTs<R> {
+    fn get_with_ts_full<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        key: K,
+        ts: T,
+        readopts: Option<&R>,
+    ) -> Result<Option<(DBVector, DBVector)>, Error>;
+
+    /// Return the newest version of a key visible at the user-defined
+    /// timestamp `ts`, together with the timestamp it was written at.
+    fn get_with_ts<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        key: K,
+        ts: T,
+    ) -> Result<Option<(DBVector, DBVector)>, Error> {
+        self.get_with_ts_full(key, ts, None)
+    }
+
+    fn get_with_ts_opt<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        key: K,
+        ts: T,
+        readopts: &R,
+    ) -> Result<Option<(DBVector, DBVector)>, Error> {
+        self.get_with_ts_full(key, ts, Some(readopts))
+    }
+}
+
+pub trait GetWithTsCF<R> {
+    fn get_with_ts_cf_full<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        key: K,
+        ts: T,
+        readopts: Option<&R>,
+    ) -> Result<Option<(DBVector, DBVector)>, Error>;
+
+    fn get_with_ts_cf<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        ts: T,
+    ) -> Result<Option<(DBVector, DBVector)>, Error> {
+        self.get_with_ts_cf_full(Some(cf), key, ts, None)
+    }
+
+    fn get_with_ts_cf_opt<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        ts: T,
+        readopts: &R,
+    ) -> Result<Option<(DBVector, DBVector)>, Error> {
+        self.get_with_ts_cf_full(Some(cf), key, ts, Some(readopts))
+    }
+}
+
+impl<D, R> GetWithTs<R> for D
+where
+    D: GetWithTsCF<R>,
+{
+    fn get_with_ts_full<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        key: K,
+        ts: T,
+        readopts: Option<&R>,
+    ) -> Result<Option<(DBVector, DBVector)>, Error> {
+        self.get_with_ts_cf_full(None, key, ts, readopts)
+    }
+}
+
+impl<D> GetWithTsCF<ReadOptions> for D
+where
+    D: Handle<ffi::rocksdb_t> + super::Read,
+{
+    fn get_with_ts_cf_full<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        key: K,
+        ts: T,
+        readopts: Option<&ReadOptions>,
+    ) -> Result<Option<(DBVector, DBVector)>, Error> {
+        let mut default_readopts = None;
+
+        let ro_handle = ReadOptions::input_or_default(readopts, &mut default_readopts)?;
+
+        let key = key.as_ref();
+        let ts = ts.as_ref();
+
+        unsafe {
+            let mut val_len: size_t = 0;
+            let mut found_ts: *mut c_char = ptr::null_mut();
+            let mut found_ts_len: size_t = 0;
+
+            let val = ffi_try!(ffi::rocksdb_get_with_ts_cf(
+                self.handle(),
+                ro_handle,
+                cf.map_or(ptr::null_mut(), |cf| cf.handle()),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                ts.as_ptr() as *const c_char,
+                ts.len() as size_t,
+                &mut val_len,
+                &mut found_ts,
+                &mut found_ts_len,
+            )) as *mut u8;
+
+            if val.is_null() {
+                Ok(None)
+            } else {
+                Ok(Some((
+                    DBVector::from_c(val, val_len),
+                    DBVector::from_c(found_ts as *mut u8, found_ts_len),
+                )))
+            }
+        }
+    }
+}
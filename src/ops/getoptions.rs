@@ -0,0 +1,45 @@
+use crate::{ffi_util::take_string, handle::Handle, ColumnFamily, Error};
+
+pub trait GetOptions {
+    /// Returns the current mutable database options, as a string of
+    /// `name=value;` pairs.
+    fn get_db_options_string(&self) -> Result<String, Error>;
+
+    /// Returns the current mutable options of the default column family, as
+    /// a string of `name=value;` pairs, including the changes made by
+    /// `set_options`.
+    fn get_options_string(&self) -> Result<String, Error>;
+
+    /// Returns the current mutable options of a column family, as a string of
+    /// `name=value;` pairs, including the changes made by `set_options_cf`.
+    fn get_options_string_cf(&self, cf: &ColumnFamily) -> Result<String, Error>;
+}
+
+impl<T> GetOptions for T
+where
+    T: Handle<ffi::rocksdb_t>,
+{
+    fn get_db_options_string(&self) -> Result<String, Error> {
+        unsafe {
+            let value = ffi_try!(ffi::rocksdb_get_db_options_string(self.handle()));
+            Ok(take_string(value))
+        }
+    }
+
+    fn get_options_string(&self) -> Result<String, Error> {
+        unsafe {
+            let value = ffi_try!(ffi::rocksdb_get_options_string(self.handle()));
+            Ok(take_string(value))
+        }
+    }
+
+    fn get_options_string_cf(&self, cf: &ColumnFamily) -> Result<String, Error> {
+        unsafe {
+            let value = ffi_try!(ffi::rocksdb_get_options_string_cf(
+                self.handle(),
+                cf.handle()
+            ));
+            Ok(take_string(value))
+        }
+    }
+}
@@ -16,10 +16,14 @@
 // PIGMED operations (Put, Iterate, Get, Merge, Delete)
 mod columnfamily;
 mod delete;
+mod delete_with_ts;
 mod get;
+mod get_merge_operands;
 mod get_pinned;
+mod get_with_ts;
 mod merge;
 mod put;
+mod put_with_ts;
 mod writebatch;
 
 mod open;
@@ -27,6 +31,7 @@ mod open;
 mod checkpoint;
 mod compact;
 mod flush;
+mod getoptions;
 mod ingest_external_file;
 mod iter;
 mod property;
@@ -34,14 +39,18 @@ mod setoptions;
 mod transaction;
 
 pub use self::delete::{Delete, DeleteCF};
+pub use self::delete_with_ts::{DeleteWithTs, DeleteWithTsCF};
 pub use self::get::{Get, GetCF};
+pub use self::get_merge_operands::{GetMergeOperands, GetMergeOperandsCF};
 pub use self::get_pinned::{GetPinned, GetPinnedCF};
+pub use self::get_with_ts::{GetWithTs, GetWithTsCF};
 pub use self::ingest_external_file::{IngestExternalFile, IngestExternalFileCF};
 pub use self::merge::{Merge, MergeCF};
 pub use self::put::{Put, PutCF};
+pub use self::put_with_ts::{PutWithTs, PutWithTsCF};
 pub use self::writebatch::WriteOps;
 
-pub use self::open::{Open, OpenCF};
+pub use self::open::{Open, OpenCF, OpenWithLatestOptions};
 
 /// Marker trait for operations that leave DB
 /// state unchanged
@@ -57,6 +66,7 @@ pub use self::columnfamily::DropCF;
 pub use self::columnfamily::GetColumnFamilys;
 pub use self::compact::{CompactRange, CompactRangeCF};
 pub use self::flush::Flush;
+pub use self::getoptions::GetOptions;
 pub use self::iter::{Iterate, IterateCF};
 pub use self::property::{GetProperty, GetPropertyCF};
 pub use self::setoptions::SetOptions;
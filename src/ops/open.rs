@@ -17,7 +17,7 @@ use std::iter;
 use std::path::Path;
 
 use crate::open_raw::{OpenRaw, OpenRawInput};
-use crate::{ColumnFamilyDescriptor, Error, Options};
+use crate::{Cache, ColumnFamilyDescriptor, Env, Error, FullOptions, Options, OptionsRegistry};
 
 pub trait Open: OpenRaw {
     /// Open a database with default options.
@@ -100,3 +100,39 @@ pub trait OpenCF: OpenRaw {
         Self::open_raw(input)
     }
 }
+
+pub trait OpenWithLatestOptions: OpenRaw {
+    /// Open a database, with all its column families, using the options stored
+    /// in its latest OPTIONS file, see `FullOptions::load_latest`.
+    ///
+    /// The comparators, merge operators and compaction filters the file
+    /// refers to by name are taken from `registry`. Opening fails if one of
+    /// them isn't registered.
+    fn open_with_latest_options<P: AsRef<Path>>(
+        path: P,
+        env: &Env,
+        cache: Option<&Cache>,
+        registry: &OptionsRegistry,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let (mut full_opts, unresolved) = FullOptions::load_latest_unresolved(path, env, cache)?;
+        registry.resolve(&mut full_opts, unresolved)?;
+
+        let FullOptions {
+            db_opts,
+            cf_descriptors,
+        } = full_opts;
+        let outlive = iter::once(db_opts.outlive.clone())
+            .chain(cf_descriptors.iter().map(|cf| cf.options.outlive.clone()))
+            .collect();
+        let input = OpenRawInput {
+            options: &db_opts,
+            path,
+            column_families: cf_descriptors,
+            open_descriptor: Self::Descriptor::default(),
+            outlive,
+        };
+
+        Self::open_raw(input)
+    }
+}
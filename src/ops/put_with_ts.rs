@@ -0,0 +1,165 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ffi;
+use libc::{c_char, size_t};
+use std::ptr;
+
+use crate::{handle::Handle, ColumnFamily, Error, WriteOptions};
+
+pub trait PutWithTs<W> {
+    fn put_with_ts_full<K, T, V>(
+        &self,
+        key: K,
+        ts: T,
+        value: V,
+        writeopts: Option<&W>,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>;
+
+    /// Insert a value under the given key, versioned by the user-defined
+    /// timestamp `ts`.
+    ///
+    /// The column family comparator must support timestamps of the size of
+    /// `ts`, see `BuiltinComparator::BytewiseWithU64Ts`.
+    fn put_with_ts<K, T, V>(&self, key: K, ts: T, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.put_with_ts_full(key, ts, value, None)
+    }
+
+    fn put_with_ts_opt<K, T, V>(&self, key: K, ts: T, value: V, writeopts: &W) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.put_with_ts_full(key, ts, value, Some(writeopts))
+    }
+}
+
+pub trait PutWithTsCF<W> {
+    fn put_with_ts_cf_full<K, T, V>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        key: K,
+        ts: T,
+        value: V,
+        writeopts: Option<&W>,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>;
+
+    fn put_with_ts_cf<K, T, V>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        ts: T,
+        value: V,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.put_with_ts_cf_full(Some(cf), key, ts, value, None)
+    }
+
+    fn put_with_ts_cf_opt<K, T, V>(
+        &self,
+        cf: &ColumnFamily,
+        key: K,
+        ts: T,
+        value: V,
+        writeopts: &W,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.put_with_ts_cf_full(Some(cf), key, ts, value, Some(writeopts))
+    }
+}
+
+impl<D, W> PutWithTs<W> for D
+where
+    D: PutWithTsCF<W>,
+{
+    fn put_with_ts_full<K, T, V>(
+        &self,
+        key: K,
+        ts: T,
+        value: V,
+        writeopts: Option<&W>,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.put_with_ts_cf_full(None, key, ts, value, writeopts)
+    }
+}
+
+impl<D> PutWithTsCF<WriteOptions> for D
+where
+    D: Handle<ffi::rocksdb_t> + super::Write,
+{
+    fn put_with_ts_cf_full<K, T, V>(
+        &self,
+        cf: Option<&ColumnFamily>,
+        key: K,
+        ts: T,
+        value: V,
+        writeopts: Option<&WriteOptions>,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let mut default_writeopts = None;
+
+        let wo_handle = WriteOptions::input_or_default(writeopts, &mut default_writeopts)?;
+
+        let key = key.as_ref();
+        let ts = ts.as_ref();
+        let value = value.as_ref();
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_put_with_ts_cf(
+                self.handle(),
+                wo_handle,
+                cf.map_or(ptr::null_mut(), |cf| cf.handle()),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                ts.as_ptr() as *const c_char,
+                ts.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            ));
+
+            Ok(())
+        }
+    }
+}
@@ -34,6 +34,18 @@ where
 
         let wo_handle = WriteOptions::input_or_default(writeopts, &mut default_writeopts)?;
 
+        if let Some(ts_size) = batch.default_ts_size() {
+            let expected =
+                unsafe { ffi::rocksdb_default_column_family_timestamp_size(self.handle()) };
+            if ts_size != expected {
+                return Err(Error::new(format!(
+                    "Invalid argument: Timestamp size {} does not match the comparator \
+                     timestamp size {}",
+                    ts_size, expected
+                )));
+            }
+        }
+
         unsafe {
             ffi_try!(ffi::rocksdb_write(self.handle(), wo_handle, batch.handle(),));
             Ok(())
@@ -199,6 +199,7 @@ impl Iterate for OptimisticTransaction {
             DBRawIterator {
                 inner: ffi::rocksdb_transaction_create_iterator(self.inner, readopts.handle()),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             }
         }
     }
@@ -218,6 +219,7 @@ impl IterateCF for OptimisticTransaction {
                     cf_handle.inner,
                 ),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             })
         }
     }
@@ -226,6 +226,7 @@ impl Iterate for OptimisticTransactionDB {
             DBRawIterator {
                 inner: ffi::rocksdb_create_iterator(self.base_db, readopts.handle()),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             }
         }
     }
@@ -245,6 +246,7 @@ impl IterateCF for OptimisticTransactionDB {
                     cf_handle.inner,
                 ),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             })
         }
     }
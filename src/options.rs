@@ -12,19 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{ffi::CStr, path};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{CStr, CString};
+use std::path;
+
+use libc::c_uchar;
 
 use crate::{
-    db_options::{Cache, OptionsMustOutliveDB},
+    db_options::{Cache, Env, OptionsMustOutliveDB},
     ffi, ffi_util, ColumnFamilyDescriptor, Error, Options,
 };
 
+/// The options which an OPTIONS file records by name only.
+const NAMED_OPTIONS: [&str; 3] = ["comparator", "merge_operator", "compaction_filter"];
+
 #[derive(Clone)]
 pub struct FullOptions {
     pub db_opts: Options,
     pub cf_descriptors: Vec<ColumnFamilyDescriptor>,
 }
 
+/// Names of the objects an OPTIONS file refers to but which could not be
+/// restored, by column family and option.
+pub(crate) type UnresolvedOptions = Vec<Vec<(&'static str, String)>>;
+
 impl FullOptions {
     pub fn load_from_file<P>(
         file: P,
@@ -54,40 +65,111 @@ impl FullOptions {
                     .unwrap_or_else(|| ffi::rocksdb_null_cache()),
             ));
             ffi::rocksdb_env_destroy(env);
-            let db_opts = result.db_opts;
-            let cf_descs = result.cf_descs;
-            let cf_descs_size = ffi::rocksdb_column_family_descriptors_count(cf_descs);
-            let mut cf_descriptors = Vec::new();
-            for index in 0..cf_descs_size {
-                let name_raw = ffi::rocksdb_column_family_descriptors_name(cf_descs, index);
-                let name_cstr = CStr::from_ptr(name_raw as *const _);
-                let name = String::from_utf8_lossy(name_cstr.to_bytes());
-                let cf_opts_inner = ffi::rocksdb_column_family_descriptors_options(cf_descs, index);
-                let outlive = OptionsMustOutliveDB {
-                    row_cache: cache.clone(),
-                    ..Default::default()
-                };
-                let cf_opts = Options {
-                    inner: cf_opts_inner,
-                    outlive,
-                };
-                cf_descriptors.push(ColumnFamilyDescriptor::new(name, cf_opts));
-            }
-            ffi::rocksdb_column_family_descriptors_destroy(cf_descs);
+            Ok(Self::from_ffi(result, cache, None).0)
+        }
+    }
 
+    /// Loads the options from the latest OPTIONS file of the database at
+    /// `db_path`, which RocksDB writes every time the database is opened or
+    /// its options are changed.
+    ///
+    /// The files are read through `env`, which is also set on the database
+    /// options. If `cache` is given, it becomes the block cache of every
+    /// column family; otherwise each one gets a cache of the size recorded in
+    /// the file.
+    ///
+    /// Comparators, merge operators and compaction filters implemented in
+    /// Rust are only recorded by name and are not restored, see
+    /// `open_with_latest_options`.
+    pub fn load_latest<P>(db_path: P, env: &Env, cache: Option<&Cache>) -> Result<Self, Error>
+    where
+        P: AsRef<path::Path>,
+    {
+        Self::load_latest_unresolved(db_path, env, cache).map(|(full_opts, _)| full_opts)
+    }
+
+    pub(crate) fn load_latest_unresolved<P>(
+        db_path: P,
+        env: &Env,
+        cache: Option<&Cache>,
+    ) -> Result<(Self, UnresolvedOptions), Error>
+    where
+        P: AsRef<path::Path>,
+    {
+        let cpath = ffi_util::to_cpath(
+            db_path,
+            "Failed to convert path to CString when load config file.",
+        )?;
+
+        unsafe {
+            let result = ffi_try!(ffi::rocksdb_options_load_latest(
+                cpath.as_ptr(),
+                env.0.inner,
+                false,
+                cache.map_or(std::ptr::null_mut(), |c| c.0.inner),
+            ));
+            Ok(Self::from_ffi(result, cache.cloned(), Some(env.clone())))
+        }
+    }
+
+    unsafe fn from_ffi(
+        result: ffi::rocksdb_fulloptions_t,
+        cache: Option<Cache>,
+        env: Option<Env>,
+    ) -> (Self, UnresolvedOptions) {
+        let db_opts = result.db_opts;
+        let cf_descs = result.cf_descs;
+        let cf_descs_size = ffi::rocksdb_column_family_descriptors_count(cf_descs);
+        let mut cf_descriptors = Vec::new();
+        let mut unresolved = Vec::new();
+        for index in 0..cf_descs_size {
+            let name_raw = ffi::rocksdb_column_family_descriptors_name(cf_descs, index);
+            let name_cstr = CStr::from_ptr(name_raw as *const _);
+            let name = String::from_utf8_lossy(name_cstr.to_bytes());
+            let cf_opts_inner = ffi::rocksdb_column_family_descriptors_options(cf_descs, index);
             let outlive = OptionsMustOutliveDB {
-                row_cache: cache,
+                row_cache: cache.clone(),
                 ..Default::default()
             };
+            let cf_opts = Options {
+                inner: cf_opts_inner,
+                outlive,
+            };
+            cf_descriptors.push(ColumnFamilyDescriptor::new(name, cf_opts));
+
+            let mut cf_unresolved = Vec::new();
+            for option in &NAMED_OPTIONS {
+                let option_name = CString::new(*option).unwrap();
+                let value = ffi::rocksdb_column_family_descriptors_unresolved_option(
+                    cf_descs,
+                    index,
+                    option_name.as_ptr(),
+                );
+                if !value.is_null() {
+                    let value = CStr::from_ptr(value).to_string_lossy().into_owned();
+                    cf_unresolved.push((*option, value));
+                }
+            }
+            unresolved.push(cf_unresolved);
+        }
+        ffi::rocksdb_column_family_descriptors_destroy(cf_descs);
 
-            Ok(Self {
+        let outlive = OptionsMustOutliveDB {
+            env,
+            row_cache: cache,
+            ..Default::default()
+        };
+
+        (
+            Self {
                 db_opts: Options {
                     inner: db_opts,
                     outlive,
                 },
                 cf_descriptors,
-            })
-        }
+            },
+            unresolved,
+        )
     }
 
     /* This method is used to check those column families which are ignored in the options file,
@@ -149,4 +231,352 @@ impl FullOptions {
         }
         Ok(())
     }
+
+    /// Checks that the database at `db_path` can be opened with these
+    /// options, against its latest OPTIONS file.
+    ///
+    /// The descriptors are matched to the column families of the file by
+    /// name and may come in any order; those not yet in the database are
+    /// skipped, and a missing "default" one falls back to default options as
+    /// when opening. The file is read through the env of `db_opts`.
+    ///
+    /// The error tells which option of which column family is incompatible,
+    /// such as a changed comparator, merge operator, prefix extractor or
+    /// table format.
+    pub fn check_compatibility<P>(
+        &self,
+        db_path: P,
+        ignore_unknown_options: bool,
+    ) -> Result<(), Error>
+    where
+        P: AsRef<path::Path>,
+    {
+        let cpath = ffi_util::to_cpath(
+            db_path,
+            "Failed to convert path to CString when check options.",
+        )?;
+
+        let default_opts = Options::default();
+        let cfs = self.column_families(&default_opts);
+
+        let c_cfs = cfs
+            .iter()
+            .map(|(name, _)| {
+                CString::new(name.as_bytes()).map_err(|_| {
+                    Error::new(format!(
+                        "Failed to convert column family name to CString: {}",
+                        name
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let cf_names: Vec<_> = c_cfs.iter().map(|name| name.as_ptr()).collect();
+        let cf_opts: Vec<_> = cfs.iter().map(|(_, opts)| opts.inner as *const _).collect();
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_options_check_compatibility(
+                cpath.as_ptr(),
+                self.db_opts.inner,
+                cfs.len() as libc::c_int,
+                cf_names.as_ptr(),
+                cf_opts.as_ptr(),
+                ignore_unknown_options,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Compares these options with `new`, such as those loaded with
+    /// `load_latest` with those the database is about to be opened with.
+    ///
+    /// Column families are matched by name, with a missing "default" one
+    /// taking default options as when opening. The changes to the options of
+    /// the database and of each column family are listed by name, in
+    /// alphabetical order, along with whether `set_options` or
+    /// `set_options_cf` could apply them to the open database instead.
+    ///
+    /// The options are compared as they are set. RocksDB adjusts some of them
+    /// when opening and records the adjusted values in the OPTIONS file, such
+    /// as a `delayed_write_rate` or `arena_block_size` of 0, the default
+    /// `ttl`, `periodic_compaction_seconds` and `max_compaction_bytes`, or a
+    /// `max_open_files` out of range. Fresh options therefore differ from
+    /// those loaded from the OPTIONS file in those, unlike options derived
+    /// from the loaded ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ckb_rocksdb::{FullOptions, Options};
+    ///
+    /// let old = FullOptions {
+    ///     db_opts: Options::default(),
+    ///     cf_descriptors: vec![],
+    /// };
+    /// let mut new = old.clone();
+    /// new.db_opts.set_write_buffer_size(1 << 20);
+    ///
+    /// let diff = old.diff(&new).unwrap();
+    /// let change = &diff.column_families[0].1[0];
+    /// assert_eq!(change.name, "write_buffer_size");
+    /// assert_eq!(change.new_value.as_deref(), Some("1048576"));
+    /// assert!(change.mutable);
+    /// ```
+    pub fn diff(&self, new: &FullOptions) -> Result<FullOptionsDiff, Error> {
+        let mut diff = FullOptionsDiff {
+            db_options: diff_options(&self.db_opts, &new.db_opts, false)?,
+            ..Default::default()
+        };
+
+        let default_opts = Options::default();
+        let old_cfs = self.column_families(&default_opts);
+        let new_cfs = new.column_families(&default_opts);
+        for (name, old_opts) in &old_cfs {
+            match new_cfs.iter().find(|(new_name, _)| new_name == name) {
+                Some((_, new_opts)) => {
+                    let changes = diff_options(old_opts, new_opts, true)?;
+                    if !changes.is_empty() {
+                        diff.column_families.push((name.to_string(), changes));
+                    }
+                }
+                None => diff.dropped_column_families.push(name.to_string()),
+            }
+        }
+        for (name, _) in &new_cfs {
+            if !old_cfs.iter().any(|(old_name, _)| old_name == name) {
+                diff.added_column_families.push(name.to_string());
+            }
+        }
+        Ok(diff)
+    }
+
+    /// The options of the column families as when opening, where the
+    /// database options stand for the "default" one if none is given.
+    fn column_families<'a>(&'a self, default_opts: &'a Options) -> Vec<(&'a str, &'a Options)> {
+        let mut cfs: Vec<(&str, &Options)> = self
+            .cf_descriptors
+            .iter()
+            .map(|cf| (cf.name.as_str(), &cf.options))
+            .collect();
+        if cfs.is_empty() {
+            cfs.push(("default", &self.db_opts));
+        } else if !cfs.iter().any(|(name, _)| *name == "default") {
+            cfs.push(("default", default_opts));
+        }
+        cfs
+    }
+}
+
+/// The differences between two `FullOptions`, see `FullOptions::diff`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FullOptionsDiff {
+    /// Changes to the database options
+    pub db_options: Vec<OptionChange>,
+    /// Changes to the options of the column families on both sides, for
+    /// those which changed
+    pub column_families: Vec<(String, Vec<OptionChange>)>,
+    /// Column families only on the new side
+    pub added_column_families: Vec<String>,
+    /// Column families only on the old side
+    pub dropped_column_families: Vec<String>,
+}
+
+impl FullOptionsDiff {
+    /// Returns true if both sides have the same options.
+    pub fn is_empty(&self) -> bool {
+        self.db_options.is_empty()
+            && self.column_families.is_empty()
+            && self.added_column_families.is_empty()
+            && self.dropped_column_families.is_empty()
+    }
+}
+
+/// An option which differs between two `FullOptions`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionChange {
+    /// Name of the option, with those of nested options prefixed by the
+    /// name of their parent, such as `table_factory.block_size`
+    pub name: String,
+    /// The old value, `None` if only set on the new side
+    pub old_value: Option<String>,
+    /// The new value, `None` if only set on the old side
+    pub new_value: Option<String>,
+    /// Whether the option can be changed on an open database
+    pub mutable: bool,
+}
+
+fn diff_options(
+    old: &Options,
+    new: &Options,
+    column_family: bool,
+) -> Result<Vec<OptionChange>, Error> {
+    let mut old_values = options_map(old, column_family, false)?;
+    let new_values = options_map(new, column_family, false)?;
+    let mutable = options_map(new, column_family, true)?;
+
+    let mut changes = Vec::new();
+    for (name, new_value) in new_values {
+        let old_value = old_values.remove(&name);
+        if old_value.as_ref() != Some(&new_value) {
+            changes.push(OptionChange {
+                mutable: mutable.contains_key(&name) && !is_id(&name),
+                name,
+                old_value,
+                new_value: Some(new_value),
+            });
+        }
+    }
+    for (name, old_value) in old_values {
+        changes.push(OptionChange {
+            mutable: false,
+            name,
+            old_value: Some(old_value),
+            new_value: None,
+        });
+    }
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(changes)
+}
+
+/// The type of a nested object, such as the table factory, which is listed
+/// with its options but cannot be changed on an open database.
+fn is_id(name: &str) -> bool {
+    name.ends_with(".id")
+}
+
+/// Reads the options, or only the mutable ones, into a map by name, with
+/// nested options flattened.
+fn options_map(
+    opts: &Options,
+    column_family: bool,
+    mutable_only: bool,
+) -> Result<BTreeMap<String, String>, Error> {
+    let value = unsafe {
+        if column_family {
+            ffi_try!(ffi::rocksdb_options_get_cf_options_string(
+                opts.inner,
+                mutable_only as c_uchar,
+            ))
+        } else {
+            ffi_try!(ffi::rocksdb_options_get_db_options_string(
+                opts.inner,
+                mutable_only as c_uchar,
+            ))
+        }
+    };
+    let opts_str = unsafe { ffi_util::take_string(value) };
+    parse_options_string(&opts_str)
+}
+
+/// Parses `name=value;` pairs with RocksDB's parser, with nested options
+/// flattened.
+fn parse_options_string(opts_str: &str) -> Result<BTreeMap<String, String>, Error> {
+    let opts_str = ffi_util::to_cstring(opts_str, "Failed to convert options string to CString")?;
+    let mut map = BTreeMap::new();
+    unsafe {
+        let opts_map = ffi_try!(ffi::rocksdb_options_string_to_map(opts_str.as_ptr()));
+        for i in 0..ffi::rocksdb_options_map_count(opts_map) {
+            let name = CStr::from_ptr(ffi::rocksdb_options_map_name(opts_map, i));
+            let value = CStr::from_ptr(ffi::rocksdb_options_map_value(opts_map, i));
+            map.insert(
+                name.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            );
+        }
+        ffi::rocksdb_options_map_destroy(opts_map);
+    }
+    Ok(map)
+}
+
+type OptionsHook = Box<dyn Fn(&mut Options) + Send + Sync>;
+
+/// The comparators, merge operators and compaction filters implemented in
+/// Rust, which an OPTIONS file only records by name, for
+/// `open_with_latest_options`.
+///
+/// Each one is registered under its name with a function setting it on the
+/// options of a column family.
+///
+/// # Examples
+///
+/// ```
+/// use ckb_rocksdb::{MergeOperands, OptionsRegistry};
+///
+/// fn concat(_key: &[u8], existing: Option<&[u8]>, operands: &mut MergeOperands) -> Option<Vec<u8>> {
+///     let mut result = existing.map(|v| v.to_vec()).unwrap_or_default();
+///     for op in operands {
+///         result.extend_from_slice(op);
+///     }
+///     Some(result)
+/// }
+///
+/// let mut registry = OptionsRegistry::default();
+/// registry.register_merge_operator("concat", |opts| {
+///     opts.set_merge_operator_associative("concat", concat)
+/// });
+/// registry.register_comparator("reverse", |opts| {
+///     opts.set_comparator("reverse", Box::new(|a: &[u8], b: &[u8]| b.cmp(a)))
+/// });
+/// ```
+#[derive(Default)]
+pub struct OptionsRegistry {
+    hooks: HashMap<(&'static str, String), OptionsHook>,
+}
+
+impl OptionsRegistry {
+    /// Registers the comparator named `name`.
+    pub fn register_comparator<F>(&mut self, name: &str, set: F) -> &mut Self
+    where
+        F: Fn(&mut Options) + Send + Sync + 'static,
+    {
+        self.register("comparator", name, set)
+    }
+
+    /// Registers the merge operator named `name`.
+    pub fn register_merge_operator<F>(&mut self, name: &str, set: F) -> &mut Self
+    where
+        F: Fn(&mut Options) + Send + Sync + 'static,
+    {
+        self.register("merge_operator", name, set)
+    }
+
+    /// Registers the compaction filter named `name`.
+    pub fn register_compaction_filter<F>(&mut self, name: &str, set: F) -> &mut Self
+    where
+        F: Fn(&mut Options) + Send + Sync + 'static,
+    {
+        self.register("compaction_filter", name, set)
+    }
+
+    fn register<F>(&mut self, option: &'static str, name: &str, set: F) -> &mut Self
+    where
+        F: Fn(&mut Options) + Send + Sync + 'static,
+    {
+        self.hooks.insert((option, name.to_owned()), Box::new(set));
+        self
+    }
+
+    /// Sets the registered objects the column families refer to, failing if
+    /// one of them isn't registered.
+    pub(crate) fn resolve(
+        &self,
+        full_opts: &mut FullOptions,
+        unresolved: UnresolvedOptions,
+    ) -> Result<(), Error> {
+        for (cf, cf_unresolved) in full_opts.cf_descriptors.iter_mut().zip(unresolved) {
+            for key in cf_unresolved {
+                match self.hooks.get(&key) {
+                    Some(set) => set(&mut cf.options),
+                    None => {
+                        return Err(Error::new(format!(
+                            "no {} registered as \"{}\" for column family \"{}\"",
+                            key.0.replace('_', " "),
+                            key.1,
+                            cf.name
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 }
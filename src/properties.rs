@@ -0,0 +1,30 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Names of database properties, to be read with
+//! [`GetProperty`](../ops/trait.GetProperty.html) and
+//! [`GetPropertyCF`](../ops/trait.GetPropertyCF.html).
+
+/// Number of blob files in the current version.
+pub const NUM_BLOB_FILES: &str = "rocksdb.num-blob-files";
+
+/// Total number and size of the blob files, and the amount of garbage in
+/// them, as a string.
+pub const BLOB_STATS: &str = "rocksdb.blob-stats";
+
+/// Total size of the blob files of all versions.
+pub const TOTAL_BLOB_FILE_SIZE: &str = "rocksdb.total-blob-file-size";
+
+/// Total size of the blob files of the current version.
+pub const LIVE_BLOB_FILE_SIZE: &str = "rocksdb.live-blob-file-size";
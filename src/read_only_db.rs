@@ -49,6 +49,7 @@ pub struct ReadOnlyOpenDescriptor {
 
 impl ops::Open for ReadOnlyDB {}
 impl ops::OpenCF for ReadOnlyDB {}
+impl ops::OpenWithLatestOptions for ReadOnlyDB {}
 
 impl OpenRaw for ReadOnlyDB {
     type Pointer = ffi::rocksdb_t;
@@ -114,6 +115,7 @@ impl ops::Iterate for ReadOnlyDB {
             DBRawIterator {
                 inner: ffi::rocksdb_create_iterator(self.inner, readopts.handle()),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             }
         }
     }
@@ -133,6 +135,7 @@ impl ops::IterateCF for ReadOnlyDB {
                     cf_handle.inner,
                 ),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             })
         }
     }
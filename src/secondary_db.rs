@@ -140,6 +140,7 @@ impl ops::Iterate for SecondaryDB {
             DBRawIterator {
                 inner: ffi::rocksdb_create_iterator(self.inner, readopts.handle()),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             }
         }
     }
@@ -159,6 +160,7 @@ impl ops::IterateCF for SecondaryDB {
                     cf_handle.inner,
                 ),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             })
         }
     }
@@ -18,6 +18,7 @@ use std::slice;
 use libc::{c_char, c_void, size_t};
 
 use crate::ffi;
+use crate::ffi_util::abort_on_panic;
 
 /// A SliceTranform is a generic pluggable way of transforming one string
 /// to another. Its primary use-case is in configuring rocksdb
@@ -34,15 +35,33 @@ pub struct SliceTransform {
 // opening a DB.
 
 impl SliceTransform {
+    /// Creates a transform from closures, which may capture configuration
+    /// such as a runtime-defined key layout.
+    ///
+    /// `in_domain_fn` defaults to accepting every key. Iterators may still
+    /// call `transform_fn` on keys outside the domain, so it must not panic
+    /// on them: a panic in any of the closures aborts the process.
     pub fn create(
         name: &str,
-        transform_fn: TransformFn<'_>,
-        in_domain_fn: Option<InDomainFn>,
+        transform_fn: Box<TransformFn>,
+        in_domain_fn: Option<Box<InDomainFn>>,
+    ) -> SliceTransform {
+        SliceTransform::create_with_in_range(name, transform_fn, in_domain_fn, None)
+    }
+
+    /// Like [`create`](#method.create), additionally taking the `in_range`
+    /// check, which defaults to rejecting every key.
+    pub fn create_with_in_range(
+        name: &str,
+        transform_fn: Box<TransformFn>,
+        in_domain_fn: Option<Box<InDomainFn>>,
+        in_range_fn: Option<Box<InRangeFn>>,
     ) -> SliceTransform {
         let cb = Box::into_raw(Box::new(TransformCallback {
             name: CString::new(name.as_bytes()).unwrap(),
             transform_fn,
             in_domain_fn,
+            in_range_fn,
         }));
 
         let st = unsafe {
@@ -51,8 +70,7 @@ impl SliceTransform {
                 Some(slice_transform_destructor_callback),
                 Some(transform_callback),
                 Some(in_domain_callback),
-                // this None points to the deprecated InRange callback
-                None,
+                Some(in_range_callback),
                 Some(slice_transform_name_callback),
             )
         };
@@ -66,6 +84,15 @@ impl SliceTransform {
         }
     }
 
+    /// Creates a transform extracting the first `len` bytes of a key, or the
+    /// whole key if it is shorter. Unlike a fixed prefix, every key is in
+    /// its domain.
+    pub fn create_capped_prefix(len: usize) -> SliceTransform {
+        SliceTransform {
+            inner: unsafe { ffi::rocksdb_slicetransform_create_capped_prefix(len) },
+        }
+    }
+
     pub fn create_noop() -> SliceTransform {
         SliceTransform {
             inner: unsafe { ffi::rocksdb_slicetransform_create_noop() },
@@ -73,21 +100,25 @@ impl SliceTransform {
     }
 }
 
-pub type TransformFn<'a> = fn(&'a [u8]) -> &'a [u8];
-pub type InDomainFn = fn(&[u8]) -> bool;
+pub type TransformFn = dyn Fn(&[u8]) -> &[u8] + Send + Sync;
+pub type InDomainFn = dyn Fn(&[u8]) -> bool + Send + Sync;
+pub type InRangeFn = dyn Fn(&[u8]) -> bool + Send + Sync;
 
-pub struct TransformCallback<'a> {
+pub struct TransformCallback {
     pub name: CString,
-    pub transform_fn: TransformFn<'a>,
-    pub in_domain_fn: Option<InDomainFn>,
+    pub transform_fn: Box<TransformFn>,
+    pub in_domain_fn: Option<Box<InDomainFn>>,
+    pub in_range_fn: Option<Box<InRangeFn>>,
 }
 
 pub unsafe extern "C" fn slice_transform_destructor_callback(raw_cb: *mut c_void) {
-    Box::from_raw(raw_cb as *mut TransformCallback<'_>);
+    abort_on_panic(|| {
+        drop(Box::from_raw(raw_cb as *mut TransformCallback));
+    })
 }
 
 pub unsafe extern "C" fn slice_transform_name_callback(raw_cb: *mut c_void) -> *const c_char {
-    let cb = &mut *(raw_cb as *mut TransformCallback<'_>);
+    let cb = &mut *(raw_cb as *mut TransformCallback);
     cb.name.as_ptr()
 }
 
@@ -97,11 +128,13 @@ pub unsafe extern "C" fn transform_callback(
     key_len: size_t,
     dst_length: *mut size_t,
 ) -> *mut c_char {
-    let cb = &mut *(raw_cb as *mut TransformCallback<'_>);
-    let key = slice::from_raw_parts(raw_key as *const u8, key_len as usize);
-    let prefix = (cb.transform_fn)(key);
-    *dst_length = prefix.len() as size_t;
-    prefix.as_ptr() as *mut c_char
+    abort_on_panic(|| {
+        let cb = &mut *(raw_cb as *mut TransformCallback);
+        let key = slice::from_raw_parts(raw_key as *const u8, key_len as usize);
+        let prefix = (cb.transform_fn)(key);
+        *dst_length = prefix.len() as size_t;
+        prefix.as_ptr() as *mut c_char
+    })
 }
 
 pub unsafe extern "C" fn in_domain_callback(
@@ -109,11 +142,29 @@ pub unsafe extern "C" fn in_domain_callback(
     raw_key: *const c_char,
     key_len: size_t,
 ) -> u8 {
-    let cb = &mut *(raw_cb as *mut TransformCallback<'_>);
-    let key = slice::from_raw_parts(raw_key as *const u8, key_len as usize);
-    if let Some(in_domain) = cb.in_domain_fn {
-        in_domain(key) as u8
-    } else {
-        0xff
-    }
+    abort_on_panic(|| {
+        let cb = &mut *(raw_cb as *mut TransformCallback);
+        let key = slice::from_raw_parts(raw_key as *const u8, key_len as usize);
+        if let Some(in_domain) = &cb.in_domain_fn {
+            in_domain(key) as u8
+        } else {
+            0xff
+        }
+    })
+}
+
+pub unsafe extern "C" fn in_range_callback(
+    raw_cb: *mut c_void,
+    raw_key: *const c_char,
+    key_len: size_t,
+) -> u8 {
+    abort_on_panic(|| {
+        let cb = &mut *(raw_cb as *mut TransformCallback);
+        let key = slice::from_raw_parts(raw_key as *const u8, key_len as usize);
+        if let Some(in_range) = &cb.in_range_fn {
+            in_range(key) as u8
+        } else {
+            0
+        }
+    })
 }
@@ -6,6 +6,7 @@ use crate::{
 };
 use libc::{c_char, c_uchar, c_void, size_t};
 use std::marker::PhantomData;
+use std::slice;
 
 pub struct Transaction<'a, T> {
     inner: *mut ffi::rocksdb_transaction_t,
@@ -28,6 +29,49 @@ impl<'a, T> Transaction<'a, T> {
         Ok(())
     }
 
+    /// Names the transaction, which it needs to be prepared. The name must
+    /// be unique among the transactions of the database which are not yet
+    /// committed, and can only be set once.
+    pub fn set_name(&self, name: &str) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_set_name(
+                self.inner,
+                name.as_ptr() as *const c_char,
+                name.len() as size_t,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the name set with `set_name`, `None` if not named.
+    pub fn get_name(&self) -> Option<String> {
+        unsafe {
+            let mut name_len: size_t = 0;
+            let name = ffi::rocksdb_transaction_get_name(self.inner, &mut name_len);
+            let result = if name_len == 0 {
+                None
+            } else {
+                let name = slice::from_raw_parts(name as *const u8, name_len);
+                Some(String::from_utf8_lossy(name).into_owned())
+            };
+            ffi::rocksdb_free(name as *mut c_void);
+            result
+        }
+    }
+
+    /// Prepares a named transaction for the two-phase commit, which requires
+    /// `Options::set_allow_2pc`.
+    ///
+    /// Once prepared, the transaction is written to the WAL and survives a
+    /// restart, in `TransactionDB::prepared_transactions`, until it is
+    /// committed or rolled back.
+    pub fn prepare(&self) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_transaction_prepare(self.inner,));
+        }
+        Ok(())
+    }
+
     /// Transaction rollback
     pub fn rollback(&self) -> Result<(), Error> {
         unsafe { ffi_try!(ffi::rocksdb_transaction_rollback(self.inner,)) }
@@ -202,6 +246,7 @@ impl<T> Iterate for Transaction<'_, T> {
             DBRawIterator {
                 inner: ffi::rocksdb_transaction_create_iterator(self.inner, readopts.handle()),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             }
         }
     }
@@ -221,6 +266,7 @@ impl<T> IterateCF for Transaction<'_, T> {
                     cf_handle.inner,
                 ),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             })
         }
     }
@@ -1,25 +1,25 @@
 use crate::{
     db_options::OptionsMustOutliveDB,
     db_vector::DBVector,
-    ffi_util::to_cstring,
     handle::{ConstHandle, Handle},
     open_raw::{OpenRaw, OpenRawFFI},
     ops::*,
-    write_batch::WriteBatch,
-    ColumnFamily, DBRawIterator, Error, Options, ReadOptions, Transaction, WriteOptions,
+    ColumnFamily, DBRawIterator, Error, ReadOptions, Transaction, WriteOptions,
 };
 
 use crate::ffi;
-use libc::{c_char, c_uchar, size_t};
+use libc::{c_int, c_uchar, c_void, size_t};
 use std::collections::BTreeMap;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::path::PathBuf;
 use std::ptr;
+use std::slice;
 
 /// A transaction database.
 pub struct TransactionDB {
     inner: *mut ffi::rocksdb_transactiondb_t,
+    db: *mut ffi::rocksdb_t,
     path: PathBuf,
     cfs: BTreeMap<String, ColumnFamily>,
     _outlive: Vec<OptionsMustOutliveDB>,
@@ -29,6 +29,12 @@ impl TransactionDB {
     pub fn path(&self) -> &Path {
         self.path.as_path()
     }
+
+    /// Returns the transaction database itself as a plain database for the
+    /// C API, so writes through it still take the locks of the transactions.
+    pub(crate) fn as_db_ptr(&self) -> *mut ffi::rocksdb_t {
+        self.db
+    }
 }
 
 impl Handle<ffi::rocksdb_transactiondb_t> for TransactionDB {
@@ -37,7 +43,15 @@ impl Handle<ffi::rocksdb_transactiondb_t> for TransactionDB {
     }
 }
 
+impl Handle<ffi::rocksdb_t> for TransactionDB {
+    fn handle(&self) -> *mut ffi::rocksdb_t {
+        self.as_db_ptr()
+    }
+}
+
 impl Open for TransactionDB {}
+impl OpenCF for TransactionDB {}
+impl OpenWithLatestOptions for TransactionDB {}
 
 impl OpenRaw for TransactionDB {
     type Pointer = ffi::rocksdb_transactiondb_t;
@@ -81,8 +95,10 @@ impl OpenRaw for TransactionDB {
             .into_iter()
             .map(|(k, h)| (k, ColumnFamily::new(h)))
             .collect();
+        let db = unsafe { ffi::rocksdb_transactiondb_as_db(pointer) };
         Ok(TransactionDB {
             inner: pointer,
+            db,
             path,
             cfs,
             _outlive: outlive,
@@ -131,6 +147,7 @@ impl Iterate for TransactionDB {
             DBRawIterator {
                 inner: ffi::rocksdb_transactiondb_create_iterator(self.inner, readopts.handle()),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             }
         }
     }
@@ -150,6 +167,7 @@ impl IterateCF for TransactionDB {
                     cf_handle.handle(),
                 ),
                 db: PhantomData,
+                _timestamps: readopts.timestamps(),
             })
         }
     }
@@ -158,6 +176,10 @@ impl IterateCF for TransactionDB {
 impl Drop for TransactionDB {
     fn drop(&mut self) {
         unsafe {
+            for cf in self.cfs.values() {
+                ffi::rocksdb_column_family_handle_destroy(cf.inner);
+            }
+            ffi::rocksdb_transactiondb_as_db_destroy(self.db);
             ffi::rocksdb_transactiondb_close(self.inner);
         }
     }
@@ -201,6 +223,68 @@ impl TransactionDBOptions {
             )
         }
     }
+
+    /// Sets when the transactions write their data to the database, which
+    /// cannot be changed once the database has been written.
+    ///
+    /// Default: `TxnDBWritePolicy::WriteCommitted`
+    pub fn set_write_policy(&self, policy: TxnDBWritePolicy) {
+        unsafe { ffi::rocksdb_transactiondb_options_set_write_policy(self.inner, policy as c_int) }
+    }
+
+    /// Returns the policy set with `set_write_policy`, `None` if the policy
+    /// has no `TxnDBWritePolicy` counterpart.
+    pub fn get_write_policy(&self) -> Option<TxnDBWritePolicy> {
+        let policy = unsafe { ffi::rocksdb_transactiondb_options_get_write_policy(self.inner) };
+        TxnDBWritePolicy::from_raw(policy)
+    }
+
+    /// Sets the size in bytes above which the write batch of a transaction is
+    /// written to the database before it is prepared, so large transactions
+    /// don't keep all their writes in memory. Only used with
+    /// `TxnDBWritePolicy::WriteUnprepared`, 0 or less means no limit. The
+    /// transactions must be named with `Transaction::set_name` before
+    /// writing more than this.
+    ///
+    /// Default: 0
+    pub fn set_default_write_batch_flush_threshold(&self, threshold: i64) {
+        unsafe {
+            ffi::rocksdb_transactiondb_options_set_default_write_batch_flush_threshold(
+                self.inner, threshold,
+            )
+        }
+    }
+
+    /// Returns the threshold set with
+    /// `set_default_write_batch_flush_threshold`.
+    pub fn get_default_write_batch_flush_threshold(&self) -> i64 {
+        unsafe {
+            ffi::rocksdb_transactiondb_options_get_default_write_batch_flush_threshold(self.inner)
+        }
+    }
+}
+
+/// When the transactions write their data to the database, see
+/// `TransactionDBOptions::set_write_policy`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TxnDBWritePolicy {
+    /// Writes the data once committed.
+    WriteCommitted = ffi::rocksdb_txndb_write_committed as isize,
+    /// Writes the data once prepared, which makes committing cheaper.
+    WritePrepared = ffi::rocksdb_txndb_write_prepared as isize,
+    /// Writes the data before it is prepared, for large transactions.
+    WriteUnprepared = ffi::rocksdb_txndb_write_unprepared as isize,
+}
+
+impl TxnDBWritePolicy {
+    fn from_raw(value: c_int) -> Option<Self> {
+        match value as u32 {
+            ffi::rocksdb_txndb_write_committed => Some(Self::WriteCommitted),
+            ffi::rocksdb_txndb_write_prepared => Some(Self::WritePrepared),
+            ffi::rocksdb_txndb_write_unprepared => Some(Self::WriteUnprepared),
+            _ => None,
+        }
+    }
 }
 
 impl Drop for TransactionDBOptions {
@@ -255,6 +339,21 @@ impl TransactionOptions {
         unsafe { ffi::rocksdb_transaction_options_set_max_write_batch_size(self.inner, size) }
     }
 
+    /// Overrides `TransactionDBOptions::set_default_write_batch_flush_threshold`
+    /// for this transaction, a negative value keeps the default.
+    ///
+    /// Default: -1
+    pub fn set_write_batch_flush_threshold(&self, threshold: i64) {
+        unsafe {
+            ffi::rocksdb_transaction_options_set_write_batch_flush_threshold(self.inner, threshold)
+        }
+    }
+
+    /// Returns the threshold set with `set_write_batch_flush_threshold`.
+    pub fn get_write_batch_flush_threshold(&self) -> i64 {
+        unsafe { ffi::rocksdb_transaction_options_get_write_batch_flush_threshold(self.inner) }
+    }
+
     pub fn set_snapshot(&mut self, set_snapshot: bool) {
         unsafe {
             ffi::rocksdb_transaction_options_set_set_snapshot(self.inner, set_snapshot as c_uchar);
@@ -276,218 +375,30 @@ impl Default for TransactionOptions {
     }
 }
 
-impl CreateCheckpointObject for TransactionDB {
-    unsafe fn create_checkpoint_object_raw(&self) -> Result<*mut ffi::rocksdb_checkpoint_t, Error> {
-        Ok(ffi_try!(
-            ffi::rocksdb_transactiondb_checkpoint_object_create(self.inner,)
-        ))
-    }
-}
-
-impl GetCF<ReadOptions> for TransactionDB {
-    fn get_cf_full<K: AsRef<[u8]>>(
-        &self,
-        cf: Option<&ColumnFamily>,
-        key: K,
-        readopts: Option<&ReadOptions>,
-    ) -> Result<Option<DBVector>, Error> {
-        let mut default_readopts = None;
-
-        let ro_handle = ReadOptions::input_or_default(readopts, &mut default_readopts)?;
-
-        let key = key.as_ref();
-        let key_ptr = key.as_ptr() as *const c_char;
-        let key_len = key.len() as size_t;
-
-        unsafe {
-            let mut val_len: size_t = 0;
-
-            let val = match cf {
-                Some(cf) => ffi_try!(ffi::rocksdb_transactiondb_get_cf(
-                    self.handle(),
-                    ro_handle,
-                    cf.handle(),
-                    key_ptr,
-                    key_len,
-                    &mut val_len,
-                )),
-                None => ffi_try!(ffi::rocksdb_transactiondb_get(
-                    self.handle(),
-                    ro_handle,
-                    key_ptr,
-                    key_len,
-                    &mut val_len,
-                )),
-            } as *mut u8;
-
-            if val.is_null() {
-                Ok(None)
-            } else {
-                Ok(Some(DBVector::from_c(val, val_len)))
-            }
-        }
-    }
-}
-
-impl PutCF<WriteOptions> for TransactionDB {
-    fn put_cf_full<K, V>(
-        &self,
-        cf: Option<&ColumnFamily>,
-        key: K,
-        value: V,
-        writeopts: Option<&WriteOptions>,
-    ) -> Result<(), Error>
-    where
-        K: AsRef<[u8]>,
-        V: AsRef<[u8]>,
-    {
-        let mut default_writeopts = None;
-
-        let wo_handle = WriteOptions::input_or_default(writeopts, &mut default_writeopts)?;
-
-        let key = key.as_ref();
-        let value = value.as_ref();
-        let key_ptr = key.as_ptr() as *const c_char;
-        let key_len = key.len() as size_t;
-        let val_ptr = value.as_ptr() as *const c_char;
-        let val_len = value.len() as size_t;
-
-        unsafe {
-            match cf {
-                Some(cf) => ffi_try!(ffi::rocksdb_transactiondb_put_cf(
-                    self.handle(),
-                    wo_handle,
-                    cf.handle(),
-                    key_ptr,
-                    key_len,
-                    val_ptr,
-                    val_len,
-                )),
-                None => ffi_try!(ffi::rocksdb_transactiondb_put(
-                    self.handle(),
-                    wo_handle,
-                    key_ptr,
-                    key_len,
-                    val_ptr,
-                    val_len,
-                )),
-            }
-
-            Ok(())
-        }
-    }
-}
-
-impl DeleteCF<WriteOptions> for TransactionDB {
-    fn delete_cf_full<K>(
-        &self,
-        cf: Option<&ColumnFamily>,
-        key: K,
-        writeopts: Option<&WriteOptions>,
-    ) -> Result<(), Error>
-    where
-        K: AsRef<[u8]>,
-    {
-        let mut default_writeopts = None;
-
-        let wo_handle = WriteOptions::input_or_default(writeopts, &mut default_writeopts)?;
-
-        let key = key.as_ref();
-        let key_ptr = key.as_ptr() as *const c_char;
-        let key_len = key.len() as size_t;
-
-        unsafe {
-            match cf {
-                Some(cf) => ffi_try!(ffi::rocksdb_transactiondb_delete_cf(
-                    self.handle(),
-                    wo_handle,
-                    cf.handle(),
-                    key_ptr,
-                    key_len,
-                )),
-                None => ffi_try!(ffi::rocksdb_transactiondb_delete(
-                    self.handle(),
-                    wo_handle,
-                    key_ptr,
-                    key_len,
-                )),
-            }
-
-            Ok(())
-        }
-    }
-}
-
-impl MergeCF<WriteOptions> for TransactionDB {
-    fn merge_cf_full<K, V>(
-        &self,
-        cf: Option<&ColumnFamily>,
-        key: K,
-        value: V,
-        writeopts: Option<&WriteOptions>,
-    ) -> Result<(), Error>
-    where
-        K: AsRef<[u8]>,
-        V: AsRef<[u8]>,
-    {
-        let mut default_writeopts = None;
-
-        let wo_handle = WriteOptions::input_or_default(writeopts, &mut default_writeopts)?;
-
-        let key = key.as_ref();
-        let value = value.as_ref();
-        let key_ptr = key.as_ptr() as *const c_char;
-        let key_len = key.len() as size_t;
-        let val_ptr = value.as_ptr() as *const c_char;
-        let val_len = value.len() as size_t;
-
+impl TransactionDB {
+    /// Returns the transactions prepared but neither committed nor rolled
+    /// back, such as those recovered from the WAL when opening a database
+    /// with `Options::set_allow_2pc`.
+    ///
+    /// Each one must be committed or rolled back, after which its name can
+    /// be reused. Since they are owned by the returned handles, no other
+    /// transaction of the database can be alive meanwhile.
+    pub fn prepared_transactions(&mut self) -> Vec<Transaction<'_, TransactionDB>> {
         unsafe {
-            match cf {
-                Some(cf) => ffi_try!(ffi::rocksdb_transactiondb_merge_cf(
-                    self.handle(),
-                    wo_handle,
-                    cf.handle(),
-                    key_ptr,
-                    key_len,
-                    val_ptr,
-                    val_len,
-                )),
-                None => ffi_try!(ffi::rocksdb_transactiondb_merge(
-                    self.handle(),
-                    wo_handle,
-                    key_ptr,
-                    key_len,
-                    val_ptr,
-                    val_len,
-                )),
+            let mut count: size_t = 0;
+            let txns = ffi::rocksdb_transactiondb_get_prepared_transactions(self.inner, &mut count);
+            if txns.is_null() {
+                return Vec::new();
             }
-
-            Ok(())
+            let result = slice::from_raw_parts(txns, count)
+                .iter()
+                .map(|txn| Transaction::new(*txn))
+                .collect();
+            ffi::rocksdb_free(txns as *mut c_void);
+            result
         }
     }
-}
-
-impl CreateCF for TransactionDB {
-    fn create_cf<N: AsRef<str>>(&mut self, name: N, opts: &Options) -> Result<(), Error> {
-        let cname = to_cstring(
-            name.as_ref(),
-            "Failed to convert path to CString when opening rocksdb",
-        )?;
-        unsafe {
-            let cf_handle = ffi_try!(ffi::rocksdb_transactiondb_create_column_family(
-                self.handle(),
-                opts.const_handle(),
-                cname.as_ptr(),
-            ));
-
-            self.get_mut_cfs()
-                .insert(name.as_ref().to_string(), ColumnFamily::new(cf_handle));
-        };
-        Ok(())
-    }
-}
 
-impl TransactionDB {
     pub fn snapshot(&self) -> Snapshot<'_> {
         let snapshot = unsafe { ffi::rocksdb_transactiondb_create_snapshot(self.inner) };
         Snapshot {
@@ -551,24 +462,3 @@ impl IterateCF for Snapshot<'_> {
         self.db.get_raw_iter_cf(cf_handle, &ro)
     }
 }
-
-impl WriteOps for TransactionDB {
-    fn write_full(
-        &self,
-        batch: &WriteBatch,
-        writeopts: Option<&WriteOptions>,
-    ) -> Result<(), Error> {
-        let mut default_writeopts = None;
-
-        let wo_handle = WriteOptions::input_or_default(writeopts, &mut default_writeopts)?;
-
-        unsafe {
-            ffi_try!(ffi::rocksdb_transactiondb_write(
-                self.handle(),
-                wo_handle,
-                batch.handle(),
-            ));
-            Ok(())
-        }
-    }
-}
@@ -44,6 +44,9 @@ use libc::{c_char, size_t};
 /// ```
 pub struct WriteBatch {
     inner: *mut ffi::rocksdb_writebatch_t,
+    // The size of the timestamps written to the default column family, which
+    // is checked against its comparator once the batch is written.
+    default_ts_size: Option<usize>,
 }
 
 impl WriteBatch {
@@ -106,6 +109,74 @@ impl WriteBatch {
         }
     }
 
+    /// Insert a value under the given key, versioned by the user-defined
+    /// timestamp `ts`.
+    ///
+    /// The timestamps written to the default column family must all have the
+    /// same size, which is checked against its comparator when the batch is
+    /// written.
+    pub fn put_with_ts<K, T, V>(&mut self, key: K, ts: T, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let (key, ts, value) = (key.as_ref(), ts.as_ref(), value.as_ref());
+        self.set_default_ts_size(ts.len())?;
+        // The timestamp is stored as the last part of the key.
+        let key_parts = [key.as_ptr() as *const c_char, ts.as_ptr() as *const c_char];
+        let key_sizes = [key.len() as size_t, ts.len() as size_t];
+        let value_ptr = value.as_ptr() as *const c_char;
+        let value_size = value.len() as size_t;
+
+        unsafe {
+            ffi::rocksdb_writebatch_putv(
+                self.handle(),
+                2,
+                key_parts.as_ptr(),
+                key_sizes.as_ptr(),
+                1,
+                &value_ptr,
+                &value_size,
+            );
+            Ok(())
+        }
+    }
+
+    /// Insert a value under the given key in the column family, versioned by
+    /// the user-defined timestamp `ts`.
+    ///
+    /// Returns an error if the size of `ts` doesn't match the comparator of
+    /// the column family.
+    pub fn put_with_ts_cf<K, T, V>(
+        &mut self,
+        cf: &ColumnFamily,
+        key: K,
+        ts: T,
+        value: V,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        T: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let (key, ts, value) = (key.as_ref(), ts.as_ref(), value.as_ref());
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_writebatch_put_with_ts_cf(
+                self.handle(),
+                cf.handle(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                ts.as_ptr() as *const c_char,
+                ts.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            ));
+            Ok(())
+        }
+    }
+
     pub fn merge<K, V>(&mut self, key: K, value: V) -> Result<(), Error>
     where
         K: AsRef<[u8]>,
@@ -177,6 +248,57 @@ impl WriteBatch {
         }
     }
 
+    /// Remove the database entry for key as of the user-defined timestamp `ts`.
+    ///
+    /// See [`put_with_ts`](#method.put_with_ts) for how the size of `ts` is
+    /// checked.
+    pub fn delete_with_ts<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        ts: T,
+    ) -> Result<(), Error> {
+        let (key, ts) = (key.as_ref(), ts.as_ref());
+        self.set_default_ts_size(ts.len())?;
+        let key_parts = [key.as_ptr() as *const c_char, ts.as_ptr() as *const c_char];
+        let key_sizes = [key.len() as size_t, ts.len() as size_t];
+
+        unsafe {
+            ffi::rocksdb_writebatch_deletev(
+                self.handle(),
+                2,
+                key_parts.as_ptr(),
+                key_sizes.as_ptr(),
+            );
+            Ok(())
+        }
+    }
+
+    /// Remove the database entry for key in the column family as of the
+    /// user-defined timestamp `ts`.
+    ///
+    /// Returns an error if the size of `ts` doesn't match the comparator of
+    /// the column family.
+    pub fn delete_with_ts_cf<K: AsRef<[u8]>, T: AsRef<[u8]>>(
+        &mut self,
+        cf: &ColumnFamily,
+        key: K,
+        ts: T,
+    ) -> Result<(), Error> {
+        let (key, ts) = (key.as_ref(), ts.as_ref());
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_writebatch_delete_with_ts_cf(
+                self.handle(),
+                cf.handle(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                ts.as_ptr() as *const c_char,
+                ts.len() as size_t,
+            ));
+            Ok(())
+        }
+    }
+
     /// Remove database entries from start key to end key.
     ///
     /// Removes the database entries in the range ["begin_key", "end_key"), i.e.,
@@ -228,14 +350,35 @@ impl WriteBatch {
         unsafe {
             ffi::rocksdb_writebatch_clear(self.inner);
         }
+        self.default_ts_size = None;
         Ok(())
     }
+
+    fn set_default_ts_size(&mut self, ts_size: usize) -> Result<(), Error> {
+        match self.default_ts_size {
+            Some(size) if size != ts_size => Err(Error::new(format!(
+                "Invalid argument: Timestamp size {} does not match the size {} of the \
+                 previous timestamps of the default column family",
+                ts_size, size
+            ))),
+            _ => {
+                self.default_ts_size = Some(ts_size);
+                Ok(())
+            }
+        }
+    }
+
+    /// The size of the timestamps written to the default column family.
+    pub(crate) fn default_ts_size(&self) -> Option<usize> {
+        self.default_ts_size
+    }
 }
 
 impl Default for WriteBatch {
     fn default() -> WriteBatch {
         WriteBatch {
             inner: unsafe { ffi::rocksdb_writebatch_create() },
+            default_ts_size: None,
         }
     }
 }
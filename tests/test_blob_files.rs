@@ -0,0 +1,96 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{prelude::*, properties, DBCompressionType, TemporaryDBPath};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+fn blob_options() -> Options {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_enable_blob_files(true);
+    opts.set_min_blob_size(1024);
+    opts.set_blob_file_size(1024 * 1024);
+    opts.set_blob_compression_type(DBCompressionType::None);
+    opts
+}
+
+fn count_blob_files<P: AsRef<Path>>(path: P) -> usize {
+    fs::read_dir(path)
+        .unwrap()
+        .filter(|entry| entry.as_ref().unwrap().path().extension() == Some(OsStr::new("blob")))
+        .count()
+}
+
+#[test]
+fn large_values_land_in_blob_files() {
+    let path = TemporaryDBPath::new();
+    let db = DB::open(&blob_options(), &path).unwrap();
+
+    db.put(b"small", vec![1u8; 16]).unwrap();
+    db.put(b"large", vec![2u8; 4096]).unwrap();
+    db.flush().unwrap();
+
+    assert_eq!(
+        db.property_int_value(properties::NUM_BLOB_FILES).unwrap(),
+        Some(1)
+    );
+    assert!(
+        db.property_int_value(properties::LIVE_BLOB_FILE_SIZE)
+            .unwrap()
+            .unwrap()
+            > 0
+    );
+    assert!(db
+        .property_value(properties::BLOB_STATS)
+        .unwrap()
+        .unwrap()
+        .contains("Number of blob files: 1"));
+    assert_eq!(count_blob_files(&path), 1);
+
+    assert_eq!(db.get(b"small").unwrap().unwrap().to_vec(), vec![1u8; 16]);
+    assert_eq!(db.get(b"large").unwrap().unwrap().to_vec(), vec![2u8; 4096]);
+}
+
+#[test]
+fn blob_gc_removes_obsolete_blob_files() {
+    let path = TemporaryDBPath::new();
+    let mut opts = blob_options();
+    opts.set_enable_blob_gc(true);
+    opts.set_blob_gc_age_cutoff(1.0);
+    opts.set_blob_gc_force_threshold(1.0);
+    let db = DB::open(&opts, &path).unwrap();
+
+    db.put(b"k1", vec![1u8; 4096]).unwrap();
+    db.flush().unwrap();
+    db.put(b"k1", vec![2u8; 4096]).unwrap();
+    db.put(b"k2", vec![3u8; 4096]).unwrap();
+    db.flush().unwrap();
+    assert_eq!(
+        db.property_int_value(properties::NUM_BLOB_FILES).unwrap(),
+        Some(2)
+    );
+
+    db.compact_range(None::<&[u8]>, None::<&[u8]>);
+
+    assert_eq!(
+        db.property_int_value(properties::NUM_BLOB_FILES).unwrap(),
+        Some(1)
+    );
+    assert_eq!(db.get(b"k1").unwrap().unwrap().to_vec(), vec![2u8; 4096]);
+    assert_eq!(db.get(b"k2").unwrap().unwrap().to_vec(), vec![3u8; 4096]);
+}
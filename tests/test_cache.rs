@@ -0,0 +1,72 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{
+    prelude::*, BlockBasedOptions, Cache, CacheEntryRole, LruCacheOptions, TemporaryDBPath,
+};
+
+#[test]
+fn lru_cache_options() {
+    let mut opts = LruCacheOptions::default();
+    opts.set_capacity(8 * 1024 * 1024);
+    opts.set_num_shard_bits(2);
+    opts.set_strict_capacity_limit(true);
+    opts.set_high_pri_pool_ratio(0.1);
+    let mut cache = Cache::new_lru_cache_opts(&opts).unwrap();
+
+    assert_eq!(cache.get_capacity(), 8 * 1024 * 1024);
+    assert!(cache.has_strict_capacity_limit());
+    cache.set_strict_capacity_limit(false);
+    assert!(!cache.has_strict_capacity_limit());
+    cache.set_capacity(4 * 1024 * 1024);
+    assert_eq!(cache.get_capacity(), 4 * 1024 * 1024);
+}
+
+#[test]
+fn cache_entry_stats() {
+    let path = TemporaryDBPath::new();
+    let cache = Cache::new_lru_cache(8 * 1024 * 1024).unwrap();
+    let stats = cache.get_entry_stats();
+    assert_eq!(stats.len(), CacheEntryRole::ALL.len());
+    assert!(stats.iter().all(|s| s.entries == 0 && s.charge == 0));
+
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_cache(&cache);
+    block_opts.set_cache_index_and_filter_blocks(true);
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_block_based_table_factory(&block_opts);
+    let db = DB::open(&opts, &path).unwrap();
+    for i in 0..100u32 {
+        db.put(format!("key{:03}", i), vec![0u8; 128]).unwrap();
+    }
+    db.flush().unwrap();
+    assert!(db.get(b"key042").unwrap().is_some());
+
+    let stats = cache.get_entry_stats();
+    let data = stats
+        .iter()
+        .find(|s| s.role == CacheEntryRole::DataBlock)
+        .unwrap();
+    assert!(data.entries > 0);
+    assert!(data.charge > 0);
+    let index = stats
+        .iter()
+        .find(|s| s.role == CacheEntryRole::IndexBlock)
+        .unwrap();
+    assert!(index.entries > 0);
+    assert!(cache.get_usage() >= stats.iter().map(|s| s.charge as usize).sum());
+}
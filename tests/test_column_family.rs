@@ -253,3 +253,23 @@ fn test_create_duplicate_column_family() {
         assert!(db.create_cf("cf1", &opts).is_err());
     }
 }
+
+#[test]
+fn test_drop_column_family_keeps_handle_on_error() {
+    let n = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let mut db = DB::open_cf(&opts, &n, &["default", "cf1"]).unwrap();
+
+    // the default column family cannot be dropped, its handle stays usable
+    assert!(db.drop_cf("default").is_err());
+    let default_cf = db.cf_handle("default").unwrap();
+    db.put_cf(default_cf, b"k1", b"v1").unwrap();
+    assert_eq!(db.get(b"k1").unwrap().unwrap().as_ref(), b"v1");
+
+    db.drop_cf("cf1").unwrap();
+    assert!(db.cf_handle("cf1").is_none());
+    assert!(db.drop_cf("cf1").is_err());
+    assert_eq!(DB::list_cf(&opts, &n).unwrap(), vec!["default"]);
+}
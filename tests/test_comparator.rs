@@ -0,0 +1,153 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{prelude::*, BuiltinComparator, IteratorMode, TemporaryDBPath};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Arc;
+
+fn collect_keys(db: &DB) -> Vec<Vec<u8>> {
+    db.iterator(IteratorMode::Start)
+        .map(|(k, _)| k.to_vec())
+        .collect()
+}
+
+#[test]
+fn test_closure_comparator() {
+    let path = TemporaryDBPath::new();
+    // Order keys by their value modulo a captured divisor, then byte-wise.
+    let modulo = 3u8;
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_comparator(
+        "modulo",
+        Box::new(move |a: &[u8], b: &[u8]| (a[0] % modulo, a).cmp(&(b[0] % modulo, b))),
+    );
+
+    let db = DB::open(&opts, &path).unwrap();
+    for k in 0u8..6 {
+        db.put([k], b"").unwrap();
+    }
+
+    assert_eq!(
+        collect_keys(&db),
+        vec![vec![0], vec![3], vec![1], vec![4], vec![2], vec![5]]
+    );
+}
+
+#[test]
+fn test_comparator_with_hooks() {
+    let path = TemporaryDBPath::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let separator_calls = calls.clone();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_comparator_with_hooks(
+        "bytewise_with_hooks",
+        Box::new(|a: &[u8], b: &[u8]| a.cmp(b)),
+        Some(Box::new(move |start: &[u8], limit: &[u8]| {
+            separator_calls.fetch_add(1, AtomicOrdering::SeqCst);
+            // Byte-wise shortest separator: keep the common prefix and bump
+            // the first differing byte when that stays below `limit`.
+            let common = start.iter().zip(limit).take_while(|(a, b)| a == b).count();
+            if common < start.len() && common < limit.len() {
+                let byte = start[common];
+                if byte < 0xff && byte + 1 < limit[common] {
+                    let mut separator = start[..=common].to_vec();
+                    separator[common] += 1;
+                    return Some(separator);
+                }
+            }
+            None
+        })),
+        None,
+    );
+
+    let db = DB::open(&opts, &path).unwrap();
+    for i in 0..1000u32 {
+        db.put(format!("key{:05}", i), vec![0u8; 128]).unwrap();
+    }
+    db.flush().unwrap();
+
+    assert!(calls.load(AtomicOrdering::SeqCst) > 0);
+    assert_eq!(
+        db.get(b"key00042").unwrap().unwrap().to_vec(),
+        vec![0u8; 128]
+    );
+    assert_eq!(collect_keys(&db).len(), 1000);
+}
+
+#[test]
+fn test_reverse_bytewise_comparator() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_builtin_comparator(BuiltinComparator::ReverseBytewise);
+
+    let db = DB::open(&opts, &path).unwrap();
+    db.put(b"a", b"").unwrap();
+    db.put(b"c", b"").unwrap();
+    db.put(b"b", b"").unwrap();
+
+    assert_eq!(
+        collect_keys(&db),
+        vec![b"c".to_vec(), b"b".to_vec(), b"a".to_vec()]
+    );
+}
+
+#[test]
+fn test_big_endian_i64_comparator() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_builtin_comparator(BuiltinComparator::BigEndianI64);
+
+    let db = DB::open(&opts, &path).unwrap();
+    for n in &[5i64, -1, i64::MIN, 0, i64::MAX, -42] {
+        db.put(n.to_be_bytes(), b"").unwrap();
+    }
+    db.put(b"ab", b"").unwrap();
+
+    let numbers: Vec<_> = collect_keys(&db)
+        .into_iter()
+        .skip(1)
+        .map(|k| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&k);
+            i64::from_be_bytes(buf)
+        })
+        .collect();
+    assert_eq!(collect_keys(&db)[0], b"ab".to_vec());
+    assert_eq!(numbers, vec![i64::MIN, -42, -1, 0, 5, i64::MAX]);
+}
+
+#[test]
+fn test_builtin_comparator_name_is_checked_on_reopen() {
+    let path = TemporaryDBPath::new();
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_builtin_comparator(BuiltinComparator::BigEndianU64);
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(1u64.to_be_bytes(), b"").unwrap();
+    }
+
+    let mut opts = Options::default();
+    opts.set_builtin_comparator(BuiltinComparator::ReverseBytewise);
+    assert!(DB::open(&opts, &path).is_err());
+
+    opts.set_builtin_comparator(BuiltinComparator::BigEndianU64);
+    assert!(DB::open(&opts, &path).is_ok());
+}
@@ -0,0 +1,347 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "serde")]
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{
+    config::{FullOptionsConfig, OptionsConfig},
+    prelude::*,
+    AccessHint, DBCompactionStyle, DBCompressionType, DBRecoveryMode, LogLevel, TemporaryDBPath,
+};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+fn error_of(config: &FullOptionsConfig) -> String {
+    config.to_full_options().err().unwrap().into_string()
+}
+
+#[test]
+fn test_config_from_toml() {
+    let config: FullOptionsConfig = toml::from_str(
+        r#"
+        [caches.blocks]
+        capacity = 33554432
+        num_shard_bits = 4
+        high_pri_pool_ratio = 0.5
+
+        [options]
+        create_if_missing = true
+        create_missing_column_families = true
+        max_background_jobs = 6
+        num_levels = 5
+        log_level = "Warn"
+        wal_recovery_mode = "PointInTime"
+        compression_type = "Lz4"
+        compression_per_level = ["None", "None", "Lz4", "Lz4", "Zstd"]
+        wal_dir = "/tmp/wal"
+        row_cache = "blocks"
+
+        [options.compression_options]
+        level = 3
+
+        [options.block_based]
+        block_size = 32768
+        block_cache = "blocks"
+        cache_index_and_filter_blocks = true
+        filter = { type = "ribbon", bits_per_key = 10.0 }
+
+        [column_families.index]
+        write_buffer_size = 1048576
+        compaction_style = "Universal"
+
+        [column_families.index.universal_compaction]
+        size_ratio = 2
+        stop_style = "Total"
+        "#,
+    )
+    .unwrap();
+
+    let full_opts = config.to_full_options().unwrap();
+    let db_opts = &full_opts.db_opts;
+    assert!(db_opts.get_create_if_missing());
+    assert_eq!(db_opts.get_max_background_jobs(), 6);
+    assert_eq!(db_opts.get_num_levels(), 5);
+    assert_eq!(db_opts.get_log_level(), Some(LogLevel::Warn));
+    assert_eq!(
+        db_opts.get_wal_recovery_mode(),
+        Some(DBRecoveryMode::PointInTime)
+    );
+    assert_eq!(db_opts.get_compression_type(), Some(DBCompressionType::Lz4));
+    assert_eq!(
+        db_opts.get_compression_per_level()[4],
+        Some(DBCompressionType::Zstd)
+    );
+    assert_eq!(db_opts.get_compression_options(), (-14, 3, 0, 0));
+    assert_eq!(db_opts.get_wal_dir(), Some(PathBuf::from("/tmp/wal")));
+    assert!(db_opts
+        .to_options_string()
+        .unwrap()
+        .contains("table_factory=BlockBasedTable;"));
+
+    let names: Vec<_> = full_opts
+        .cf_descriptors
+        .iter()
+        .map(|cf| cf.name())
+        .collect();
+    assert_eq!(names, ["default", "index"]);
+    let index_opts = &full_opts.cf_descriptors[1].options();
+    assert_eq!(index_opts.get_write_buffer_size(), 1048576);
+    assert_eq!(
+        index_opts.get_compaction_style(),
+        Some(DBCompactionStyle::Universal)
+    );
+    // inherited from `options`
+    assert_eq!(index_opts.get_num_levels(), 5);
+    assert_eq!(
+        full_opts.cf_descriptors[0]
+            .options()
+            .get_write_buffer_size(),
+        64 << 20
+    );
+}
+
+#[test]
+fn test_config_from_json_opens_db() {
+    let config: FullOptionsConfig = serde_json::from_str(
+        r#"{
+            "options": {
+                "create_if_missing": true,
+                "create_missing_column_families": true,
+                "compression_type": "None"
+            },
+            "column_families": {
+                "default": { "max_write_buffer_number": 3 },
+                "meta": { "block_based": { "filter": { "type": "bloom", "bits_per_key": 10.0 } } }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let full_opts = config.to_full_options().unwrap();
+    assert_eq!(full_opts.cf_descriptors.len(), 2);
+    assert_eq!(
+        full_opts.cf_descriptors[0]
+            .options()
+            .get_max_write_buffer_number(),
+        3
+    );
+
+    let path = TemporaryDBPath::new();
+    let db = DB::open_cf_descriptors(&full_opts.db_opts, &path, full_opts.cf_descriptors).unwrap();
+    let meta = db.cf_handle("meta").unwrap();
+    db.put_cf(meta, b"k", b"v").unwrap();
+    assert_eq!(db.get_cf(meta, b"k").unwrap().unwrap().as_ref(), b"v");
+}
+
+#[test]
+fn test_config_errors_name_the_field() {
+    let config: FullOptionsConfig = toml::from_str(
+        r#"
+        [column_families.index.block_based]
+        block_cache = "missing"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        error_of(&config),
+        "column_families.index.block_based.block_cache: unknown cache \"missing\""
+    );
+
+    let config: FullOptionsConfig = toml::from_str("[options]\nnum_levels = 0").unwrap();
+    assert_eq!(error_of(&config), "options.num_levels: must be at least 1");
+
+    let config: FullOptionsConfig =
+        toml::from_str("[caches.small]\ncapacity = 1024\nnum_shard_bits = 30").unwrap();
+    assert_eq!(
+        error_of(&config),
+        "caches.small.num_shard_bits: must be less than 20"
+    );
+
+    let config: FullOptionsConfig = toml::from_str("[options]\nblob_gc_age_cutoff = 1.5").unwrap();
+    assert_eq!(
+        error_of(&config),
+        "options.blob_gc_age_cutoff: 1.5 is not between 0 and 1"
+    );
+
+    let config: FullOptionsConfig =
+        toml::from_str("[options.universal_compaction]\nmin_merge_width = 4\nmax_merge_width = 3")
+            .unwrap();
+    assert_eq!(
+        error_of(&config),
+        "options.universal_compaction.max_merge_width: must not be less than min_merge_width"
+    );
+
+    let err = toml::from_str::<FullOptionsConfig>("[options]\nmax_backgroud_jobs = 2")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("max_backgroud_jobs"), "{}", err);
+}
+
+/// Sets the `OptionsConfig` keys which map to a single setter, checks them
+/// with the matching getters and returns their names.
+macro_rules! check_config_keys {
+    ($($field:ident: $value:expr => $getter:ident $(== $expected:expr)?,)*) => {{
+        let config = FullOptionsConfig {
+            options: OptionsConfig {
+                $($field: Some($value),)*
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let full_opts = config.to_full_options().unwrap();
+        $(
+            assert_eq!(
+                full_opts.db_opts.$getter(),
+                check_config_keys!(@expected $value $(, $expected)?),
+                stringify!($field)
+            );
+        )*
+        vec![$(stringify!($field)),*]
+    }};
+    (@expected $value:expr) => {
+        $value
+    };
+    (@expected $value:expr, $expected:expr) => {
+        $expected
+    };
+}
+
+#[test]
+fn test_config_keys_round_trip_through_getters() {
+    let checked = check_config_keys! {
+        create_if_missing: true => get_create_if_missing,
+        create_missing_column_families: true => get_create_missing_column_families,
+        error_if_exists: true => get_error_if_exists,
+        paranoid_checks: false => get_paranoid_checks,
+        compression_type: DBCompressionType::Lz4
+            => get_compression_type == Some(DBCompressionType::Lz4),
+        bottommost_compression_type: DBCompressionType::Zstd
+            => get_bottommost_compression_type == Some(DBCompressionType::Zstd),
+        compaction_readahead_size: 2 << 20 => get_compaction_readahead_size,
+        enable_blob_files: true => get_enable_blob_files,
+        min_blob_size: 1024 => get_min_blob_size,
+        blob_file_size: 1 << 30 => get_blob_file_size,
+        blob_compression_type: DBCompressionType::Snappy
+            => get_blob_compression_type == Some(DBCompressionType::Snappy),
+        enable_blob_gc: true => get_enable_blob_gc,
+        blob_gc_age_cutoff: 0.5 => get_blob_gc_age_cutoff,
+        blob_gc_force_threshold: 0.8 => get_blob_gc_force_threshold,
+        level_compaction_dynamic_level_bytes: true => get_level_compaction_dynamic_level_bytes,
+        optimize_filters_for_hits: true => get_optimize_filters_for_hits,
+        delete_obsolete_files_period_micros: 1000 => get_delete_obsolete_files_period_micros,
+        max_open_files: 123 => get_max_open_files,
+        max_file_opening_threads: 4 => get_max_file_opening_threads,
+        use_fsync: true => get_use_fsync,
+        log_level: LogLevel::Warn => get_log_level == Some(LogLevel::Warn),
+        bytes_per_sync: 1 << 20 => get_bytes_per_sync,
+        wal_bytes_per_sync: 2 << 20 => get_wal_bytes_per_sync,
+        writable_file_max_buffer_size: 3 << 20 => get_writable_file_max_buffer_size,
+        allow_concurrent_memtable_write: false => get_allow_concurrent_memtable_write,
+        enable_write_thread_adaptive_yield: false => get_enable_write_thread_adaptive_yield,
+        max_sequential_skip_in_iterations: 16 => get_max_sequential_skip_in_iterations,
+        use_direct_reads: true => get_use_direct_reads,
+        use_direct_io_for_flush_and_compaction: true => get_use_direct_io_for_flush_and_compaction,
+        is_fd_close_on_exec: false => get_is_fd_close_on_exec,
+        table_cache_num_shard_bits: 5 => get_table_cache_num_shard_bits,
+        target_file_size_multiplier: 2 => get_target_file_size_multiplier,
+        max_write_buffer_number: 4 => get_max_write_buffer_number,
+        write_buffer_size: 4 * 1024 * 1024 => get_write_buffer_size,
+        db_write_buffer_size: 128 << 20 => get_db_write_buffer_size,
+        max_bytes_for_level_base: 512 << 20 => get_max_bytes_for_level_base,
+        max_bytes_for_level_multiplier: 8.0 => get_max_bytes_for_level_multiplier,
+        max_manifest_file_size: 64 << 20 => get_max_manifest_file_size,
+        target_file_size_base: 128 << 20 => get_target_file_size_base,
+        min_write_buffer_number_to_merge: 2 => get_min_write_buffer_number_to_merge,
+        level_zero_file_num_compaction_trigger: 8 => get_level_zero_file_num_compaction_trigger,
+        level_zero_slowdown_writes_trigger: 17 => get_level_zero_slowdown_writes_trigger,
+        level_zero_stop_writes_trigger: 24 => get_level_zero_stop_writes_trigger,
+        compaction_style: DBCompactionStyle::Universal
+            => get_compaction_style == Some(DBCompactionStyle::Universal),
+        unordered_write: true => get_unordered_write,
+        max_subcompactions: 2 => get_max_subcompactions,
+        max_background_jobs: 3 => get_max_background_jobs,
+        disable_auto_compactions: true => get_disable_auto_compactions,
+        memtable_huge_page_size: 2 << 20 => get_memtable_huge_page_size,
+        max_successive_merges: 10 => get_max_successive_merges,
+        bloom_locality: 1 => get_bloom_locality,
+        inplace_update_support: true => get_inplace_update_support,
+        inplace_update_locks: 100 => get_inplace_update_locks,
+        skip_checking_sst_file_sizes_on_db_open: true
+            => get_skip_checking_sst_file_sizes_on_db_open,
+        max_write_buffer_size_to_maintain: 64 << 20 => get_max_write_buffer_size_to_maintain,
+        enable_pipelined_write: true => get_enable_pipelined_write,
+        report_bg_io_stats: true => get_report_bg_io_stats,
+        max_total_wal_size: 1 << 30 => get_max_total_wal_size,
+        wal_recovery_mode: DBRecoveryMode::AbsoluteConsistency
+            => get_wal_recovery_mode == Some(DBRecoveryMode::AbsoluteConsistency),
+        stats_dump_period_sec: 300 => get_stats_dump_period_sec,
+        stats_persist_period_sec: 60 => get_stats_persist_period_sec,
+        advise_random_on_open: false => get_advise_random_on_open,
+        access_hint_on_compaction_start: AccessHint::Sequential
+            => get_access_hint_on_compaction_start == Some(AccessHint::Sequential),
+        use_adaptive_mutex: true => get_use_adaptive_mutex,
+        num_levels: 3 => get_num_levels,
+        memtable_prefix_bloom_ratio: 0.1 => get_memtable_prefix_bloom_ratio,
+        max_compaction_bytes: 1 << 30 => get_max_compaction_bytes,
+        wal_ttl_seconds: 3600 => get_wal_ttl_seconds,
+        wal_size_limit_mb: 1024 => get_wal_size_limit_mb,
+        manifest_preallocation_size: 1 << 20 => get_manifest_preallocation_size,
+        skip_stats_update_on_db_open: true => get_skip_stats_update_on_db_open,
+        keep_log_file_num: 10 => get_keep_log_file_num,
+        allow_mmap_writes: true => get_allow_mmap_writes,
+        allow_mmap_reads: true => get_allow_mmap_reads,
+        manual_wal_flush: true => get_manual_wal_flush,
+        atomic_flush: true => get_atomic_flush,
+        max_log_file_size: 1 << 20 => get_max_log_file_size,
+        log_file_time_to_roll: 86400 => get_log_file_time_to_roll,
+        recycle_log_file_num: 2 => get_recycle_log_file_num,
+        soft_pending_compaction_bytes_limit: 1 << 30 => get_soft_pending_compaction_bytes_limit,
+        hard_pending_compaction_bytes_limit: 2 << 30 => get_hard_pending_compaction_bytes_limit,
+        arena_block_size: 1 << 20 => get_arena_block_size,
+        dump_malloc_stats: true => get_dump_malloc_stats,
+        memtable_whole_key_filtering: true => get_memtable_whole_key_filtering,
+        compression_per_level: vec![DBCompressionType::None, DBCompressionType::Lz4]
+            => get_compression_per_level
+            == vec![Some(DBCompressionType::None), Some(DBCompressionType::Lz4)],
+        max_bytes_for_level_multiplier_additional: vec![1, 2, 3]
+            => get_max_bytes_for_level_multiplier_additional,
+        db_log_dir: PathBuf::from("/tmp/rocksdb-log")
+            => get_db_log_dir == Some(PathBuf::from("/tmp/rocksdb-log")),
+        wal_dir: PathBuf::from("/tmp/rocksdb-wal")
+            => get_wal_dir == Some(PathBuf::from("/tmp/rocksdb-wal")),
+    };
+
+    // The sections are checked by the other tests.
+    let sections = [
+        "compression_options",
+        "bottommost_compression_options",
+        "universal_compaction",
+        "fifo_compaction",
+        "block_based",
+        "row_cache",
+    ];
+    let keys: BTreeSet<String> = match serde_json::to_value(OptionsConfig::default()).unwrap() {
+        Value::Object(map) => map.keys().cloned().collect(),
+        value => panic!("not an object: {}", value),
+    };
+    let expected_keys: BTreeSet<String> = checked
+        .iter()
+        .chain(sections.iter())
+        .map(|key| key.to_string())
+        .collect();
+    assert_eq!(keys, expected_keys);
+}
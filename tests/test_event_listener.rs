@@ -0,0 +1,209 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{
+    event_listener::{
+        BackgroundErrorReason, CompactionJobInfo, FlushJobInfo, MemTableInfo,
+        TableFileCreationInfo, TableFileCreationReason, WriteStallCondition, WriteStallInfo,
+    },
+    prelude::*,
+    Error, EventListener, MergeOperands, TemporaryDBPath,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Events {
+    flush_begin: Vec<FlushJobInfo>,
+    flush_completed: Vec<FlushJobInfo>,
+    compaction_completed: Vec<CompactionJobInfo>,
+    table_files_created: Vec<TableFileCreationInfo>,
+    memtables_sealed: Vec<MemTableInfo>,
+    background_errors: Vec<(BackgroundErrorReason, Error)>,
+    stall_conditions: Vec<WriteStallInfo>,
+}
+
+struct Recorder(Arc<Mutex<Events>>);
+
+impl EventListener for Recorder {
+    fn on_flush_begin(&self, info: &FlushJobInfo) {
+        self.0.lock().unwrap().flush_begin.push(info.clone());
+    }
+
+    fn on_flush_completed(&self, info: &FlushJobInfo) {
+        self.0.lock().unwrap().flush_completed.push(info.clone());
+    }
+
+    fn on_compaction_completed(&self, info: &CompactionJobInfo) {
+        self.0
+            .lock()
+            .unwrap()
+            .compaction_completed
+            .push(info.clone());
+    }
+
+    fn on_table_file_created(&self, info: &TableFileCreationInfo) {
+        self.0
+            .lock()
+            .unwrap()
+            .table_files_created
+            .push(info.clone());
+    }
+
+    fn on_memtable_sealed(&self, info: &MemTableInfo) {
+        self.0.lock().unwrap().memtables_sealed.push(info.clone());
+    }
+
+    fn on_background_error(&self, reason: BackgroundErrorReason, error: &Error) {
+        self.0
+            .lock()
+            .unwrap()
+            .background_errors
+            .push((reason, error.clone()));
+    }
+
+    fn on_stall_conditions_changed(&self, info: &WriteStallInfo) {
+        self.0.lock().unwrap().stall_conditions.push(info.clone());
+    }
+}
+
+#[test]
+fn test_event_listener() {
+    let path = TemporaryDBPath::new();
+    let events = Arc::new(Mutex::new(Events::default()));
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.add_event_listener(Recorder(events.clone()));
+        let db = DB::open(&opts, &path).unwrap();
+
+        for round in 0..2 {
+            for i in 0..100 {
+                db.put(format!("key{}", i), format!("value{}", round))
+                    .unwrap();
+            }
+            db.flush().unwrap();
+        }
+        db.compact_range(None::<&[u8]>, None::<&[u8]>);
+    }
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.flush_begin.len(), 2);
+    assert_eq!(events.flush_completed.len(), 2);
+    assert_eq!(events.memtables_sealed.len(), 2);
+    assert_eq!(events.memtables_sealed[0].num_entries, 100);
+
+    let flush = &events.flush_completed[0];
+    assert_eq!(flush.cf_name, "default");
+    assert!(flush.file_path.ends_with(".sst"));
+
+    assert!(!events.compaction_completed.is_empty());
+    let compaction = &events.compaction_completed[0];
+    assert!(compaction.status.is_ok());
+    assert_eq!(compaction.input_files.len(), 2);
+    assert_eq!(compaction.num_input_records, 200);
+    assert_eq!(compaction.num_output_records, 100);
+
+    let reasons: Vec<_> = events
+        .table_files_created
+        .iter()
+        .map(|info| info.reason)
+        .collect();
+    assert_eq!(
+        &reasons[..3],
+        &[
+            TableFileCreationReason::Flush,
+            TableFileCreationReason::Flush,
+            TableFileCreationReason::Compaction
+        ]
+    );
+}
+
+fn failing_merge(
+    _key: &[u8],
+    _existing: Option<&[u8]>,
+    _operands: &mut MergeOperands,
+) -> Option<Vec<u8>> {
+    None
+}
+
+#[test]
+fn test_event_listener_background_error() {
+    let path = TemporaryDBPath::new();
+    let events = Arc::new(Mutex::new(Events::default()));
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_merge_operator("failing", failing_merge, failing_merge);
+    opts.add_event_listener(Recorder(events.clone()));
+    let db = DB::open(&opts, &path).unwrap();
+
+    // Flushing merges the operand into the value, which fails.
+    db.put(b"k1", b"v1").unwrap();
+    db.merge(b"k1", b"v2").unwrap();
+    assert!(db.flush().is_err());
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.background_errors.len(), 1);
+    let (reason, error) = &events.background_errors[0];
+    assert!(matches!(
+        reason,
+        BackgroundErrorReason::Flush | BackgroundErrorReason::FlushNoWAL
+    ));
+    assert!(error.as_ref().contains("Corruption"), "{}", error);
+}
+
+#[test]
+fn test_event_listener_stall_conditions() {
+    let path = TemporaryDBPath::new();
+    let events = Arc::new(Mutex::new(Events::default()));
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_level_zero_file_num_compaction_trigger(1);
+        opts.set_level_zero_slowdown_writes_trigger(1);
+        opts.set_level_zero_stop_writes_trigger(20);
+        opts.add_event_listener(Recorder(events.clone()));
+        let db = DB::open(&opts, &path).unwrap();
+
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+        db.compact_range(None::<&[u8]>, None::<&[u8]>);
+    }
+
+    let events = events.lock().unwrap();
+    let changes: Vec<_> = events
+        .stall_conditions
+        .iter()
+        .map(|info| (info.cf_name.as_str(), info.prev, info.cur))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            (
+                "default",
+                WriteStallCondition::Normal,
+                WriteStallCondition::Delayed
+            ),
+            (
+                "default",
+                WriteStallCondition::Delayed,
+                WriteStallCondition::Normal
+            ),
+        ]
+    );
+}
@@ -0,0 +1,117 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "fault-injection")]
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{prelude::*, FaultInjectionEnv, FlushOptions, TemporaryDBPath, WriteOptions};
+
+fn options(fault_env: &FaultInjectionEnv) -> Options {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(fault_env.env());
+    opts
+}
+
+fn sync_write_options() -> WriteOptions {
+    let mut write_opts = WriteOptions::default();
+    write_opts.set_sync(true);
+    write_opts
+}
+
+#[test]
+fn test_power_loss_drops_unsynced_writes() {
+    let path = TemporaryDBPath::new();
+    let fault_env = FaultInjectionEnv::new().unwrap();
+    let opts = options(&fault_env);
+
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        db.put_opt(b"k1", b"v1", &sync_write_options()).unwrap();
+        db.put(b"k2", b"v2").unwrap();
+        db.put_opt(b"k3", b"v3", &sync_write_options()).unwrap();
+        db.put(b"k4", b"v4").unwrap();
+
+        fault_env.crash();
+        assert!(fault_env.is_crashed());
+        assert!(db.put(b"k5", b"v5").is_err());
+    }
+    fault_env.recover().unwrap();
+
+    let db = DB::open(&opts, &path).unwrap();
+    // The sync of k3 also persisted k2, which was written before.
+    assert_eq!(db.get(b"k1").unwrap().unwrap().as_ref(), b"v1");
+    assert_eq!(db.get(b"k2").unwrap().unwrap().as_ref(), b"v2");
+    assert_eq!(db.get(b"k3").unwrap().unwrap().as_ref(), b"v3");
+    assert!(db.get(b"k4").unwrap().is_none());
+    assert!(db.get(b"k5").unwrap().is_none());
+}
+
+#[test]
+fn test_power_loss_keeps_flushed_data() {
+    let path = TemporaryDBPath::new();
+    let fault_env = FaultInjectionEnv::new().unwrap();
+    let opts = options(&fault_env);
+
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        let mut write_opts = WriteOptions::default();
+        write_opts.disable_wal(true);
+        db.put_opt(b"flushed", b"v1", &write_opts).unwrap();
+        let mut flush_opts = FlushOptions::default();
+        flush_opts.set_wait(true);
+        db.flush_opt(&flush_opts).unwrap();
+        db.put_opt(b"not_flushed", b"v2", &write_opts).unwrap();
+
+        fault_env.crash();
+    }
+    fault_env.recover().unwrap();
+
+    let db = DB::open(&opts, &path).unwrap();
+    assert_eq!(db.get(b"flushed").unwrap().unwrap().as_ref(), b"v1");
+    assert!(db.get(b"not_flushed").unwrap().is_none());
+}
+
+#[test]
+fn test_injected_write_and_sync_errors() {
+    let path = TemporaryDBPath::new();
+    let fault_env = FaultInjectionEnv::new().unwrap();
+    let opts = options(&fault_env);
+
+    let db = DB::open(&opts, &path).unwrap();
+    fault_env.fail_syncs_after(0);
+    db.put(b"k1", b"v1").unwrap();
+    assert!(db.put_opt(b"k2", b"v2", &sync_write_options()).is_err());
+    drop(db);
+
+    fault_env.clear_faults();
+    let db = DB::open(&opts, &path).unwrap();
+    fault_env.fail_writes_after(1);
+    db.put(b"k3", b"v3").unwrap();
+    let err = db.put(b"k4", b"v4").unwrap_err();
+    assert!(err.to_string().contains("injected write error"));
+}
+
+#[test]
+fn test_no_space() {
+    let path = TemporaryDBPath::new();
+    let fault_env = FaultInjectionEnv::new().unwrap();
+    let opts = options(&fault_env);
+
+    let db = DB::open(&opts, &path).unwrap();
+    fault_env.set_no_space(true);
+    let err = db.put(b"k1", b"v1").unwrap_err();
+    assert!(err.to_string().contains("No space"));
+}
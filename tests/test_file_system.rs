@@ -0,0 +1,189 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::file_system::{
+    FileSystem, RandomAccessFile, SequentialFile, StdFileSystem, WritableFile,
+};
+use crate::rocksdb::{prelude::*, Env, TemporaryDBPath};
+use std::ffi::OsString;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Counts the bytes written through the files it creates.
+struct CountingWritableFile {
+    inner: Box<dyn WritableFile>,
+    written: Arc<AtomicU64>,
+}
+
+impl WritableFile for CountingWritableFile {
+    fn append(&mut self, data: &[u8]) -> io::Result<()> {
+        self.written.fetch_add(data.len() as u64, Ordering::SeqCst);
+        self.inner.append(data)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.inner.sync()
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        self.inner.close()
+    }
+}
+
+struct CountingFileSystem {
+    inner: StdFileSystem,
+    written: Arc<AtomicU64>,
+    limit: u64,
+}
+
+impl FileSystem for CountingFileSystem {
+    fn new_sequential_file(&self, path: &Path) -> io::Result<Box<dyn SequentialFile>> {
+        self.inner.new_sequential_file(path)
+    }
+
+    fn new_random_access_file(&self, path: &Path) -> io::Result<Box<dyn RandomAccessFile>> {
+        self.inner.new_random_access_file(path)
+    }
+
+    fn new_writable_file(&self, path: &Path) -> io::Result<Box<dyn WritableFile>> {
+        if self.written.load(Ordering::SeqCst) >= self.limit {
+            return Err(io::Error::new(io::ErrorKind::Other, "quota exceeded"));
+        }
+        Ok(Box::new(CountingWritableFile {
+            inner: self.inner.new_writable_file(path)?,
+            written: self.written.clone(),
+        }))
+    }
+
+    fn file_exists(&self, path: &Path) -> io::Result<bool> {
+        self.inner.file_exists(path)
+    }
+
+    fn get_children(&self, dir: &Path) -> io::Result<Vec<OsString>> {
+        self.inner.get_children(dir)
+    }
+
+    fn delete_file(&self, path: &Path) -> io::Result<()> {
+        self.inner.delete_file(path)
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.inner.create_dir(path)
+    }
+
+    fn create_dir_if_missing(&self, path: &Path) -> io::Result<()> {
+        self.inner.create_dir_if_missing(path)
+    }
+
+    fn delete_dir(&self, path: &Path) -> io::Result<()> {
+        self.inner.delete_dir(path)
+    }
+
+    fn get_file_size(&self, path: &Path) -> io::Result<u64> {
+        self.inner.get_file_size(path)
+    }
+
+    fn get_file_modification_time(&self, path: &Path) -> io::Result<u64> {
+        self.inner.get_file_modification_time(path)
+    }
+
+    fn rename_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        self.inner.rename_file(src, target)
+    }
+
+    fn lock_file(&self, path: &Path) -> io::Result<()> {
+        self.inner.lock_file(path)
+    }
+
+    fn unlock_file(&self, path: &Path) -> io::Result<()> {
+        self.inner.unlock_file(path)
+    }
+
+    fn sync_dir(&self, dir: &Path) -> io::Result<()> {
+        self.inner.sync_dir(dir)
+    }
+
+    fn is_directory(&self, path: &Path) -> io::Result<bool> {
+        self.inner.is_directory(path)
+    }
+}
+
+#[test]
+fn test_std_file_system() {
+    let path = TemporaryDBPath::new();
+    let env = Env::from_file_system(StdFileSystem::new()).unwrap();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(&env);
+
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+        db.put(b"k2", b"v2").unwrap();
+
+        // The lock is held while the database is open.
+        assert!(DB::open(&opts, &path).is_err());
+    }
+
+    let db = DB::open(&opts, &path).unwrap();
+    assert_eq!(db.get(b"k1").unwrap().unwrap().as_ref(), b"v1");
+    assert_eq!(db.get(b"k2").unwrap().unwrap().as_ref(), b"v2");
+}
+
+#[test]
+fn test_file_system_wrapper() {
+    let path = TemporaryDBPath::new();
+    let written = Arc::new(AtomicU64::new(0));
+    let env = Env::from_file_system(CountingFileSystem {
+        inner: StdFileSystem::new(),
+        written: written.clone(),
+        limit: u64::MAX,
+    })
+    .unwrap();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(&env);
+
+    let db = DB::open(&opts, &path).unwrap();
+    let before = written.load(Ordering::SeqCst);
+    db.put(b"k1", vec![0u8; 4096]).unwrap();
+    db.flush().unwrap();
+    assert!(written.load(Ordering::SeqCst) >= before + 4096);
+}
+
+#[test]
+fn test_file_system_error() {
+    let path = TemporaryDBPath::new();
+    let env = Env::from_file_system(CountingFileSystem {
+        inner: StdFileSystem::new(),
+        written: Arc::new(AtomicU64::new(0)),
+        limit: 0,
+    })
+    .unwrap();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(&env);
+
+    let err = DB::open(&opts, &path).unwrap_err();
+    assert!(err.to_string().contains("quota exceeded"));
+}
@@ -14,7 +14,11 @@
 
 extern crate ckb_rocksdb as rocksdb;
 
-use crate::rocksdb::{prelude::*, FullOptions, TemporaryDBPath, DB};
+use crate::rocksdb::{
+    prelude::*, BlockBasedOptions, Cache, ColumnFamilyDescriptor, Env, FullOptions, IteratorMode,
+    MergeOperands, OptionChange, OptionsRegistry, PlainTableFactoryOptions, ReadOnlyDB,
+    SliceTransform, TemporaryDBPath, TransactionDB, DB,
+};
 
 #[test]
 fn test_options_load_from_file() {
@@ -108,3 +112,304 @@ fn test_options_load_from_file() {
         assert!(cf_c_opt.is_some());
     }
 }
+
+fn concat_merge(
+    _key: &[u8],
+    existing: Option<&[u8]>,
+    operands: &mut MergeOperands,
+) -> Option<Vec<u8>> {
+    let mut result = existing.map(|v| v.to_vec()).unwrap_or_default();
+    for op in operands {
+        result.extend_from_slice(op);
+    }
+    Some(result)
+}
+
+fn reverse_options() -> Options {
+    let mut opts = Options::default();
+    opts.set_comparator("reverse", Box::new(|a: &[u8], b: &[u8]| b.cmp(a)));
+    opts.set_merge_operator_associative("concat", concat_merge);
+    opts
+}
+
+fn create_db_with_rust_options(path: &TemporaryDBPath) {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let mut cf_opts = reverse_options();
+    cf_opts.set_write_buffer_size(8 * 1024 * 1024);
+    let db = DB::open_cf_descriptors(
+        &opts,
+        path,
+        vec![ColumnFamilyDescriptor::new("reversed", cf_opts)],
+    )
+    .unwrap();
+    let cf = db.cf_handle("reversed").unwrap();
+    db.put_cf(cf, b"a", b"1").unwrap();
+    db.merge_cf(cf, b"b", b"2").unwrap();
+}
+
+fn rust_options_registry() -> OptionsRegistry {
+    let mut registry = OptionsRegistry::default();
+    registry
+        .register_comparator("reverse", |opts| {
+            opts.set_comparator("reverse", Box::new(|a: &[u8], b: &[u8]| b.cmp(a)))
+        })
+        .register_merge_operator("concat", |opts| {
+            opts.set_merge_operator_associative("concat", concat_merge)
+        });
+    registry
+}
+
+#[test]
+fn test_options_load_latest() {
+    let path = TemporaryDBPath::new();
+    create_db_with_rust_options(&path);
+
+    let env = Env::default().unwrap();
+    let full_opts = FullOptions::load_latest(&path, &env, None).unwrap();
+    let mut names: Vec<_> = full_opts
+        .cf_descriptors
+        .iter()
+        .map(|cf| cf.name())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, vec!["default", "reversed"]);
+
+    let empty = TemporaryDBPath::new();
+    assert!(FullOptions::load_latest(&empty, &env, None).is_err());
+}
+
+#[test]
+fn test_open_with_latest_options() {
+    let path = TemporaryDBPath::new();
+    create_db_with_rust_options(&path);
+    let env = Env::default().unwrap();
+    let cache = Cache::new_lru_cache(1024 * 1024).unwrap();
+
+    let err = DB::open_with_latest_options(&path, &env, None, &OptionsRegistry::default())
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("\"reverse\""), "{}", err);
+
+    let registry = rust_options_registry();
+    {
+        let db = DB::open_with_latest_options(&path, &env, Some(&cache), &registry).unwrap();
+        let cf = db.cf_handle("reversed").unwrap();
+        db.merge_cf(cf, b"b", b"3").unwrap();
+        assert_eq!(db.get_cf(cf, b"b").unwrap().unwrap().to_vec(), b"23");
+        let keys: Vec<_> = db
+            .iterator_cf(cf, IteratorMode::Start)
+            .unwrap()
+            .map(|(k, _)| k.to_vec())
+            .collect();
+        assert_eq!(keys, vec![b"b".to_vec(), b"a".to_vec()]);
+    }
+    {
+        let db = ReadOnlyDB::open_with_latest_options(&path, &env, None, &registry).unwrap();
+        let cf = db.cf_handle("reversed").unwrap();
+        assert_eq!(db.get_cf(cf, b"a").unwrap().unwrap().to_vec(), b"1");
+    }
+    {
+        let db = TransactionDB::open_with_latest_options(&path, &env, None, &registry).unwrap();
+        let cf = db.cf_handle("reversed").unwrap();
+        assert_eq!(db.get_cf(cf, b"b").unwrap().unwrap().to_vec(), b"23");
+    }
+}
+
+#[test]
+fn test_check_options_compatibility() {
+    let path = TemporaryDBPath::new();
+    create_db_with_rust_options(&path);
+
+    let full_opts = |cf_opts: Options| FullOptions {
+        db_opts: Options::default(),
+        cf_descriptors: vec![ColumnFamilyDescriptor::new("reversed", cf_opts)],
+    };
+    full_opts(reverse_options())
+        .check_compatibility(&path, false)
+        .unwrap();
+
+    let mut cf_opts = Options::default();
+    cf_opts.set_merge_operator_associative("concat", concat_merge);
+    let err = full_opts(cf_opts)
+        .check_compatibility(&path, false)
+        .unwrap_err();
+    assert!(err.to_string().contains("reversed"), "{}", err);
+    assert!(err.to_string().contains("comparator"), "{}", err);
+
+    let mut cf_opts = reverse_options();
+    cf_opts.set_merge_operator_associative("append", concat_merge);
+    let err = full_opts(cf_opts)
+        .check_compatibility(&path, false)
+        .unwrap_err();
+    assert!(err.to_string().contains("merge_operator"), "{}", err);
+
+    let mut cf_opts = reverse_options();
+    cf_opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(2));
+    let err = full_opts(cf_opts)
+        .check_compatibility(&path, false)
+        .unwrap_err();
+    assert!(err.to_string().contains("prefix_extractor"), "{}", err);
+
+    let err = FullOptions {
+        db_opts: Options::default(),
+        cf_descriptors: vec![],
+    }
+    .check_compatibility(&path, false)
+    .unwrap_err();
+    assert!(err.to_string().contains("reversed"), "{}", err);
+}
+
+#[test]
+fn test_check_options_compatibility_table_format() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(3));
+    drop(DB::open(&opts, &path).unwrap());
+
+    let mut db_opts = Options::default();
+    db_opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(3));
+    let full_opts = FullOptions {
+        db_opts,
+        cf_descriptors: vec![],
+    };
+    full_opts.check_compatibility(&path, false).unwrap();
+
+    let mut cf_opts = Options::default();
+    cf_opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(3));
+    cf_opts.set_plain_table_factory(&PlainTableFactoryOptions {
+        user_key_length: 0,
+        bloom_bits_per_key: 10,
+        hash_table_ratio: 0.75,
+        index_sparseness: 16,
+    });
+    let err = FullOptions {
+        db_opts: Options::default(),
+        cf_descriptors: vec![ColumnFamilyDescriptor::new("default", cf_opts)],
+    }
+    .check_compatibility(&path, false)
+    .unwrap_err();
+    assert!(err.to_string().contains("table_factory"), "{}", err);
+}
+
+#[test]
+fn test_full_options_diff() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    drop(DB::open_cf(&opts, &path, ["cf1", "cf2"]).unwrap());
+
+    let env = Env::default().unwrap();
+    let loaded = FullOptions::load_latest(&path, &env, None).unwrap();
+    assert!(loaded.diff(&loaded.clone()).unwrap().is_empty());
+
+    let loaded_opts = |name: &str| {
+        let cf = loaded.cf_descriptors.iter().find(|cf| cf.name() == name);
+        cf.unwrap().options().clone()
+    };
+    let mut db_opts = loaded.db_opts.clone();
+    db_opts.set_max_open_files(100);
+    db_opts.set_use_fsync(true);
+    let mut cf_opts = loaded_opts("cf1");
+    cf_opts.set_write_buffer_size(1 << 20);
+    cf_opts.set_num_levels(4);
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_size(16 * 1024);
+    cf_opts.set_block_based_table_factory(&block_opts);
+    let new = FullOptions {
+        db_opts,
+        cf_descriptors: vec![
+            ColumnFamilyDescriptor::new("default", loaded_opts("default")),
+            ColumnFamilyDescriptor::new("cf1", cf_opts),
+            ColumnFamilyDescriptor::new("cf3", loaded_opts("default")),
+        ],
+    };
+
+    let diff = loaded.diff(&new).unwrap();
+    assert_eq!(diff.added_column_families, ["cf3"]);
+    assert_eq!(diff.dropped_column_families, ["cf2"]);
+    assert_eq!(
+        changes(&diff.db_options),
+        [
+            ("max_open_files", "-1", "100", true),
+            ("use_fsync", "false", "true", false),
+        ]
+    );
+    assert_eq!(diff.column_families.len(), 1);
+    assert_eq!(diff.column_families[0].0, "cf1");
+    assert_eq!(
+        changes(&diff.column_families[0].1),
+        [
+            ("num_levels", "7", "4", false),
+            ("table_factory.block_size", "4096", "16384", true),
+            ("write_buffer_size", "67108864", "1048576", true),
+        ]
+    );
+}
+
+#[test]
+fn test_full_options_diff_against_opening_options() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.set_max_open_files(5);
+    drop(DB::open_cf(&opts, &path, ["cf1"]).unwrap());
+
+    let env = Env::default().unwrap();
+    let loaded = FullOptions::load_latest(&path, &env, None).unwrap();
+    let fresh = FullOptions {
+        db_opts: opts.clone(),
+        cf_descriptors: vec![
+            ColumnFamilyDescriptor::new("default", opts.clone()),
+            ColumnFamilyDescriptor::new("cf1", opts),
+        ],
+    };
+
+    // only the options RocksDB adjusts when opening differ, see the caveat
+    // of FullOptions::diff
+    let diff = loaded.diff(&fresh).unwrap();
+    assert_eq!(
+        changes(&diff.db_options),
+        [
+            ("delayed_write_rate", "16777216", "0", true),
+            ("max_open_files", "20", "5", true),
+        ]
+    );
+    assert_eq!(diff.column_families.len(), 2);
+    for (name, cf_changes) in &diff.column_families {
+        assert_eq!(
+            changes(cf_changes),
+            [
+                ("arena_block_size", "1048576", "0", true),
+                ("max_compaction_bytes", "1677721600", "0", true),
+                (
+                    "periodic_compaction_seconds",
+                    "0",
+                    "18446744073709551614",
+                    true
+                ),
+                ("ttl", "2592000", "18446744073709551614", true),
+            ],
+            "{}",
+            name
+        );
+    }
+}
+
+fn changes(changes: &[OptionChange]) -> Vec<(&str, &str, &str, bool)> {
+    changes
+        .iter()
+        .map(|change| {
+            (
+                change.name.as_str(),
+                change.old_value.as_deref().unwrap(),
+                change.new_value.as_deref().unwrap(),
+                change.mutable,
+            )
+        })
+        .collect()
+}
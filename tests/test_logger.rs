@@ -0,0 +1,67 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{prelude::*, LogLevel, TemporaryDBPath};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_logger_receives_messages() {
+    let path = TemporaryDBPath::new();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let sink = messages.clone();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_logger(LogLevel::Info, move |level, msg| {
+        sink.lock().unwrap().push((level, msg.to_owned()));
+    });
+
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+    }
+
+    let messages = messages.lock().unwrap();
+    assert!(messages.iter().any(|(level, _)| *level == LogLevel::Header));
+    assert!(messages
+        .iter()
+        .any(|(level, msg)| *level == LogLevel::Info && msg.contains("[default]")));
+    assert!(!path.as_ref().join("LOG").exists());
+}
+
+#[test]
+fn test_logger_filters_by_level() {
+    let path = TemporaryDBPath::new();
+    let levels = Arc::new(Mutex::new(Vec::new()));
+    let sink = levels.clone();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_logger(LogLevel::Warn, move |level, _| {
+        sink.lock().unwrap().push(level);
+    });
+
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+    }
+
+    let levels = levels.lock().unwrap();
+    assert!(!levels.is_empty());
+    assert!(levels
+        .iter()
+        .all(|level| !matches!(level, LogLevel::Debug | LogLevel::Info)));
+}
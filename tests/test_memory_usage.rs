@@ -0,0 +1,53 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{
+    get_memory_usage_stats, prelude::*, BlockBasedOptions, Cache, DBWithTTL, TemporaryDBPath,
+};
+
+#[test]
+fn memory_usage_of_dbs_and_caches() {
+    let path_a = TemporaryDBPath::new();
+    let path_b = TemporaryDBPath::new();
+    let cache = Cache::new_lru_cache(8 * 1024 * 1024).unwrap();
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_cache(&cache);
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_block_based_table_factory(&block_opts);
+
+    let db = DB::open(&opts, &path_a).unwrap();
+    let ttl_db = DBWithTTL::open(&opts, &path_b).unwrap();
+    let empty = get_memory_usage_stats(&[&db, &ttl_db], &[&cache]).unwrap();
+
+    for i in 0..100u32 {
+        db.put(format!("key{:03}", i), vec![0u8; 128]).unwrap();
+        ttl_db.put(format!("key{:03}", i), vec![0u8; 128]).unwrap();
+    }
+    let written = get_memory_usage_stats(&[&db, &ttl_db], &[&cache]).unwrap();
+    assert!(written.mem_table_unflushed > empty.mem_table_unflushed);
+    assert!(written.mem_table_total >= written.mem_table_unflushed);
+    let db_only = get_memory_usage_stats(&[&db], &[]).unwrap();
+    assert!(db_only.mem_table_unflushed < written.mem_table_unflushed);
+    assert_eq!(db_only.cache_total, 0);
+
+    db.flush().unwrap();
+    assert!(db.get(b"key042").unwrap().is_some());
+    let flushed = get_memory_usage_stats(&[&db, &ttl_db], &[&cache, &cache]).unwrap();
+    assert!(flushed.mem_table_readers_total > 0);
+    assert!(flushed.cache_total > 0);
+    assert_eq!(flushed.cache_total, cache.get_usage() as u64);
+}
@@ -402,3 +402,49 @@ fn test_merge_state() {
     }
     assert!(DB::destroy(&opts, path).is_ok());
 }
+
+#[test]
+fn get_merge_operands_test() {
+    use ckb_rocksdb::ops::{
+        GetColumnFamilys, GetMergeOperands, GetMergeOperandsCF, MergeCF, OpenCF,
+    };
+    use ckb_rocksdb::ColumnFamilyDescriptor;
+
+    let db_path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.set_merge_operator_associative("test operator", test_provided_merge);
+
+    let mut default_opts = Options::default();
+    default_opts.set_merge_operator_associative("test operator", test_provided_merge);
+    let default_cf = ColumnFamilyDescriptor::new("default", default_opts);
+
+    let mut cf_opts = Options::default();
+    cf_opts.set_merge_operator_associative("test operator", test_provided_merge);
+    let cf = ColumnFamilyDescriptor::new("counters", cf_opts);
+
+    let db = DB::open_cf_descriptors(&opts, &db_path, vec![default_cf, cf]).unwrap();
+    db.put(b"k1", b"a").unwrap();
+    db.merge(b"k1", b"b").unwrap();
+    db.merge(b"k1", b"c").unwrap();
+
+    let operands = db.get_merge_operands(b"k1", 10).unwrap();
+    let operands: Vec<_> = operands.iter().map(|op| op.to_vec()).collect();
+    assert_eq!(operands, vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+
+    // more operands than the limit
+    assert!(db.get_merge_operands(b"k1", 2).is_err());
+    // an invalid limit
+    assert!(db.get_merge_operands(b"k1", 0).is_err());
+    // a missing key
+    assert!(db.get_merge_operands(b"missing", 10).unwrap().is_empty());
+
+    let cf = db.cf_handle("counters").unwrap();
+    db.merge_cf(cf, b"k2", b"x").unwrap();
+    db.merge_cf(cf, b"k2", b"y").unwrap();
+    let operands = db.get_merge_operands_cf(cf, b"k2", 10).unwrap();
+    let operands: Vec<_> = operands.iter().map(|op| op.to_vec()).collect();
+    assert_eq!(operands, vec![b"x".to_vec(), b"y".to_vec()]);
+    assert_eq!(db.get(b"k1").unwrap().unwrap().as_ref(), b"abc".as_ref());
+}
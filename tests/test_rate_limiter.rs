@@ -0,0 +1,51 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{prelude::*, RateLimiter, RateLimiterMode, TemporaryDBPath};
+
+#[test]
+fn test_shared_rate_limiter() {
+    let limiter = RateLimiter::new(64 * 1024 * 1024, 100 * 1000, 10).unwrap();
+    let path_a = TemporaryDBPath::new();
+    let path_b = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_rate_limiter(&limiter);
+
+    let db_a = DB::open(&opts, &path_a).unwrap();
+    db_a.put(b"k1", vec![0u8; 4096]).unwrap();
+    db_a.flush().unwrap();
+    let after_a = limiter.get_total_bytes_through();
+    assert!(after_a >= 4096);
+
+    let db_b = DB::open(&opts, &path_b).unwrap();
+    db_b.put(b"k1", vec![0u8; 4096]).unwrap();
+    db_b.flush().unwrap();
+    assert!(limiter.get_total_bytes_through() >= after_a + 4096);
+    assert!(limiter.get_total_requests() > 0);
+}
+
+#[test]
+fn test_set_bytes_per_second() {
+    let limiter =
+        RateLimiter::new_with_mode(1024 * 1024, 100 * 1000, 10, RateLimiterMode::AllIo, false)
+            .unwrap();
+    assert_eq!(limiter.get_bytes_per_second(), 1024 * 1024);
+
+    limiter.set_bytes_per_second(2 * 1024 * 1024);
+    assert_eq!(limiter.get_bytes_per_second(), 2 * 1024 * 1024);
+    assert!(limiter.get_single_burst_bytes() > 0);
+}
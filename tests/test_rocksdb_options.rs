@@ -14,7 +14,12 @@
 //
 extern crate ckb_rocksdb as rocksdb;
 
-use crate::rocksdb::{prelude::*, TemporaryDBPath};
+use crate::rocksdb::{
+    prelude::*, AccessHint, BlockBasedOptions, DBCompactionStyle, DBCompressionType, DBPath,
+    DBRecoveryMode, FifoCompactOptions, LogLevel, TemporaryDBPath, UniversalCompactOptions,
+    UniversalCompactionStopStyle,
+};
+use std::path::PathBuf;
 
 #[test]
 fn test_set_num_levels() {
@@ -37,3 +42,347 @@ fn test_increase_parallelism() {
         let _db = DB::open(&opts, &n).unwrap();
     }
 }
+
+#[test]
+fn test_options_string_round_trip() {
+    let mut opts = Options::default();
+    opts.set_max_open_files(123);
+    opts.set_write_buffer_size(4 * 1024 * 1024);
+    let opts_str = opts.to_options_string().unwrap();
+    assert!(opts_str.contains("max_open_files=123;"), "{}", opts_str);
+    assert!(
+        opts_str.contains("write_buffer_size=4194304;"),
+        "{}",
+        opts_str
+    );
+
+    let restored = Options::from_options_string(&Options::default(), &opts_str).unwrap();
+    assert_eq!(restored.to_options_string().unwrap(), opts_str);
+
+    let tuned = Options::from_options_string(&opts, "max_write_buffer_number=5").unwrap();
+    let tuned_str = tuned.to_options_string().unwrap();
+    assert!(tuned_str.contains("max_write_buffer_number=5;"));
+    assert!(tuned_str.contains("max_open_files=123;"));
+
+    assert!(Options::from_options_string(&opts, "no_such_option=1").is_err());
+    assert!(Options::from_options_string(&opts, "max_open_files=abc").is_err());
+}
+
+#[test]
+fn test_block_based_options_string_round_trip() {
+    let mut opts = BlockBasedOptions::default();
+    opts.set_block_size(32 * 1024);
+    let opts_str = opts.to_options_string().unwrap();
+    assert!(opts_str.contains("block_size=32768;"), "{}", opts_str);
+
+    let tuned = BlockBasedOptions::from_options_string(&opts, "block_restart_interval=8").unwrap();
+    let tuned_str = tuned.to_options_string().unwrap();
+    assert!(tuned_str.contains("block_restart_interval=8;"));
+    assert!(tuned_str.contains("block_size=32768;"));
+
+    assert!(BlockBasedOptions::from_options_string(&opts, "no_such_option=1").is_err());
+}
+
+#[test]
+fn test_get_options_string() {
+    let n = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.set_max_background_jobs(3);
+    let db = DB::open_cf(&opts, &n, ["cf1"]).unwrap();
+
+    let db_opts_str = db.get_db_options_string().unwrap();
+    assert!(
+        db_opts_str.contains("max_background_jobs=3;"),
+        "{}",
+        db_opts_str
+    );
+
+    assert!(db
+        .get_options_string()
+        .unwrap()
+        .contains("disable_auto_compactions=false;"));
+    db.set_options(&[("disable_auto_compactions", "true")])
+        .unwrap();
+    assert!(db
+        .get_options_string()
+        .unwrap()
+        .contains("disable_auto_compactions=true;"));
+
+    let cf1 = db.cf_handle("cf1").unwrap();
+    assert!(db
+        .get_options_string_cf(cf1)
+        .unwrap()
+        .contains("disable_auto_compactions=false;"));
+    db.set_options_cf(cf1, &[("write_buffer_size", "1048576")])
+        .unwrap();
+    assert!(db
+        .get_options_string_cf(cf1)
+        .unwrap()
+        .contains("write_buffer_size=1048576;"));
+}
+
+#[test]
+fn test_options_getters() {
+    let mut opts = Options::default();
+    assert_eq!(opts.get_bottommost_compression_type(), None);
+    assert_eq!(opts.get_bottommost_compression_options(), None);
+    assert_eq!(opts.get_db_log_dir(), None);
+    assert_eq!(opts.get_wal_dir(), None);
+    assert!(opts.get_db_paths().is_empty());
+
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.set_error_if_exists(true);
+    opts.set_paranoid_checks(false);
+    opts.set_enable_blob_files(true);
+    opts.set_enable_blob_gc(true);
+    opts.set_level_compaction_dynamic_level_bytes(true);
+    opts.set_optimize_filters_for_hits(true);
+    opts.set_use_fsync(true);
+    opts.set_allow_concurrent_memtable_write(false);
+    opts.set_enable_write_thread_adaptive_yield(false);
+    opts.set_use_direct_reads(true);
+    opts.set_use_direct_io_for_flush_and_compaction(true);
+    opts.set_is_fd_close_on_exec(false);
+    opts.set_unordered_write(true);
+    opts.set_disable_auto_compactions(true);
+    opts.set_inplace_update_support(true);
+    opts.set_skip_checking_sst_file_sizes_on_db_open(true);
+    opts.set_enable_pipelined_write(true);
+    opts.set_report_bg_io_stats(true);
+    opts.set_advise_random_on_open(false);
+    opts.set_use_adaptive_mutex(true);
+    opts.set_skip_stats_update_on_db_open(true);
+    opts.set_allow_mmap_writes(true);
+    opts.set_allow_mmap_reads(true);
+    opts.set_manual_wal_flush(true);
+    opts.set_atomic_flush(true);
+    opts.set_dump_malloc_stats(true);
+    opts.set_memtable_whole_key_filtering(true);
+    assert!(opts.get_create_if_missing());
+    assert!(opts.get_create_missing_column_families());
+    assert!(opts.get_error_if_exists());
+    assert!(!opts.get_paranoid_checks());
+    assert!(opts.get_enable_blob_files());
+    assert!(opts.get_enable_blob_gc());
+    assert!(opts.get_level_compaction_dynamic_level_bytes());
+    assert!(opts.get_optimize_filters_for_hits());
+    assert!(opts.get_use_fsync());
+    assert!(!opts.get_allow_concurrent_memtable_write());
+    assert!(!opts.get_enable_write_thread_adaptive_yield());
+    assert!(opts.get_use_direct_reads());
+    assert!(opts.get_use_direct_io_for_flush_and_compaction());
+    assert!(!opts.get_is_fd_close_on_exec());
+    assert!(opts.get_unordered_write());
+    assert!(opts.get_disable_auto_compactions());
+    assert!(opts.get_inplace_update_support());
+    assert!(opts.get_skip_checking_sst_file_sizes_on_db_open());
+    assert!(opts.get_enable_pipelined_write());
+    assert!(opts.get_report_bg_io_stats());
+    assert!(!opts.get_advise_random_on_open());
+    assert!(opts.get_use_adaptive_mutex());
+    assert!(opts.get_skip_stats_update_on_db_open());
+    assert!(opts.get_allow_mmap_writes());
+    assert!(opts.get_allow_mmap_reads());
+    assert!(opts.get_manual_wal_flush());
+    assert!(opts.get_atomic_flush());
+    assert!(opts.get_dump_malloc_stats());
+    assert!(opts.get_memtable_whole_key_filtering());
+
+    opts.set_zstd_max_train_bytes(1 << 20);
+    opts.set_compaction_readahead_size(2 << 20);
+    opts.set_min_blob_size(1024);
+    opts.set_blob_file_size(1 << 30);
+    opts.set_blob_gc_age_cutoff(0.5);
+    opts.set_blob_gc_force_threshold(0.8);
+    opts.set_delete_obsolete_files_period_micros(1000);
+    opts.set_max_open_files(100);
+    opts.set_max_file_opening_threads(4);
+    opts.set_bytes_per_sync(1 << 20);
+    opts.set_wal_bytes_per_sync(2 << 20);
+    opts.set_writable_file_max_buffer_size(3 << 20);
+    opts.set_max_sequential_skip_in_iterations(16);
+    opts.set_table_cache_num_shard_bits(5);
+    opts.set_target_file_size_multiplier(2);
+    opts.set_max_write_buffer_number(4);
+    opts.set_write_buffer_size(32 << 20);
+    opts.set_db_write_buffer_size(128 << 20);
+    opts.set_max_bytes_for_level_base(512 << 20);
+    opts.set_max_bytes_for_level_multiplier(8.0);
+    opts.set_max_manifest_file_size(64 << 20);
+    opts.set_target_file_size_base(128 << 20);
+    opts.set_min_write_buffer_number_to_merge(2);
+    opts.set_level_zero_file_num_compaction_trigger(8);
+    opts.set_level_zero_slowdown_writes_trigger(17);
+    opts.set_level_zero_stop_writes_trigger(24);
+    opts.set_max_subcompactions(2);
+    opts.set_max_background_jobs(6);
+    opts.set_memtable_huge_page_size(2 << 20);
+    opts.set_max_successive_merges(10);
+    opts.set_bloom_locality(1);
+    opts.set_inplace_update_locks(100);
+    opts.set_max_write_buffer_size_to_maintain(64 << 20);
+    opts.set_max_total_wal_size(1 << 30);
+    opts.set_stats_dump_period_sec(300);
+    opts.set_stats_persist_period_sec(60);
+    opts.set_num_levels(5);
+    opts.set_memtable_prefix_bloom_ratio(0.1);
+    opts.set_max_compaction_bytes(1 << 30);
+    opts.set_wal_ttl_seconds(3600);
+    opts.set_wal_size_limit_mb(1024);
+    opts.set_manifest_preallocation_size(1 << 20);
+    opts.set_keep_log_file_num(10);
+    opts.set_max_log_file_size(1 << 20);
+    opts.set_log_file_time_to_roll(86400);
+    opts.set_recycle_log_file_num(2);
+    opts.set_soft_pending_compaction_bytes_limit(1 << 30);
+    opts.set_hard_pending_compaction_bytes_limit(2 << 30);
+    opts.set_arena_block_size(1 << 20);
+    assert_eq!(opts.get_zstd_max_train_bytes(), 1 << 20);
+    assert_eq!(opts.get_compaction_readahead_size(), 2 << 20);
+    assert_eq!(opts.get_min_blob_size(), 1024);
+    assert_eq!(opts.get_blob_file_size(), 1 << 30);
+    assert_eq!(opts.get_blob_gc_age_cutoff(), 0.5);
+    assert_eq!(opts.get_blob_gc_force_threshold(), 0.8);
+    assert_eq!(opts.get_delete_obsolete_files_period_micros(), 1000);
+    assert_eq!(opts.get_max_open_files(), 100);
+    assert_eq!(opts.get_max_file_opening_threads(), 4);
+    assert_eq!(opts.get_bytes_per_sync(), 1 << 20);
+    assert_eq!(opts.get_wal_bytes_per_sync(), 2 << 20);
+    assert_eq!(opts.get_writable_file_max_buffer_size(), 3 << 20);
+    assert_eq!(opts.get_max_sequential_skip_in_iterations(), 16);
+    assert_eq!(opts.get_table_cache_num_shard_bits(), 5);
+    assert_eq!(opts.get_target_file_size_multiplier(), 2);
+    assert_eq!(opts.get_max_write_buffer_number(), 4);
+    assert_eq!(opts.get_write_buffer_size(), 32 << 20);
+    assert_eq!(opts.get_db_write_buffer_size(), 128 << 20);
+    assert_eq!(opts.get_max_bytes_for_level_base(), 512 << 20);
+    assert_eq!(opts.get_max_bytes_for_level_multiplier(), 8.0);
+    assert_eq!(opts.get_max_manifest_file_size(), 64 << 20);
+    assert_eq!(opts.get_target_file_size_base(), 128 << 20);
+    assert_eq!(opts.get_min_write_buffer_number_to_merge(), 2);
+    assert_eq!(opts.get_level_zero_file_num_compaction_trigger(), 8);
+    assert_eq!(opts.get_level_zero_slowdown_writes_trigger(), 17);
+    assert_eq!(opts.get_level_zero_stop_writes_trigger(), 24);
+    assert_eq!(opts.get_max_subcompactions(), 2);
+    assert_eq!(opts.get_max_background_jobs(), 6);
+    assert_eq!(opts.get_memtable_huge_page_size(), 2 << 20);
+    assert_eq!(opts.get_max_successive_merges(), 10);
+    assert_eq!(opts.get_bloom_locality(), 1);
+    assert_eq!(opts.get_inplace_update_locks(), 100);
+    assert_eq!(opts.get_max_write_buffer_size_to_maintain(), 64 << 20);
+    assert_eq!(opts.get_max_total_wal_size(), 1 << 30);
+    assert_eq!(opts.get_stats_dump_period_sec(), 300);
+    assert_eq!(opts.get_stats_persist_period_sec(), 60);
+    assert_eq!(opts.get_num_levels(), 5);
+    assert_eq!(opts.get_memtable_prefix_bloom_ratio(), 0.1);
+    assert_eq!(opts.get_max_compaction_bytes(), 1 << 30);
+    assert_eq!(opts.get_wal_ttl_seconds(), 3600);
+    assert_eq!(opts.get_wal_size_limit_mb(), 1024);
+    assert_eq!(opts.get_manifest_preallocation_size(), 1 << 20);
+    assert_eq!(opts.get_keep_log_file_num(), 10);
+    assert_eq!(opts.get_max_log_file_size(), 1 << 20);
+    assert_eq!(opts.get_log_file_time_to_roll(), 86400);
+    assert_eq!(opts.get_recycle_log_file_num(), 2);
+    assert_eq!(opts.get_soft_pending_compaction_bytes_limit(), 1 << 30);
+    assert_eq!(opts.get_hard_pending_compaction_bytes_limit(), 2 << 30);
+    assert_eq!(opts.get_arena_block_size(), 1 << 20);
+
+    opts.set_compression_type(DBCompressionType::Lz4);
+    opts.set_bottommost_compression_type(DBCompressionType::Zstd);
+    opts.set_blob_compression_type(DBCompressionType::Snappy);
+    opts.set_compaction_style(DBCompactionStyle::Universal);
+    opts.set_log_level(LogLevel::Warn);
+    opts.set_wal_recovery_mode(DBRecoveryMode::AbsoluteConsistency);
+    opts.set_access_hint_on_compaction_start(AccessHint::Sequential);
+    assert_eq!(opts.get_compression_type(), Some(DBCompressionType::Lz4));
+    assert_eq!(
+        opts.get_bottommost_compression_type(),
+        Some(DBCompressionType::Zstd)
+    );
+    assert_eq!(
+        opts.get_blob_compression_type(),
+        Some(DBCompressionType::Snappy)
+    );
+    assert_eq!(
+        opts.get_compaction_style(),
+        Some(DBCompactionStyle::Universal)
+    );
+    assert_eq!(opts.get_log_level(), Some(LogLevel::Warn));
+    assert_eq!(
+        opts.get_wal_recovery_mode(),
+        Some(DBRecoveryMode::AbsoluteConsistency)
+    );
+    assert_eq!(
+        opts.get_access_hint_on_compaction_start(),
+        Some(AccessHint::Sequential)
+    );
+
+    opts.set_compression_per_level(&[DBCompressionType::None, DBCompressionType::Lz4]);
+    opts.set_compression_options(-14, 4, 0, 16 * 1024);
+    opts.set_bottommost_compression_options(-15, 6, 1, 8 * 1024, true);
+    opts.set_bottommost_zstd_max_train_bytes(1 << 19, true);
+    opts.set_max_bytes_for_level_multiplier_additional(&[1, 2, 3, 4, 5]);
+    opts.set_db_log_dir("/tmp/rocksdb-log");
+    opts.set_wal_dir("/tmp/rocksdb-wal");
+    opts.set_db_paths(&[
+        DBPath::new("/tmp/rocksdb-a", 1 << 30).unwrap(),
+        DBPath::new("/tmp/rocksdb-b", 2 << 30).unwrap(),
+    ]);
+    assert_eq!(
+        opts.get_compression_per_level(),
+        vec![Some(DBCompressionType::None), Some(DBCompressionType::Lz4)]
+    );
+    assert_eq!(opts.get_compression_options(), (-14, 4, 0, 16 * 1024));
+    assert_eq!(
+        opts.get_bottommost_compression_options(),
+        Some((-15, 6, 1, 8 * 1024))
+    );
+    assert_eq!(opts.get_bottommost_zstd_max_train_bytes(), Some(1 << 19));
+    assert_eq!(
+        opts.get_max_bytes_for_level_multiplier_additional(),
+        vec![1, 2, 3, 4, 5]
+    );
+    assert_eq!(
+        opts.get_db_log_dir(),
+        Some(PathBuf::from("/tmp/rocksdb-log"))
+    );
+    assert_eq!(opts.get_wal_dir(), Some(PathBuf::from("/tmp/rocksdb-wal")));
+    assert_eq!(
+        opts.get_db_paths(),
+        vec![
+            (PathBuf::from("/tmp/rocksdb-a"), 1 << 30),
+            (PathBuf::from("/tmp/rocksdb-b"), 2 << 30)
+        ]
+    );
+
+    let mut uco = UniversalCompactOptions::default();
+    uco.set_size_ratio(2);
+    uco.set_min_merge_width(3);
+    uco.set_max_merge_width(10);
+    uco.set_max_size_amplification_percent(150);
+    uco.set_compression_size_percent(50);
+    uco.set_stop_style(UniversalCompactionStopStyle::Similar);
+    opts.set_universal_compaction_options(&uco);
+    let uco = opts.get_universal_compaction_options();
+    assert_eq!(uco.get_size_ratio(), 2);
+    assert_eq!(uco.get_min_merge_width(), 3);
+    assert_eq!(uco.get_max_merge_width(), 10);
+    assert_eq!(uco.get_max_size_amplification_percent(), 150);
+    assert_eq!(uco.get_compression_size_percent(), 50);
+    assert_eq!(
+        uco.get_stop_style(),
+        Some(UniversalCompactionStopStyle::Similar)
+    );
+
+    let mut fco = FifoCompactOptions::default();
+    fco.set_max_table_files_size(1 << 20);
+    opts.set_fifo_compaction_options(&fco);
+    assert_eq!(
+        opts.get_fifo_compaction_options()
+            .get_max_table_files_size(),
+        1 << 20
+    );
+}
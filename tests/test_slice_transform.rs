@@ -15,7 +15,7 @@ pub fn test_slice_transform() {
             &k[..3]
         }
 
-        let prefix_extractor = SliceTransform::create("first_three", first_three, None);
+        let prefix_extractor = SliceTransform::create("first_three", Box::new(first_three), None);
 
         let mut opts = Options::default();
         opts.create_if_missing(true);
@@ -66,7 +66,7 @@ fn test_no_in_domain() {
         opts.create_if_missing(true);
         opts.set_prefix_extractor(SliceTransform::create(
             "test slice transform",
-            extract_suffix,
+            Box::new(extract_suffix),
             None,
         ));
         opts.set_memtable_prefix_bloom_ratio(0.1);
@@ -81,3 +81,64 @@ fn test_no_in_domain() {
         );
     }
 }
+
+#[test]
+fn test_closure_slice_transform() {
+    let n = TemporaryDBPath::new();
+    {
+        // The prefix length is only known at runtime.
+        let prefix_len = "tenant:".len();
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_prefix_extractor(SliceTransform::create_with_in_range(
+            "runtime_prefix",
+            Box::new(move |key: &[u8]| &key[..key.len().min(prefix_len)]),
+            Some(Box::new(move |key: &[u8]| key.len() >= prefix_len)),
+            Some(Box::new(move |key: &[u8]| key.len() == prefix_len)),
+        ));
+
+        let db = DB::open(&opts, &n).unwrap();
+        db.put(b"tenant:1", b"a").unwrap();
+        db.put(b"tenant:2", b"b").unwrap();
+        db.put(b"other:3", b"c").unwrap();
+        db.put(b"x", b"d").unwrap();
+
+        let keys: Vec<_> = db.prefix_iterator(b"tenant:").map(|(k, _)| k).collect();
+        assert_eq!(
+            keys,
+            vec![
+                b"tenant:1".to_vec().into_boxed_slice(),
+                b"tenant:2".to_vec().into_boxed_slice()
+            ]
+        );
+        assert_eq!(db.get(b"x").unwrap().unwrap().as_ref(), b"d".as_ref());
+    }
+}
+
+#[test]
+fn test_capped_prefix() {
+    let n = TemporaryDBPath::new();
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.set_prefix_extractor(SliceTransform::create_capped_prefix(3));
+
+        let db = DB::open(&opts, &n).unwrap();
+        db.put(b"aaa1", b"1").unwrap();
+        db.put(b"aaa2", b"2").unwrap();
+        db.put(b"ab", b"3").unwrap();
+        db.put(b"bbb1", b"4").unwrap();
+
+        let values: Vec<_> = db.prefix_iterator(b"aaa").map(|(_, v)| v).collect();
+        assert_eq!(
+            values,
+            vec![
+                b"1".to_vec().into_boxed_slice(),
+                b"2".to_vec().into_boxed_slice()
+            ]
+        );
+        // Keys shorter than the cap are their own prefix.
+        let values: Vec<_> = db.prefix_iterator(b"ab").map(|(_, v)| v).collect();
+        assert_eq!(values, vec![b"3".to_vec().into_boxed_slice()]);
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{prelude::*, Env, SstFileManager, TemporaryDBPath};
+
+#[test]
+fn sst_file_manager_tracks_files() {
+    let path = TemporaryDBPath::new();
+    let manager = SstFileManager::new(&Env::default().unwrap()).unwrap();
+    manager.set_delete_rate_bytes_per_second(1024 * 1024);
+    manager.set_max_trash_db_ratio(0.5);
+    assert_eq!(manager.get_delete_rate_bytes_per_second(), 1024 * 1024);
+    assert!((manager.get_max_trash_db_ratio() - 0.5).abs() < f64::EPSILON);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_sst_file_manager(&manager);
+    let db = DB::open(&opts, &path).unwrap();
+    drop(opts);
+    assert_eq!(manager.get_total_size(), 0);
+
+    db.put(b"k1", vec![1u8; 4096]).unwrap();
+    db.flush().unwrap();
+    assert!(manager.get_total_size() > 0);
+    assert!(!manager.is_max_allowed_space_reached());
+}
+
+#[test]
+fn sst_file_manager_stops_writes_when_space_is_reached() {
+    let path = TemporaryDBPath::new();
+    let manager = SstFileManager::new(&Env::default().unwrap()).unwrap();
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_sst_file_manager(&manager);
+    let db = DB::open(&opts, &path).unwrap();
+
+    db.put(b"k1", vec![1u8; 4096]).unwrap();
+    db.flush().unwrap();
+    manager.set_max_allowed_space_usage(manager.get_total_size());
+    assert!(manager.is_max_allowed_space_reached());
+
+    db.put(b"k2", vec![2u8; 4096]).unwrap();
+    assert!(db.flush().is_err());
+    assert!(db.put(b"k3", b"v3").is_err());
+    assert_eq!(db.get(b"k1").unwrap().unwrap().to_vec(), vec![1u8; 4096]);
+}
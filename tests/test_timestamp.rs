@@ -0,0 +1,211 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{
+    ops::{DeleteWithTs, GetWithTs, PutWithTs},
+    prelude::*,
+    BuiltinComparator, ColumnFamilyDescriptor, IteratorMode, TemporaryDBPath, WriteBatch,
+};
+
+fn ts(t: u64) -> [u8; 8] {
+    t.to_le_bytes()
+}
+
+fn open_with_ts(path: &TemporaryDBPath) -> DB {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_builtin_comparator(BuiltinComparator::BytewiseWithU64Ts);
+    DB::open(&opts, path).unwrap()
+}
+
+#[test]
+fn test_point_in_time_reads() {
+    let path = TemporaryDBPath::new();
+    let db = open_with_ts(&path);
+
+    db.put_with_ts(b"k1", ts(10), b"v10").unwrap();
+    db.put_with_ts(b"k1", ts(20), b"v20").unwrap();
+    db.delete_with_ts(b"k1", ts(30)).unwrap();
+
+    assert!(db.get_with_ts(b"k1", ts(5)).unwrap().is_none());
+    let (value, found_ts) = db.get_with_ts(b"k1", ts(15)).unwrap().unwrap();
+    assert_eq!(&*value, b"v10");
+    assert_eq!(&*found_ts, &ts(10));
+    let (value, found_ts) = db.get_with_ts(b"k1", ts(25)).unwrap().unwrap();
+    assert_eq!(&*value, b"v20");
+    assert_eq!(&*found_ts, &ts(20));
+    assert!(db.get_with_ts(b"k1", ts(30)).unwrap().is_none());
+
+    // Timestamps of the wrong size are rejected.
+    assert!(db.put_with_ts(b"k1", [0u8; 4], b"v").is_err());
+    assert!(db.get_with_ts(b"k1", [0u8; 4]).is_err());
+}
+
+#[test]
+fn test_write_batch_with_ts() {
+    let path = TemporaryDBPath::new();
+    let db = open_with_ts(&path);
+
+    let mut batch = WriteBatch::default();
+    batch.put_with_ts(b"a", ts(1), b"a1").unwrap();
+    batch.put_with_ts(b"b", ts(1), b"b1").unwrap();
+    batch.delete_with_ts(b"a", ts(2)).unwrap();
+    db.write(&batch).unwrap();
+
+    assert_eq!(&*db.get_with_ts(b"a", ts(1)).unwrap().unwrap().0, b"a1");
+    assert!(db.get_with_ts(b"a", ts(2)).unwrap().is_none());
+    assert_eq!(&*db.get_with_ts(b"b", ts(2)).unwrap().unwrap().0, b"b1");
+}
+
+#[test]
+fn test_iterator_with_ts() {
+    let path = TemporaryDBPath::new();
+    let db = open_with_ts(&path);
+
+    db.put_with_ts(b"a", ts(1), b"a1").unwrap();
+    db.put_with_ts(b"a", ts(3), b"a3").unwrap();
+    db.put_with_ts(b"b", ts(2), b"b2").unwrap();
+
+    let mut readopts = ReadOptions::default();
+    readopts.set_timestamp(ts(2));
+    let mut iter = db.get_raw_iter(&readopts);
+    iter.seek_to_first();
+    let mut entries = Vec::new();
+    while iter.valid() {
+        entries.push((
+            iter.key().unwrap().to_vec(),
+            iter.value().unwrap().to_vec(),
+            iter.timestamp().unwrap().to_vec(),
+        ));
+        iter.next();
+    }
+    assert_eq!(
+        entries,
+        vec![
+            (b"a".to_vec(), b"a1".to_vec(), ts(1).to_vec()),
+            (b"b".to_vec(), b"b2".to_vec(), ts(2).to_vec()),
+        ]
+    );
+
+    let cloned = readopts.clone();
+    let mut iter = db.get_raw_iter(&cloned);
+    iter.seek(b"a");
+    assert_eq!(iter.value(), Some(&b"a1"[..]));
+}
+
+#[test]
+fn test_iterator_with_iter_start_ts() {
+    let path = TemporaryDBPath::new();
+    let db = open_with_ts(&path);
+
+    db.put_with_ts(b"a", ts(1), b"a1").unwrap();
+    db.put_with_ts(b"a", ts(2), b"a2").unwrap();
+    db.put_with_ts(b"a", ts(3), b"a3").unwrap();
+    db.put_with_ts(b"b", ts(2), b"b2").unwrap();
+
+    let mut readopts = ReadOptions::default();
+    readopts.set_timestamp(ts(3));
+    readopts.set_iter_start_ts(ts(2));
+    let mut iter = db.iterator_opt(IteratorMode::Start, &readopts);
+    assert_eq!(iter.timestamp(), None);
+    let mut entries = Vec::new();
+    while let Some((key, value)) = iter.next() {
+        // Keys are internal keys: the user key, the timestamp, then 8 bytes
+        // of sequence number and type.
+        let user_key = key[..key.len() - 16].to_vec();
+        assert_eq!(
+            &key[key.len() - 16..key.len() - 8],
+            iter.timestamp().unwrap()
+        );
+        entries.push((user_key, value.to_vec(), iter.timestamp().unwrap().to_vec()));
+    }
+    // Every version in [2, 3] is returned, newest first.
+    assert_eq!(
+        entries,
+        vec![
+            (b"a".to_vec(), b"a3".to_vec(), ts(3).to_vec()),
+            (b"a".to_vec(), b"a2".to_vec(), ts(2).to_vec()),
+            (b"b".to_vec(), b"b2".to_vec(), ts(2).to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn test_iterator_outlives_read_options_with_ts() {
+    let path = TemporaryDBPath::new();
+    let db = open_with_ts(&path);
+
+    db.put_with_ts(b"a", ts(1), b"a1").unwrap();
+    db.put_with_ts(b"a", ts(3), b"a3").unwrap();
+
+    let mut readopts = ReadOptions::default();
+    readopts.set_timestamp(ts(2));
+    let mut iter = db.get_raw_iter(&readopts);
+    // setting another timestamp doesn't change the one of the iterator
+    readopts.set_timestamp(ts(3));
+    drop(readopts);
+
+    iter.seek_to_first();
+    assert_eq!(iter.value(), Some(&b"a1"[..]));
+    assert_eq!(iter.timestamp(), Some(&ts(1)[..]));
+    iter.next();
+    assert!(!iter.valid());
+
+    let mut readopts = ReadOptions::default();
+    readopts.set_timestamp(ts(3));
+    readopts.set_iter_start_ts(ts(1));
+    let iter = db.iterator_opt(IteratorMode::Start, &readopts);
+    drop(readopts);
+    assert_eq!(iter.count(), 2);
+}
+
+#[test]
+fn test_write_batch_checks_ts_size() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let mut cf_opts = Options::default();
+    cf_opts.set_builtin_comparator(BuiltinComparator::BytewiseWithU64Ts);
+    let cf = ColumnFamilyDescriptor::new("versioned", cf_opts);
+    let db = DB::open_cf_descriptors(&opts, &path, vec![cf]).unwrap();
+    let versioned = db.cf_handle("versioned").unwrap();
+
+    let mut batch = WriteBatch::default();
+    batch.put_with_ts_cf(versioned, b"a", ts(1), b"a1").unwrap();
+    assert!(batch
+        .put_with_ts_cf(versioned, b"a", [0u8; 4], b"a2")
+        .is_err());
+    assert!(batch.delete_with_ts_cf(versioned, b"a", [0u8; 9]).is_err());
+    assert_eq!(batch.len(), 1);
+    db.write(&batch).unwrap();
+    let mut readopts = ReadOptions::default();
+    readopts.set_timestamp(ts(1));
+    let mut iter = db.get_raw_iter_cf(versioned, &readopts).unwrap();
+    iter.seek_to_first();
+    assert_eq!(iter.value(), Some(&b"a1"[..]));
+
+    // the default column family of this database has no timestamps
+    let mut batch = WriteBatch::default();
+    batch.put_with_ts(b"b", ts(1), b"b1").unwrap();
+    assert!(batch.delete_with_ts(b"b", [0u8; 4]).is_err());
+    assert!(db.write(&batch).is_err());
+    assert!(db.get(b"b").unwrap().is_none());
+    batch.clear().unwrap();
+    batch.put(b"b", b"b1").unwrap();
+    db.write(&batch).unwrap();
+    assert_eq!(db.get(b"b").unwrap().unwrap().as_ref(), b"b1");
+}
@@ -1,8 +1,10 @@
 extern crate ckb_rocksdb as rocksdb;
 
 use crate::rocksdb::{
-    prelude::*, MergeOperands, Options, TemporaryDBPath, TransactionDB, TransactionDBOptions,
-    TransactionOptions, WriteOptions,
+    backup::{BackupEngine, BackupEngineOptions, RestoreOptions},
+    prelude::*,
+    ColumnFamilyDescriptor, MergeOperands, Options, SstFileWriter, TemporaryDBPath, TransactionDB,
+    TransactionDBOptions, TransactionOptions, TxnDBWritePolicy, WriteOptions, DB,
 };
 
 #[test]
@@ -287,3 +289,294 @@ pub fn test_transaction_merge() {
         trans2.commit().unwrap();
     }
 }
+
+#[test]
+pub fn test_transaction_db_open_cf() {
+    let path = TemporaryDBPath::new();
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db = DB::open_cf(&opts, &path, ["cf1", "cf2"]).unwrap();
+        db.put_cf(db.cf_handle("cf1").unwrap(), b"k1", b"v1")
+            .unwrap();
+    }
+    {
+        let mut db = TransactionDB::open_cf(&Options::default(), &path, ["cf1", "cf2"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        assert_eq!(db.get_cf(cf1, b"k1").unwrap().unwrap().as_ref(), b"v1");
+
+        {
+            let trans = db.transaction_default();
+            trans.put_cf(cf1, b"k2", b"v2").unwrap();
+            trans
+                .put_cf(db.cf_handle("cf2").unwrap(), b"k3", b"v3")
+                .unwrap();
+            trans.commit().unwrap();
+        }
+
+        db.drop_cf("cf2").unwrap();
+        assert!(db.cf_handle("cf2").is_none());
+        assert!(db.drop_cf("cf2").is_err());
+    }
+    {
+        let cfs = DB::list_cf(&Options::default(), &path).unwrap();
+        assert_eq!(cfs, ["default", "cf1"]);
+
+        let db = TransactionDB::open_cf_descriptors(
+            &Options::default(),
+            &path,
+            vec![ColumnFamilyDescriptor::new("cf1", Options::default())],
+        )
+        .unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        assert_eq!(db.get_cf(cf1, b"k2").unwrap().unwrap().as_ref(), b"v2");
+    }
+}
+
+#[test]
+pub fn test_transaction_db_base_db_ops() {
+    let path = TemporaryDBPath::new();
+    let restore_path = TemporaryDBPath::new();
+    let backup_path = TemporaryDBPath::new();
+    let sst_dir = tempfile::tempdir().unwrap();
+    let sst_path = sst_dir.path().join("k2.sst");
+    {
+        let db = TransactionDB::open_default(&path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+        db.compact_range(None::<&[u8]>, None::<&[u8]>);
+        assert_eq!(
+            db.property_int_value("rocksdb.num-files-at-level0")
+                .unwrap(),
+            Some(0)
+        );
+        db.set_options(&[("disable_auto_compactions", "true")])
+            .unwrap();
+        assert!(db
+            .get_options_string()
+            .unwrap()
+            .contains("disable_auto_compactions=true"));
+
+        let opts = Options::default();
+        let mut writer = SstFileWriter::create(&opts);
+        writer.open(&sst_path).unwrap();
+        writer.put(b"k2", b"v2").unwrap();
+        writer.finish().unwrap();
+        db.ingest_external_file(vec![&sst_path]).unwrap();
+        assert_eq!(db.get(b"k2").unwrap().unwrap().as_ref(), b"v2");
+
+        // writes outside of transactions still wait for their locks
+        let trans = db.transaction_default();
+        trans.get_for_update(b"k1").unwrap();
+        assert!(db.put(b"k1", b"v3").is_err());
+        trans.commit().unwrap();
+
+        let mut backup_engine =
+            BackupEngine::open(&BackupEngineOptions::default(), &backup_path).unwrap();
+        backup_engine.create_new_backup_flush(&db, true).unwrap();
+        backup_engine
+            .restore_from_latest_backup(&restore_path, &restore_path, &RestoreOptions::default())
+            .unwrap();
+    }
+
+    let db = TransactionDB::open_default(&restore_path).unwrap();
+    assert_eq!(db.get(b"k1").unwrap().unwrap().as_ref(), b"v1");
+    assert_eq!(db.get(b"k2").unwrap().unwrap().as_ref(), b"v2");
+}
+
+#[test]
+pub fn test_transaction_two_phase_commit() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_allow_2pc(true);
+    {
+        let db = TransactionDB::open(&opts, &path).unwrap();
+
+        let trans = db.transaction_default();
+        assert_eq!(trans.get_name(), None);
+        trans.put(b"k1", b"v1").unwrap();
+        assert!(trans.prepare().is_err());
+        trans.set_name("xid1").unwrap();
+        assert_eq!(trans.get_name().as_deref(), Some("xid1"));
+        trans.prepare().unwrap();
+
+        let trans2 = db.transaction_default();
+        assert!(trans2.set_name("xid1").is_err());
+        trans2.set_name("xid2").unwrap();
+        trans2.put(b"k2", b"v2").unwrap();
+        trans2.prepare().unwrap();
+
+        assert!(db.get(b"k1").unwrap().is_none());
+    }
+    {
+        let mut db = TransactionDB::open(&opts, &path).unwrap();
+        let mut prepared = db.prepared_transactions();
+        prepared.sort_by_key(|trans| trans.get_name());
+        let names: Vec<_> = prepared.iter().map(|trans| trans.get_name()).collect();
+        assert_eq!(names, [Some("xid1".to_owned()), Some("xid2".to_owned())]);
+        prepared[0].commit().unwrap();
+        prepared[1].rollback().unwrap();
+        drop(prepared);
+
+        assert!(db.prepared_transactions().is_empty());
+        assert_eq!(db.get(b"k1").unwrap().unwrap().as_ref(), b"v1");
+        assert!(db.get(b"k2").unwrap().is_none());
+    }
+    {
+        let mut db = TransactionDB::open(&opts, &path).unwrap();
+        assert!(db.prepared_transactions().is_empty());
+        assert_eq!(db.get(b"k1").unwrap().unwrap().as_ref(), b"v1");
+    }
+}
+
+#[test]
+pub fn test_transaction_db_write_policy() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_allow_2pc(true);
+    assert!(opts.get_allow_2pc());
+
+    let txn_db_opts = TransactionDBOptions::default();
+    assert_eq!(
+        txn_db_opts.get_write_policy(),
+        Some(TxnDBWritePolicy::WriteCommitted)
+    );
+    txn_db_opts.set_write_policy(TxnDBWritePolicy::WritePrepared);
+    assert_eq!(
+        txn_db_opts.get_write_policy(),
+        Some(TxnDBWritePolicy::WritePrepared)
+    );
+
+    let db = TransactionDB::open_with_descriptor(&opts, &path, txn_db_opts).unwrap();
+    let trans = db.transaction_default();
+    trans.set_name("xid").unwrap();
+    trans.put(b"k", b"v").unwrap();
+    trans.prepare().unwrap();
+    // written once prepared, but only visible once committed
+    assert!(db.get(b"k").unwrap().is_none());
+    trans.commit().unwrap();
+    assert_eq!(db.get(b"k").unwrap().unwrap().as_ref(), b"v");
+}
+
+fn active_memtable_entries(db: &TransactionDB) -> u64 {
+    db.property_int_value("rocksdb.num-entries-active-mem-table")
+        .unwrap()
+        .unwrap()
+}
+
+// Writes a transaction of 1000 keys and returns the number of them found in
+// the memtable before it is committed.
+fn written_before_commit(
+    policy: TxnDBWritePolicy,
+    txn_db_opts: TransactionDBOptions,
+    txn_opts: &TransactionOptions,
+) -> u64 {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    txn_db_opts.set_write_policy(policy);
+    let db = TransactionDB::open_with_descriptor(&opts, &path, txn_db_opts).unwrap();
+
+    let trans = db.transaction(&WriteOptions::default(), txn_opts);
+    trans.set_name("reindex").unwrap();
+    for i in 0..1000u32 {
+        trans
+            .put(format!("key{:04}", i), format!("value{:04}", i))
+            .unwrap();
+    }
+    let written = active_memtable_entries(&db);
+    // whatever was written is not visible until committed
+    assert!(db.get(b"key0000").unwrap().is_none());
+    trans.commit().unwrap();
+    assert_eq!(db.get(b"key0999").unwrap().unwrap().as_ref(), b"value0999");
+    written
+}
+
+#[test]
+pub fn test_transaction_db_write_unprepared_flushes_large_batches() {
+    // the whole transaction is kept in memory until committed
+    let written = written_before_commit(
+        TxnDBWritePolicy::WriteCommitted,
+        TransactionDBOptions::default(),
+        &TransactionOptions::default(),
+    );
+    assert_eq!(written, 0);
+
+    // without a threshold nothing is flushed either
+    let written = written_before_commit(
+        TxnDBWritePolicy::WriteUnprepared,
+        TransactionDBOptions::default(),
+        &TransactionOptions::default(),
+    );
+    assert_eq!(written, 0);
+
+    let txn_db_opts = TransactionDBOptions::default();
+    assert_eq!(txn_db_opts.get_default_write_batch_flush_threshold(), 0);
+    txn_db_opts.set_default_write_batch_flush_threshold(1024);
+    assert_eq!(txn_db_opts.get_default_write_batch_flush_threshold(), 1024);
+    let written = written_before_commit(
+        TxnDBWritePolicy::WriteUnprepared,
+        txn_db_opts,
+        &TransactionOptions::default(),
+    );
+    assert!(written > 900, "{}", written);
+
+    // the threshold of the transaction overrides the default one
+    let txn_opts = TransactionOptions::default();
+    assert_eq!(txn_opts.get_write_batch_flush_threshold(), -1);
+    txn_opts.set_write_batch_flush_threshold(1024);
+    assert_eq!(txn_opts.get_write_batch_flush_threshold(), 1024);
+    let written = written_before_commit(
+        TxnDBWritePolicy::WriteUnprepared,
+        TransactionDBOptions::default(),
+        &txn_opts,
+    );
+    assert!(written > 900, "{}", written);
+}
+
+#[test]
+pub fn test_transaction_db_write_unprepared_rollback() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let txn_db_opts = TransactionDBOptions::default();
+    txn_db_opts.set_write_policy(TxnDBWritePolicy::WriteUnprepared);
+    txn_db_opts.set_default_write_batch_flush_threshold(1);
+    let db = TransactionDB::open_with_descriptor(&opts, &path, txn_db_opts).unwrap();
+
+    db.put(b"k1", b"v0").unwrap();
+    let trans = db.transaction_default();
+    trans.set_name("xid").unwrap();
+    trans.put(b"k1", b"v1").unwrap();
+    trans.put(b"k2", b"v2").unwrap();
+    assert!(active_memtable_entries(&db) > 1);
+    assert_eq!(trans.get(b"k2").unwrap().unwrap().as_ref(), b"v2");
+    trans.rollback().unwrap();
+    drop(trans);
+
+    assert_eq!(db.get(b"k1").unwrap().unwrap().as_ref(), b"v0");
+    assert!(db.get(b"k2").unwrap().is_none());
+}
+
+#[test]
+pub fn test_two_write_queues() {
+    let path = TemporaryDBPath::new();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_allow_2pc(true);
+    opts.set_two_write_queues(true);
+    assert!(opts.get_two_write_queues());
+    let txn_db_opts = TransactionDBOptions::default();
+    txn_db_opts.set_write_policy(TxnDBWritePolicy::WritePrepared);
+    let db = TransactionDB::open_with_descriptor(&opts, &path, txn_db_opts).unwrap();
+
+    let trans = db.transaction_default();
+    trans.set_name("xid").unwrap();
+    trans.put(b"k", b"v").unwrap();
+    trans.prepare().unwrap();
+    trans.commit().unwrap();
+    assert_eq!(db.get(b"k").unwrap().unwrap().as_ref(), b"v");
+}
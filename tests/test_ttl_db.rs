@@ -1,7 +1,12 @@
 extern crate ckb_rocksdb as rocksdb;
 
 use crate::rocksdb::{
-    prelude::*, ColumnFamilyDescriptor, DBWithTTL, TTLOpenDescriptor, TemporaryDBPath,
+    prelude::*, ColumnFamilyDescriptor, CompactionDecision, DBWithTTL, Env, TTLOpenDescriptor,
+    TemporaryDBPath,
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
 };
 
 #[test]
@@ -84,3 +89,83 @@ fn open_ttl_db_cf_with_descriptor_by_columns() {
         assert!(r.unwrap().unwrap().to_utf8().unwrap() == "v1111");
     }
 }
+
+#[test]
+fn ttl_db_expiry_with_mock_clock() {
+    let path = TemporaryDBPath::new();
+    let env = Env::mock_clock_env().unwrap();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(&env);
+
+    let db =
+        DBWithTTL::open_with_descriptor(&opts, &path, TTLOpenDescriptor::by_default(60)).unwrap();
+    db.put(b"k1", b"v1").unwrap();
+    db.compact_range::<&[u8], &[u8]>(None, None);
+    assert!(db.get(b"k1").unwrap().is_some());
+
+    env.advance(Duration::from_secs(120)).unwrap();
+    db.put(b"k2", b"v2").unwrap();
+    db.compact_range::<&[u8], &[u8]>(None, None);
+    assert!(db.get(b"k1").unwrap().is_none());
+    assert!(db.get(b"k2").unwrap().is_some());
+}
+
+#[test]
+fn mock_clock_stands_still() {
+    let path = TemporaryDBPath::new();
+    let env = Env::mock_clock_env().unwrap();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(&env);
+
+    let db =
+        DBWithTTL::open_with_descriptor(&opts, &path, TTLOpenDescriptor::by_default(1)).unwrap();
+    db.put(b"k1", b"v1").unwrap();
+    thread::sleep(Duration::from_millis(2100));
+    db.compact_range::<&[u8], &[u8]>(None, None);
+    assert!(db.get(b"k1").unwrap().is_some());
+
+    env.advance(Duration::from_secs(2)).unwrap();
+    db.compact_range::<&[u8], &[u8]>(None, None);
+    assert!(db.get(b"k1").unwrap().is_none());
+}
+
+#[test]
+fn periodic_compaction_with_mock_clock() {
+    let path = TemporaryDBPath::new();
+    let env = Env::mock_clock_env().unwrap();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(&env);
+    opts.set_periodic_compaction_seconds(3600);
+    opts.set_compaction_filter("expired", |_, key: &[u8], _: &[u8]| {
+        if key.starts_with(b"expired") {
+            CompactionDecision::Remove
+        } else {
+            CompactionDecision::Keep
+        }
+    });
+
+    let db = DB::open(&opts, &path).unwrap();
+    db.put(b"expired", b"v1").unwrap();
+    db.flush().unwrap();
+
+    // a new file makes RocksDB look for files old enough to compact
+    env.advance(Duration::from_secs(7200)).unwrap();
+    db.put(b"k2", b"v2").unwrap();
+    db.flush().unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while db.get(b"expired").unwrap().is_some() {
+        assert!(Instant::now() < deadline, "the old file was not compacted");
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(db.get(b"k2").unwrap().is_some());
+}
+
+#[test]
+fn advance_requires_mock_clock_env() {
+    let env = Env::default().unwrap();
+    assert!(env.advance(Duration::from_secs(1)).is_err());
+}
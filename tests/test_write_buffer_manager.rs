@@ -0,0 +1,62 @@
+// Copyright 2020 Nervos Core Dev
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+extern crate ckb_rocksdb as rocksdb;
+
+use crate::rocksdb::{prelude::*, Cache, TemporaryDBPath, TransactionDB, WriteBufferManager};
+
+#[test]
+fn write_buffer_manager_is_shared_between_dbs() {
+    let path_a = TemporaryDBPath::new();
+    let path_b = TemporaryDBPath::new();
+    let manager = WriteBufferManager::new(64 * 1024 * 1024, false).unwrap();
+    assert!(manager.enabled());
+    assert!(!manager.cost_to_cache());
+    assert_eq!(manager.get_buffer_size(), 64 * 1024 * 1024);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_write_buffer_manager(&manager);
+    let db = DB::open(&opts, &path_a).unwrap();
+    let txn_db = TransactionDB::open(&opts, &path_b).unwrap();
+    drop(opts);
+
+    db.put(b"k1", vec![1u8; 4096]).unwrap();
+    let usage = manager.get_usage();
+    assert!(usage > 0);
+    txn_db.put(b"k1", vec![1u8; 4096]).unwrap();
+    assert!(manager.get_usage() > usage);
+    assert!(manager.get_mutable_memtable_usage() > 0);
+
+    manager.set_buffer_size(32 * 1024 * 1024);
+    assert_eq!(manager.get_buffer_size(), 32 * 1024 * 1024);
+}
+
+#[test]
+fn write_buffer_manager_charges_cache() {
+    let path = TemporaryDBPath::new();
+    let cache = Cache::new_lru_cache(64 * 1024 * 1024).unwrap();
+    let manager = WriteBufferManager::new_with_cache(0, &cache, false).unwrap();
+    assert!(!manager.enabled());
+    assert!(manager.cost_to_cache());
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_write_buffer_manager(&manager);
+    let db = DB::open(&opts, &path).unwrap();
+    db.put(b"k1", vec![1u8; 4096]).unwrap();
+
+    assert!(manager.get_dummy_entries_in_cache_usage() > 0);
+    assert!(cache.get_usage() >= manager.get_dummy_entries_in_cache_usage());
+}